@@ -1,17 +1,34 @@
 use ares::checkers::athena::Athena;
 use ares::checkers::checker_type::{Check, Checker};
-use ares::checkers::CheckerTypes;
-use ares::decoders::base64_decoder::Base64Decoder;
-use ares::decoders::interface::{Crack, Decoder};
+use ares::checkers::{checkers_by_cost, CheckerTypes};
+use ares::config::default_config;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
+/// Plaintext which the first checkers identify, plaintext only the English Checker
+/// identifies, and gibberish which goes through every checker
+const TEXTS: &[(&str, &str)] = &[
+    ("ip address", "192.168.0.1"),
+    ("english", "The quick brown fox jumps over the lazy dog"),
+    ("gibberish", "vjkrerkdnxhrfjekfdjexk"),
+];
+
 pub fn criterion_benchmark(c: &mut Criterion) {
-    let decode_base64 = Decoder::<Base64Decoder>::new();
-    let athena_checker = Checker::<Athena>::new();
-    let checker = CheckerTypes::CheckAthena(athena_checker);
-    c.bench_function("base64 successful decoding", |b| {
-        b.iter(|| decode_base64.crack(black_box("aGVsbG8gd29ybGQ="), &checker))
-    });
+    let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+    let mut group = c.benchmark_group("athena");
+    for (name, text) in TEXTS {
+        group.bench_function(*name, |b| b.iter(|| athena.check(black_box(text))));
+    }
+    group.finish();
+
+    // Each checker Athena runs, in the order it runs them, on text none of them identify
+    let mut group = c.benchmark_group("checkers");
+    for checker in checkers_by_cost(&default_config()) {
+        let name = checker.check("").checker_name;
+        group.bench_function(name, |b| {
+            b.iter(|| checker.check(black_box("vjkrerkdnxhrfjekfdjexk")))
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,84 @@
+use ares::checkers::checker_type::{Check, Checker};
+use ares::checkers::default_checker::DefaultChecker;
+use ares::checkers::CheckerTypes;
+use ares::decoders::DECODER_MAP;
+use base64::{engine::general_purpose, Engine};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// A text each decoder can decode, taken from the decoder's tests
+const SAMPLES: &[(&str, &str)] = &[
+    ("A1Z26", "8 5 12 12 15"),
+    ("Atbash", "svool dliow"),
+    ("Base32", "NBSWY3DPEB3W64TMMQ======"),
+    ("Base58 Bitcoin", "StV1DL6CwTryKyV"),
+    ("Base58 Flickr", "rTu1dk6cWsRYjYu"),
+    ("Base58 Monero", "StV1DL6CwTryKyV"),
+    ("Base58 Ripple", "StVrDLaUATiyKyV"),
+    ("Base64", "aGVsbG8gd29ybGQ="),
+    (
+        "Base64 URL",
+        "aHR0cHM6Ly93d3cuZ29vZ2xlLmNvbS8_ZXhhbXBsZT10ZXN0",
+    ),
+    ("Base65536", "𒅓鹨𖡮𒀠啦ꍢ顡啫𓍱𓁡𠁴唬𓍪鱤啥𖥭𔐠𔕯ᔮ"),
+    ("Base91", "TPwJh>Io2Tv!lE"),
+    (
+        "Binary",
+        "0110100001100101011011000110110001101111001000000111011101101111011100100110110001100100",
+    ),
+    ("Caesar Cipher", "fyyfhp"),
+    (
+        "Citrix Ctx1",
+        "MNGIKIANMEGBKIANMHGCOHECJADFPPFKINCIOBEEIFCA",
+    ),
+    ("Hexadecimal", "68656c6c6f20776f726c64"),
+    (
+        "Morse Code",
+        ".---- ----. ..--- .-.-.- .---- -.... ---.. .-.-.- ----- .-.-.- .----",
+    ),
+    ("Railfence", "xcz n akt,emiol r gywShfbqajd op uuv"),
+    ("Reverse", "dlrow olleh"),
+    ("URL", "hello%20world%21"),
+];
+
+/// Times each decoder on its own. The Default Checker never identifies anything,
+/// so the time doesn't include the checkers and decoders which try every key keep going.
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let checker = CheckerTypes::CheckDefault(Checker::<DefaultChecker>::new());
+    let mut group = c.benchmark_group("decoders");
+    for (name, sample) in SAMPLES {
+        let Some(decoder) = DECODER_MAP.get(name) else {
+            continue;
+        };
+        group.bench_function(*name, |b| {
+            b.iter(|| decoder.crack(black_box(sample), &checker))
+        });
+    }
+    group.finish();
+
+    // The decoders which run on nearly every text, on a megabyte of it
+    let plaintext = "hello world ".repeat(1024 * 1024 / 12);
+    let large_samples = [
+        ("Base64", general_purpose::STANDARD.encode(&plaintext)),
+        (
+            "Hexadecimal",
+            plaintext
+                .bytes()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+        ),
+    ];
+    let mut group = c.benchmark_group("large decoders");
+    group.sample_size(10);
+    for (name, sample) in &large_samples {
+        let Some(decoder) = DECODER_MAP.get(name) else {
+            continue;
+        };
+        group.bench_function(*name, |b| {
+            b.iter(|| decoder.crack(black_box(sample), &checker))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+use ares::config::Config;
+use ares::perform_cracking;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Encoded texts like the ones people give Ares, from one layer of encoding to several
+const INPUTS: &[(&str, &str)] = &[
+    ("base64", "aGVsbG8gdGhlcmUgZ2VuZXJhbA=="),
+    (
+        "base64 twice",
+        "VFdWbGRDQnRaU0JoZENCMGFHVWdiMnhrSUc5aGF5QjBjbVZsSUdGMElHMXBaRzVwWjJoMA==",
+    ),
+    (
+        "hexadecimal of base64",
+        "5647686c4948463161574e7249474a796233647549475a76654342716457317763794276646d56794948526f5a53427359587035494752765a773d3d",
+    ),
+    (
+        "base64 of rot13",
+        "R3VyIGNuZmZqYmVxIHNiZSBndXIgZnJlaXJlIHZmIGpldmdncmEgYmEgZ3VyIGp1dmdyb2JuZXE=",
+    ),
+];
+
+/// Times whole runs of Ares, from checking the input to finding the plaintext
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("end to end");
+    // Each run takes much longer than a decoder, so we take fewer samples
+    group.sample_size(10);
+    for (name, input) in INPUTS {
+        group.bench_function(*name, |b| {
+            b.iter(|| perform_cracking(black_box(input), Config::default()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,17 @@
+//! Generates the gRPC service in `proto/ares.proto` when Ares is built with the `grpc` feature.
+//! protoc comes from `protoc-bin-vendored`, so building doesn't need it installed.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/ares.proto");
+        let protoc =
+            protoc_bin_vendored::protoc_bin_path().expect("protoc is vendored for this platform");
+        // prost-build runs the protoc in $PROTOC
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos(&["proto/ares.proto"], &["proto"])
+            .expect("proto/ares.proto compiles");
+    }
+}
@@ -1,4 +1,7 @@
+use std::time::Duration;
+
 use super::checker_type::Checker;
+use super::lemmeknow_checker::LemmeKnowMatch;
 
 /// The checkerResult struct is used to store the results of a checker.
 pub struct CheckResult {
@@ -16,6 +19,14 @@ pub struct CheckResult {
     pub checker_description: &'static str,
     /// Link to more info about checker
     pub link: &'static str,
+    /// How confident the checker is that the text is plaintext, from 0.0 to 1.0.
+    /// 0.0 if the text was not identified.
+    pub confidence: f32,
+    /// How long the checker took to check the text.
+    /// Set by [`CheckerTypes::check`](super::CheckerTypes::check), which logs it with `-vv`
+    pub time_taken: Duration,
+    /// What LemmeKnow identified the text as, if it was the LemmeKnow Checker which identified it
+    pub lemmeknow: Option<LemmeKnowMatch>,
 }
 
 /// To save time we have a default
@@ -33,6 +44,9 @@ impl CheckResult {
             checker_description: checker_used.description,
             description: "".to_string(),
             link: checker_used.link,
+            confidence: 0.0,
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
         }
     }
 }
@@ -1,7 +1,10 @@
 /// Checker_type is a type used to define checkers
 /// This means that we can standardise the way we check for plaintext
 use crate::checkers::checker_result::CheckResult;
+use crate::config::Config;
+use crate::searchers::context::SearchContext;
 use lemmeknow::Identifier;
+use std::sync::Arc;
 
 /// Every checker is of type CheckerType
 /// This will let us pick & choose which checkers to use
@@ -25,6 +28,13 @@ pub struct Checker<Type> {
     pub popularity: f32,
     /// lemmeknow config object
     pub lemmeknow_config: Identifier,
+    /// The config of the run this checker is used in, like the regex to look for
+    pub config: Arc<Config>,
+    /// The decoders used to get to the text being checked, like "Base64 → Reverse".
+    /// The human checker shows this so the user knows where the text came from.
+    pub decoding_path: String,
+    /// The state of the run this checker is used in, like whether its timer is paused
+    pub(crate) context: Arc<SearchContext>,
     /// https://doc.rust-lang.org/std/marker/struct.PhantomData.html
     /// Let's us save memory by telling the compiler that our type
     /// acts like a type <T> even though it doesn't.
@@ -33,6 +43,63 @@ pub struct Checker<Type> {
     pub _phantom: std::marker::PhantomData<Type>,
 }
 
+impl<Type> Checker<Type> {
+    /// Uses the config of this run instead of the default one
+    pub fn with_config(mut self, config: Arc<Config>) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Uses the state of this run instead of a context of its own
+    pub(crate) fn with_context(mut self, context: Arc<SearchContext>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Sets the decoders used to get to the texts this checker will check
+    pub fn with_decoding_path(mut self, decoding_path: String) -> Self {
+        self.decoding_path = decoding_path;
+        self
+    }
+
+    /// Is this checker turned on in the config?
+    /// It is off if `enabled_checkers` is not empty and doesn't name it,
+    /// or if `disabled_checkers` names it. Checkers can be named by their name
+    /// with or without "Checker" ("LemmeKnow") or by one of their tags ("english").
+    pub fn is_enabled(&self) -> bool {
+        (self.config.enabled_checkers.is_empty() || self.named_in(&self.config.enabled_checkers))
+            && !self.named_in(&self.config.disabled_checkers)
+    }
+
+    /// Returns true if the checker's name or one of its tags is in the list, case insensitively
+    pub(crate) fn named_in(&self, names_or_tags: &[String]) -> bool {
+        names_or_tags.iter().any(|wanted| {
+            self.has_name(wanted) || self.tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted))
+        })
+    }
+
+    /// Returns true if this is the checker's name, with or without "Checker", case insensitively
+    pub(crate) fn has_name(&self, wanted: &str) -> bool {
+        let short_name = self.name.trim_end_matches(" Checker");
+        self.name.eq_ignore_ascii_case(wanted) || short_name.eq_ignore_ascii_case(wanted)
+    }
+}
+
+impl<Type> Checker<Type>
+where
+    Checker<Type>: Check,
+{
+    /// Checks the text if the checker is turned on, see [`Checker::is_enabled`].
+    /// A checker which is turned off never identifies anything.
+    pub fn check_if_enabled(&self, text: &str) -> CheckResult {
+        if self.is_enabled() {
+            self.check(text)
+        } else {
+            CheckResult::new(self)
+        }
+    }
+}
+
 /// Every checker must implement this trait
 /// Which checks the given text to see if its plaintext
 /// and returns CheckResult, which is our results object.
@@ -44,3 +111,45 @@ pub trait Check {
     /// Checks the given text to see if its plaintext
     fn check(&self, text: &str) -> CheckResult;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{Check, Checker};
+    use crate::checkers::{english::EnglishChecker, lemmeknow_checker::LemmeKnow};
+    use crate::config::Config;
+
+    #[test]
+    fn checkers_are_enabled_by_default() {
+        assert!(Checker::<LemmeKnow>::new().is_enabled());
+    }
+
+    #[test]
+    fn disabled_checkers_are_named_by_name_or_tag() {
+        let config = Arc::new(Config {
+            disabled_checkers: vec!["lemmeknow".to_string()],
+            ..Default::default()
+        });
+        let lemmeknow = Checker::<LemmeKnow>::new().with_config(config.clone());
+        assert!(!lemmeknow.is_enabled());
+        assert!(!lemmeknow.check_if_enabled("192.168.0.1").is_identified);
+        assert!(Checker::<EnglishChecker>::new()
+            .with_config(config)
+            .is_enabled());
+    }
+
+    #[test]
+    fn only_enabled_checkers_are_used() {
+        let config = Arc::new(Config {
+            enabled_checkers: vec!["English Checker".to_string()],
+            ..Default::default()
+        });
+        assert!(!Checker::<LemmeKnow>::new()
+            .with_config(config.clone())
+            .is_enabled());
+        assert!(Checker::<EnglishChecker>::new()
+            .with_config(config)
+            .is_enabled());
+    }
+}
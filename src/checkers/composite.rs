@@ -0,0 +1,485 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use lemmeknow::Identifier;
+use log::trace;
+
+use super::{
+    checker_type::{Check, Checker},
+    english::EnglishChecker,
+    file_signature::FileSignatureChecker,
+    flag_checker::FlagChecker,
+    lemmeknow_checker::LemmeKnow,
+    regex_checker::RegexChecker,
+    secrets::SecretsChecker,
+    sensitive_data::SensitiveDataChecker,
+    statistical::StatisticalChecker,
+    structured_data::StructuredDataChecker,
+    wordlist::WordlistChecker,
+    CheckerTypes,
+};
+use crate::{
+    checkers::checker_result::CheckResult,
+    config::{default_config, Config},
+    searchers::context::SearchContext,
+};
+
+/// The Composite Checker decides if the text is plaintext with an [`ExitCondition`],
+/// like "regex || wordlist" or "flag && english", instead of stopping at the first checker which says yes.
+pub struct CompositeChecker;
+
+/// A boolean expression of checkers, like `regex && english` or `(wordlist || flag) && !lemmeknow`.
+/// Checkers are named by their name or one of their tags, like `--only-checkers`.
+/// `!` binds tightest, then `&&`, then `||`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExitCondition {
+    /// True if any checker with this name or tag identifies the text
+    Checker(String),
+    /// True if the condition is false
+    Not(Box<ExitCondition>),
+    /// True if both conditions are true
+    And(Box<ExitCondition>, Box<ExitCondition>),
+    /// True if either condition is true
+    Or(Box<ExitCondition>, Box<ExitCondition>),
+}
+
+impl ExitCondition {
+    /// Parses an exit condition like `regex || wordlist`
+    ///
+    /// # Errors
+    ///
+    /// Returns why if the expression isn't valid, like `regex &&` or `(english`
+    pub fn parse(expression: &str) -> Result<ExitCondition, String> {
+        let tokens = tokenise(expression)?;
+        let mut parser = Parser { tokens, next: 0 };
+        let condition = parser.or()?;
+        match parser.tokens.get(parser.next) {
+            None => Ok(condition),
+            Some(token) => Err(format!("did not expect {token}")),
+        }
+    }
+
+    /// Returns the first checker name in the condition which doesn't name any checker
+    pub fn unknown_checker(&self, config: &Arc<Config>) -> Option<&str> {
+        match self {
+            ExitCondition::Checker(name) => checkers_named(name, config, &Arc::default())
+                .is_empty()
+                .then_some(name),
+            ExitCondition::Not(condition) => condition.unknown_checker(config),
+            ExitCondition::And(left, right) | ExitCondition::Or(left, right) => left
+                .unknown_checker(config)
+                .or_else(|| right.unknown_checker(config)),
+        }
+    }
+
+    /// Checks the text, returning the results of the checkers which made the condition true.
+    /// A condition which is only true because of a `!` has no results.
+    fn evaluate(
+        &self,
+        text: &str,
+        config: &Arc<Config>,
+        context: &Arc<SearchContext>,
+    ) -> Option<Vec<CheckResult>> {
+        match self {
+            ExitCondition::Checker(name) => checkers_named(name, config, context)
+                .iter()
+                .map(|checker| checker.check(text))
+                .find(|result| result.is_identified)
+                .map(|result| vec![result]),
+            ExitCondition::Not(condition) => match condition.evaluate(text, config, context) {
+                Some(_) => None,
+                None => Some(vec![]),
+            },
+            ExitCondition::And(left, right) => {
+                let mut results = left.evaluate(text, config, context)?;
+                results.extend(right.evaluate(text, config, context)?);
+                Some(results)
+            }
+            ExitCondition::Or(left, right) => left
+                .evaluate(text, config, context)
+                .or_else(|| right.evaluate(text, config, context)),
+        }
+    }
+}
+
+impl fmt::Display for ExitCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExitCondition::Checker(name) => write!(f, "{name}"),
+            ExitCondition::Not(condition) => match condition.as_ref() {
+                ExitCondition::And(..) | ExitCondition::Or(..) => write!(f, "!({condition})"),
+                _ => write!(f, "!{condition}"),
+            },
+            ExitCondition::And(left, right) => {
+                for (side, separator) in [(left, ""), (right, " && ")] {
+                    match side.as_ref() {
+                        ExitCondition::Or(..) => write!(f, "{separator}({side})")?,
+                        _ => write!(f, "{separator}{side}")?,
+                    }
+                }
+                Ok(())
+            }
+            ExitCondition::Or(left, right) => write!(f, "{left} || {right}"),
+        }
+    }
+}
+
+/// A piece of an exit condition
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    /// The name or tag of a checker
+    Name(String),
+    /// `!`
+    Not,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `(`
+    Open,
+    /// `)`
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "{name}"),
+            Token::Not => write!(f, "!"),
+            Token::And => write!(f, "&&"),
+            Token::Or => write!(f, "||"),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+        }
+    }
+}
+
+/// Splits an exit condition into names and operators
+fn tokenise(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = expression.chars().peekable();
+    while let Some(char) = chars.next() {
+        let token = match char {
+            char if char.is_whitespace() => continue,
+            '!' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' | '|' => {
+                if chars.next() != Some(char) {
+                    return Err(format!("{char} should be {char}{char}"));
+                }
+                if char == '&' {
+                    Token::And
+                } else {
+                    Token::Or
+                }
+            }
+            char if char.is_alphanumeric() || char == '_' || char == '-' => {
+                let mut name = char.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_' || next == '-') {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                Token::Name(name)
+            }
+            char => return Err(format!("did not expect {char}")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser for exit conditions, with a function for each level of precedence
+struct Parser {
+    /// The tokens of the exit condition
+    tokens: Vec<Token>,
+    /// The index of the next token to parse
+    next: usize,
+}
+
+impl Parser {
+    /// Parses conditions joined by `||`
+    fn or(&mut self) -> Result<ExitCondition, String> {
+        let mut condition = self.and()?;
+        while self.tokens.get(self.next) == Some(&Token::Or) {
+            self.next += 1;
+            condition = ExitCondition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    /// Parses conditions joined by `&&`
+    fn and(&mut self) -> Result<ExitCondition, String> {
+        let mut condition = self.unary()?;
+        while self.tokens.get(self.next) == Some(&Token::And) {
+            self.next += 1;
+            condition = ExitCondition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Ok(condition)
+    }
+
+    /// Parses a checker name, a condition in brackets or either of them after `!`
+    fn unary(&mut self) -> Result<ExitCondition, String> {
+        let token = self.tokens.get(self.next);
+        self.next += 1;
+        match token {
+            Some(Token::Name(name)) => Ok(ExitCondition::Checker(name.clone())),
+            Some(Token::Not) => Ok(ExitCondition::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let condition = self.or()?;
+                if self.tokens.get(self.next) != Some(&Token::Close) {
+                    return Err("a ( is never closed".to_string());
+                }
+                self.next += 1;
+                Ok(condition)
+            }
+            Some(token) => Err(format!("expected a checker name, not {token}")),
+            None => Err("expected a checker name at the end".to_string()),
+        }
+    }
+}
+
+/// The built in checkers with this name or tag, given this run's config and state.
+/// A checker called `name` wins over checkers tagged with it, so `regex` is the Regex Checker
+/// and not LemmeKnow, which is tagged "regex" too.
+/// Naming a checker in the exit condition uses it even if it is turned off.
+fn checkers_named(
+    name: &str,
+    config: &Arc<Config>,
+    context: &Arc<SearchContext>,
+) -> Vec<CheckerTypes> {
+    let name = [name.to_string()];
+    let checkers: Vec<(bool, CheckerTypes)> = [
+        named(
+            config,
+            context,
+            &name,
+            Checker::<RegexChecker>::new(),
+            CheckerTypes::CheckRegex,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<WordlistChecker>::new(),
+            CheckerTypes::CheckWordlist,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<FlagChecker>::new(),
+            CheckerTypes::CheckFlag,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<FileSignatureChecker>::new(),
+            CheckerTypes::CheckFileSignature,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<SecretsChecker>::new(),
+            CheckerTypes::CheckSecrets,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<SensitiveDataChecker>::new(),
+            CheckerTypes::CheckSensitiveData,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<StructuredDataChecker>::new(),
+            CheckerTypes::CheckStructuredData,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<LemmeKnow>::new(),
+            CheckerTypes::CheckLemmeKnow,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<EnglishChecker>::new(),
+            CheckerTypes::CheckEnglish,
+        ),
+        named(
+            config,
+            context,
+            &name,
+            Checker::<StatisticalChecker>::new(),
+            CheckerTypes::CheckStatistical,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let called_name = checkers.iter().any(|(called_name, _)| *called_name);
+    checkers
+        .into_iter()
+        .filter(|(has_name, _)| *has_name || !called_name)
+        .map(|(_, checker)| checker)
+        .collect()
+}
+
+/// Gives the checker this run's config and state and returns it if it has the name or tag,
+/// along with whether it is called `name` rather than only tagged with it
+fn named<Type>(
+    config: &Arc<Config>,
+    context: &Arc<SearchContext>,
+    name: &[String],
+    checker: Checker<Type>,
+    wrap: fn(Checker<Type>) -> CheckerTypes,
+) -> Option<(bool, CheckerTypes)> {
+    let checker = checker
+        .with_config(config.clone())
+        .with_context(context.clone());
+    checker
+        .named_in(name)
+        .then(|| (checker.has_name(&name[0]), wrap(checker)))
+}
+
+impl Check for Checker<CompositeChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Composite Checker",
+            description: "Combines other checkers with && (and), || (or) and ! (not)",
+            link: "",
+            tags: vec!["composite", "exit_condition"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        let Some(condition) = &self.config.exit_condition else {
+            return CheckResult::new(self);
+        };
+        trace!("Checking {} with the exit condition {}", text, condition);
+        let Some(results) = condition.evaluate(text, &self.config, &self.context) else {
+            return CheckResult::new(self);
+        };
+        let reasons: Vec<String> = results
+            .iter()
+            .map(|result| format!("{}: {}", result.checker_name, result.description))
+            .collect();
+        // Every checker in `results` was needed, so we are only as confident as the least confident one.
+        // A condition true only because of a `!` is what the user asked for, so it is fully confident.
+        let confidence = results
+            .iter()
+            .map(|result| result.confidence)
+            .reduce(f32::min)
+            .unwrap_or(1.0);
+
+        CheckResult {
+            is_identified: true,
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: format!("{condition} matched. {}", reasons.join(", ")),
+            link: self.link,
+            confidence,
+            time_taken: Duration::ZERO,
+            lemmeknow: results.iter().find_map(|result| result.lemmeknow),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{checkers_named, CompositeChecker, ExitCondition};
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::checkers::CheckerTypes;
+    use crate::config::Config;
+
+    /// A composite checker using this exit condition and regexes
+    fn composite_checker(condition: &str, regex: &[&str]) -> Checker<CompositeChecker> {
+        let config = Config {
+            exit_condition: Some(ExitCondition::parse(condition).unwrap()),
+            regex: regex.iter().map(|pattern| pattern.to_string()).collect(),
+            ..Default::default()
+        };
+        Checker::<CompositeChecker>::new().with_config(Arc::new(config))
+    }
+
+    #[test]
+    fn conditions_are_parsed_with_precedence() {
+        let condition = ExitCondition::parse("!regex || flag && (english || wordlist)").unwrap();
+        assert_eq!(
+            condition.to_string(),
+            "!regex || flag && (english || wordlist)"
+        );
+        assert!(matches!(condition, ExitCondition::Or(..)));
+        for invalid in ["regex &&", "(english", "regex & english", "english)", ""] {
+            assert!(
+                ExitCondition::parse(invalid).is_err(),
+                "{invalid} is invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_checkers_are_found() {
+        let config = Arc::new(Config::default());
+        let condition = ExitCondition::parse("english || englsh").unwrap();
+        assert_eq!(condition.unknown_checker(&config), Some("englsh"));
+        let condition = ExitCondition::parse("English && !lemmeknow").unwrap();
+        assert_eq!(condition.unknown_checker(&config), None);
+    }
+
+    #[test]
+    fn checkers_called_the_name_win_over_tags() {
+        let config = Arc::new(Config::default());
+        let regex = checkers_named("regex", &config, &Arc::default());
+        assert_eq!(regex.len(), 1);
+        assert!(matches!(regex[0], CheckerTypes::CheckRegex(_)));
+        // Nothing is called "ctf", so the checkers tagged with it are used
+        assert!(!checkers_named("ctf", &config, &Arc::default()).is_empty());
+    }
+
+    #[test]
+    fn and_needs_every_checker() {
+        let checker = composite_checker("flag && english", &[]);
+        assert!(!checker.check("flag{x}").is_identified);
+        let result = checker.check("the flag is flag{hello} and this is english");
+        assert!(result.is_identified);
+        assert!(result.description.contains("Flag Checker"));
+        assert!(result.description.contains("English Checker"));
+    }
+
+    #[test]
+    fn or_needs_any_checker() {
+        let checker = composite_checker("regex || flag", &["^secret"]);
+        assert!(checker.check("secret message").is_identified);
+        assert!(checker.check("flag{x}").is_identified);
+        assert!(!checker.check("hello world").is_identified);
+    }
+
+    #[test]
+    fn not_inverts_a_checker() {
+        let checker = composite_checker("english && !regex", &["password"]);
+        assert!(checker.check("hello there general kenobi").is_identified);
+        assert!(!checker.check("the password is hello there").is_identified);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::default_config;
 use lemmeknow::Identifier;
 
 use super::{
@@ -20,6 +21,9 @@ impl Check for Checker<DefaultChecker> {
             expected_runtime: 0.0,
             popularity: 0.0,
             lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
             _phantom: std::marker::PhantomData,
         }
     }
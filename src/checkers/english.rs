@@ -1,4 +1,7 @@
+use std::time::Duration;
+
 use crate::checkers::checker_result::CheckResult;
+use crate::config::default_config;
 use crate::storage;
 use lemmeknow::Identifier;
 use log::{debug, trace};
@@ -20,6 +23,9 @@ impl Check for Checker<EnglishChecker> {
             // English is the most popular language
             popularity: 1.0,
             lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -45,6 +51,9 @@ impl Check for Checker<EnglishChecker> {
             checker_description: self.description,
             description: filename.to_string(),
             link: self.link,
+            confidence: 0.0,
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
         };
 
         // After we've normalised our string, if we find it's a length 0 we don't do anything
@@ -54,6 +63,7 @@ impl Check for Checker<EnglishChecker> {
         }
 
         let split_input = input.split(' ');
+        let total_words = input.split(' ').count() as f64;
 
         // loop through all the words in the input
         for word in split_input {
@@ -78,16 +88,19 @@ impl Check for Checker<EnglishChecker> {
                 words_found,
                 input.len()
             );
-            // TODO: We are also typecasting to f64 instead of usize, which costs CPU cycles.
-            if words_found / (input.split(' ').count()) as f64 > PLAINTEXT_DETECTION_PERCENTAGE {
-                debug!("Found {} words in {}", words_found, original_input);
-                debug!(
-                    "Returning from English checker successfully with {}",
-                    original_input
-                );
-                result.is_identified = true;
-                break;
-            }
+        }
+
+        // The share of words in the dictionary is also how confident we are it's English
+        // TODO: We are also typecasting to f64 instead of usize, which costs CPU cycles.
+        let words_in_dictionary = words_found / total_words;
+        if words_in_dictionary > PLAINTEXT_DETECTION_PERCENTAGE {
+            debug!("Found {} words in {}", words_found, original_input);
+            debug!(
+                "Returning from English checker successfully with {}",
+                original_input
+            );
+            result.is_identified = true;
+            result.confidence = words_in_dictionary as f32;
         }
 
         result
@@ -123,6 +136,19 @@ mod tests {
         assert!(checker.check("preinterview").is_identified);
     }
 
+    #[test]
+    fn confidence_is_the_share_of_words_in_the_dictionary() {
+        let checker = Checker::<EnglishChecker>::new();
+        let all_english = checker.check("hello there general");
+        assert!(all_english.is_identified);
+        assert!((all_english.confidence - 1.0).abs() < f32::EPSILON);
+
+        let half_english = checker.check("hello there xqzvk pjhfw");
+        assert!(half_english.is_identified);
+        assert!((half_english.confidence - 0.5).abs() < f32::EPSILON);
+        assert_eq!(checker.check("xqzvk pjhfw").confidence, 0.0);
+    }
+
     #[test]
     fn test_check_basic2() {
         let checker = Checker::<EnglishChecker>::new();
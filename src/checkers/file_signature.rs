@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use lemmeknow::Identifier;
+use log::trace;
+
+use super::checker_type::{Check, Checker};
+use crate::{checkers::checker_result::CheckResult, config::default_config};
+
+/// The File Signature Checker looks for the magic bytes at the start of a file,
+/// so a base64 or hex encoded PNG or ZIP is reported as "decoded to a PNG file".
+///
+/// Decoders return text, so binary files are passed around with every byte as
+/// the character with the same value (Latin-1), which is what the hexadecimal
+/// decoder already does. [`text_to_bytes`] turns them back into the original bytes.
+pub struct FileSignatureChecker;
+
+/// A kind of file we can recognise by its first bytes
+pub struct FileSignature {
+    /// What we call the file, like "PNG"
+    pub name: &'static str,
+    /// The extension to use when saving the file, without the dot
+    pub extension: &'static str,
+    /// The magic bytes every file of this kind starts with
+    magic: &'static [u8],
+}
+
+/// The files we recognise. Signatures shorter than 4 bytes are left out
+/// because they are too easy to hit by accident.
+const FILE_SIGNATURES: [FileSignature; 12] = [
+    FileSignature {
+        name: "PNG",
+        extension: "png",
+        magic: b"\x89PNG\r\n\x1a\n",
+    },
+    FileSignature {
+        name: "JPEG",
+        extension: "jpg",
+        magic: b"\xff\xd8\xff",
+    },
+    FileSignature {
+        name: "GIF",
+        extension: "gif",
+        magic: b"GIF8",
+    },
+    FileSignature {
+        name: "PDF",
+        extension: "pdf",
+        magic: b"%PDF-",
+    },
+    FileSignature {
+        name: "ZIP",
+        extension: "zip",
+        magic: b"PK\x03\x04",
+    },
+    FileSignature {
+        name: "gzip",
+        extension: "gz",
+        magic: b"\x1f\x8b\x08",
+    },
+    FileSignature {
+        name: "7z",
+        extension: "7z",
+        magic: b"7z\xbc\xaf\x27\x1c",
+    },
+    FileSignature {
+        name: "RAR",
+        extension: "rar",
+        magic: b"Rar!\x1a\x07",
+    },
+    FileSignature {
+        name: "ELF",
+        extension: "elf",
+        magic: b"\x7fELF",
+    },
+    FileSignature {
+        name: "Java class",
+        extension: "class",
+        magic: b"\xca\xfe\xba\xbe",
+    },
+    FileSignature {
+        name: "WebAssembly",
+        extension: "wasm",
+        magic: b"\0asm",
+    },
+    FileSignature {
+        name: "SQLite database",
+        extension: "sqlite",
+        magic: b"SQLite format 3\0",
+    },
+];
+
+/// Returns the kind of file the bytes are, if they start with a signature we know
+pub fn file_signature(bytes: &[u8]) -> Option<&'static FileSignature> {
+    FILE_SIGNATURES
+        .iter()
+        .find(|signature| bytes.starts_with(signature.magic))
+}
+
+/// Turns decoded bytes which are not UTF-8 into text, if they are a file we recognise.
+/// Every byte becomes the character with the same value so [`text_to_bytes`] can undo it.
+pub fn binary_file_as_text(bytes: &[u8]) -> Option<String> {
+    file_signature(bytes)?;
+    Some(bytes.iter().map(|&byte| char::from(byte)).collect())
+}
+
+/// Gets the bytes a decoder produced back from its text.
+/// Text with only characters up to U+00FF is treated as one byte per character,
+/// anything else is UTF-8.
+pub fn text_to_bytes(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(c).ok())
+        .collect::<Option<Vec<u8>>>()
+        .unwrap_or_else(|| text.as_bytes().to_vec())
+}
+
+impl Check for Checker<FileSignatureChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "File Signature Checker",
+            description: "Checks for the magic bytes of files like PNG, ZIP, PDF and ELF",
+            link: "https://en.wikipedia.org/wiki/List_of_file_signatures",
+            tags: vec!["file", "magic", "binary"],
+            expected_runtime: 0.001,
+            popularity: 0.8,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking {:?} for file signatures", text);
+        let signature = file_signature(&text_to_bytes(text));
+
+        CheckResult {
+            is_identified: signature.is_some(),
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: signature.map_or_else(String::new, |signature| {
+                format!("decoded to a {} file", signature.name)
+            }),
+            link: self.link,
+            confidence: if signature.is_some() { 0.9 } else { 0.0 },
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifies_png() {
+        let checker = Checker::<FileSignatureChecker>::new();
+        let png = binary_file_as_text(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let result = checker.check(&png);
+        assert!(result.is_identified);
+        assert_eq!(result.description, "decoded to a PNG file");
+    }
+
+    #[test]
+    fn identifies_ascii_signatures() {
+        let checker = Checker::<FileSignatureChecker>::new();
+        assert!(checker.check("%PDF-1.7 and the rest").is_identified);
+    }
+
+    #[test]
+    fn does_not_identify_text() {
+        let checker = Checker::<FileSignatureChecker>::new();
+        assert!(!checker.check("hello world").is_identified);
+    }
+
+    #[test]
+    fn text_to_bytes_undoes_binary_file_as_text() {
+        let bytes = b"PK\x03\x04\xff\x00\x80";
+        assert_eq!(text_to_bytes(&binary_file_as_text(bytes).unwrap()), bytes);
+        assert_eq!(binary_file_as_text(b"\xff\xfe not a file"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use lemmeknow::Identifier;
+use log::trace;
+use regex::Regex;
+
+use super::checker_type::{Check, Checker};
+use crate::{checkers::checker_result::CheckResult, config::default_config};
+
+/// The Flag Checker looks for CTF flags like `flag{...}` or `picoCTF{...}`.
+/// Flags are exactly what CTF players are looking for, so when one matches
+/// Athena stops there instead of running the other checkers.
+/// Athena only runs it when `Config.flag_formats` isn't empty, so the known formats
+/// are looked for along with the custom ones. `--flag-format flag` looks for just the known ones.
+pub struct FlagChecker;
+
+/// The flag formats used by popular CTFs, matched case insensitively
+const KNOWN_FLAG_FORMATS: [&str; 4] = ["flag", "CTF", "HTB", "picoCTF"];
+
+/// Builds a regex matching `format{...}` for each known format and each of the custom ones.
+/// The format has to start a word, so `CTF` doesn't match the end of `DUCTF{...}`.
+/// Each run keeps what this returns in its context, see `SearchContext::flag_regex`.
+pub(crate) fn flag_regex(custom_formats: &[String]) -> Result<Regex, regex::Error> {
+    let formats: Vec<String> = KNOWN_FLAG_FORMATS
+        .into_iter()
+        .chain(custom_formats.iter().map(String::as_str))
+        .map(regex::escape)
+        .collect();
+    Regex::new(&format!(r"(?i)\b(?:{})\{{[^{{}}\s]+\}}", formats.join("|")))
+}
+
+impl Check for Checker<FlagChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Flag Checker",
+            description: "Looks for CTF flags like flag{...}, CTF{...}, HTB{...} and picoCTF{...}",
+            link: "https://ctftime.org/",
+            tags: vec!["flag", "ctf"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking {} for CTF flags", text);
+        let flag = self
+            .context
+            .flag_regex(&self.config)
+            .ok()
+            .and_then(|flags| flags.find(text))
+            .map(|found| found.as_str());
+
+        CheckResult {
+            is_identified: flag.is_some(),
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: flag.map_or_else(String::new, |flag| format!("CTF flag {flag}")),
+            link: self.link,
+            confidence: if flag.is_some() { 1.0 } else { 0.0 },
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        flag_checker::FlagChecker,
+    };
+    use crate::config::Config;
+    use crate::searchers::context::SearchContext;
+
+    #[test]
+    fn finds_known_flag_formats() {
+        let checker = Checker::<FlagChecker>::new();
+        for text in [
+            "flag{hello_world}",
+            "the answer is CTF{b4s3_64}",
+            "HTB{s0m3_fl4g}",
+            "picoCTF{r0t_13_is_fun}",
+        ] {
+            assert!(checker.check(text).is_identified, "{text} is a flag");
+        }
+    }
+
+    #[test]
+    fn does_not_find_flags_in_normal_text() {
+        let checker = Checker::<FlagChecker>::new();
+        assert!(!checker.check("hello world").is_identified);
+        assert!(!checker.check("flag{}").is_identified);
+    }
+
+    #[test]
+    fn finds_custom_flag_formats() {
+        let config = Config {
+            flag_formats: vec!["DUCTF".to_string()],
+            ..Default::default()
+        };
+        let checker = Checker::<FlagChecker>::new().with_config(Arc::new(config));
+        let result = checker.check("DUCTF{custom}");
+        assert!(result.is_identified);
+        assert_eq!(result.description, "CTF flag DUCTF{custom}");
+    }
+
+    #[test]
+    fn flag_regex_is_built_once_a_run() {
+        let config = Config {
+            flag_formats: vec!["DUCTF".to_string()],
+            ..Default::default()
+        };
+        let context = SearchContext::default();
+        let first = context.flag_regex(&config).unwrap();
+        let second = context.flag_regex(&config).unwrap();
+        assert!(std::ptr::eq(first, second));
+        assert!(first.is_match("flag{known}"));
+        assert!(first.is_match("DUCTF{custom}"));
+    }
+}
@@ -1,29 +1,265 @@
 use crate::checkers::checker_result::CheckResult;
+use crate::checkers::secrets::redact_secrets;
 use crate::cli_pretty_printing::human_checker_check;
-use crate::config::get_config;
-use crate::timer;
+use crate::config::Config;
+use crate::searchers::context::SearchContext;
 use text_io::read;
 
+/// A possible plaintext the human checker asks about.
+/// This is what [`Config::human_checker_callback`] is given, so library users can ask in their own UI.
+#[derive(Debug, Clone)]
+pub struct CandidateInfo {
+    /// The text which might be the plaintext
+    pub text: String,
+    /// What the checker thinks the text is, like "English text" or the name of a LemmeKnow match
+    pub description: String,
+    /// The name of the checker which identified the text
+    pub checker_name: &'static str,
+    /// A short description of that checker
+    pub checker_description: &'static str,
+    /// How confident the checker is that the text is plaintext, from 0.0 to 1.0
+    pub confidence: f32,
+}
+
+impl From<&CheckResult> for CandidateInfo {
+    fn from(result: &CheckResult) -> Self {
+        CandidateInfo {
+            text: result.text.clone(),
+            description: result.description.clone(),
+            checker_name: result.checker_name,
+            checker_description: result.checker_description,
+            confidence: result.confidence,
+        }
+    }
+}
+
 /// The Human Checker asks humans if the expected plaintext is real plaintext
 /// We can use all the automated checkers in the world, but sometimes they get false positives
 /// Humans have the last say.
-/// TODO: Add a way to specify a list of checkers to use in the library. This checker is not library friendly!
+/// If `Config.human_checker_callback` is set we ask it instead of reading the answer from stdin,
+/// even in API mode.
+/// `decoding_path` is the decoders used to get to the text, which we show to help the human decide.
+/// The timer of the run in `context` is paused while we wait for an answer, and only then.
 // compile this if we are not running tests
-pub fn human_checker(input: &CheckResult) -> bool {
-    timer::pause();
-    // wait instead of get so it waits for config being set
-    let config = get_config();
+pub fn human_checker(
+    input: &CheckResult,
+    config: &Config,
+    context: &SearchContext,
+    decoding_path: &str,
+) -> bool {
     // We still call human checker, just if config is false we return True
-    if !config.human_checker_on || config.api_mode {
+    if !config.human_checker_on {
         return true;
     }
-    human_checker_check(&input.description, &input.text);
-
-    let reply: String = read!("{}\n");
-    let result = reply.to_ascii_lowercase().starts_with('y');
+    // The user already said no to this text down another branch
+    if context.human_rejections.is_rejected(&input.text) {
+        return false;
+    }
+    if config
+        .auto_accept_confidence
+        .is_some_and(|threshold| input.confidence > threshold)
+    {
+        return true;
+    }
+    // The timer counts again as soon as we have an answer
+    let result = match &config.human_checker_callback {
+        Some(callback) => {
+            let _pause = context.pause_timer();
+            callback(&CandidateInfo::from(input))
+        }
+        // Nobody is reading stdin in API mode
+        None if config.api_mode => return true,
+        None => {
+            let _pause = context.pause_timer();
+            ask_on_stdin(input, config, decoding_path)
+        }
+    };
     if !result {
-        timer::resume();
+        context.human_rejections.record(&input.text);
         return false;
     }
     true
 }
+
+/// Prints the possible plaintext and returns true if the user answers yes
+fn ask_on_stdin(input: &CheckResult, config: &Config, decoding_path: &str) -> bool {
+    let text = if config.show_secrets {
+        input.text.clone()
+    } else {
+        redact_secrets(&input.text)
+    };
+    human_checker_check(input, &text, decoding_path, &config.regex);
+
+    let reply: String = read!("{}\n");
+    reply.to_ascii_lowercase().starts_with('y')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::checkers::english::EnglishChecker;
+
+    /// An English CheckResult for `text`, as if the English Checker had identified it
+    fn english_result(text: &str) -> CheckResult {
+        let mut result = CheckResult::new(&Checker::<EnglishChecker>::new());
+        result.is_identified = true;
+        result.text = text.to_string();
+        result.description = "English text".to_string();
+        result
+    }
+
+    #[test]
+    fn the_callback_is_asked_instead_of_stdin() {
+        let asked = Arc::new(Mutex::new(Vec::new()));
+        let seen = asked.clone();
+        let config = Config {
+            human_checker_on: true,
+            human_checker_callback: Some(Arc::new(move |candidate: &CandidateInfo| {
+                seen.lock().unwrap().push(candidate.text.clone());
+                candidate.text == "hello world"
+            })),
+            ..Default::default()
+        };
+
+        assert!(human_checker(
+            &english_result("hello world"),
+            &config,
+            &SearchContext::default(),
+            ""
+        ));
+        assert!(!human_checker(
+            &english_result("hello there"),
+            &config,
+            &SearchContext::default(),
+            ""
+        ));
+        assert_eq!(*asked.lock().unwrap(), ["hello world", "hello there"]);
+    }
+
+    #[test]
+    fn rejected_texts_are_only_asked_about_once_a_run() {
+        let asked = Arc::new(Mutex::new(0));
+        let count = asked.clone();
+        let config = Config {
+            human_checker_on: true,
+            human_checker_callback: Some(Arc::new(move |_: &CandidateInfo| {
+                *count.lock().unwrap() += 1;
+                false
+            })),
+            ..Default::default()
+        };
+
+        let run = SearchContext::default();
+        assert!(!human_checker(
+            &english_result("hello there"),
+            &config,
+            &run,
+            ""
+        ));
+        assert!(!human_checker(
+            &english_result("hello there"),
+            &config,
+            &run,
+            ""
+        ));
+        assert_eq!(*asked.lock().unwrap(), 1);
+        // Another run asks again
+        let next_run = SearchContext::default();
+        assert!(!human_checker(
+            &english_result("hello there"),
+            &config,
+            &next_run,
+            ""
+        ));
+        assert_eq!(*asked.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn the_timer_is_only_paused_while_asking() {
+        let context = Arc::new(SearchContext::default());
+        let asked_context = context.clone();
+        let config = Config {
+            human_checker_on: true,
+            human_checker_callback: Some(Arc::new(move |_: &CandidateInfo| {
+                asked_context.timer_is_paused()
+            })),
+            ..Default::default()
+        };
+        // The callback answers yes only if the timer was paused while it was asked
+        assert!(human_checker(
+            &english_result("hello world"),
+            &config,
+            &context,
+            ""
+        ));
+        assert!(!context.timer_is_paused());
+
+        let config = Config {
+            human_checker_on: false,
+            ..Default::default()
+        };
+        assert!(human_checker(
+            &english_result("hello world"),
+            &config,
+            &context,
+            ""
+        ));
+        assert!(!context.timer_is_paused());
+    }
+
+    #[test]
+    fn the_callback_is_only_asked_when_the_human_checker_is_on() {
+        let config = Config {
+            human_checker_on: false,
+            human_checker_callback: Some(Arc::new(|_: &CandidateInfo| false)),
+            ..Default::default()
+        };
+        assert!(human_checker(
+            &english_result("hello world"),
+            &config,
+            &SearchContext::default(),
+            ""
+        ));
+    }
+
+    #[test]
+    fn the_callback_is_told_what_the_checker_found() {
+        let config = Config {
+            human_checker_on: true,
+            human_checker_callback: Some(Arc::new(|candidate: &CandidateInfo| {
+                candidate.checker_name == "English Checker"
+                    && candidate.description == "English text"
+            })),
+            ..Default::default()
+        };
+        assert!(human_checker(
+            &english_result("hello world"),
+            &config,
+            &SearchContext::default(),
+            ""
+        ));
+    }
+
+    #[test]
+    fn confident_plaintext_is_accepted_without_asking() {
+        let config = Config {
+            human_checker_on: true,
+            api_mode: false,
+            auto_accept_confidence: Some(0.5),
+            ..Default::default()
+        };
+        let mut result = CheckResult::new(&Checker::<EnglishChecker>::new());
+        result.text = "This is a perfectly normal English sentence.".to_string();
+        result.confidence = 0.9;
+        // Asking would wait for stdin, so this only returns if the plaintext is accepted
+        assert!(human_checker(
+            &result,
+            &config,
+            &SearchContext::default(),
+            "Base64"
+        ));
+    }
+}
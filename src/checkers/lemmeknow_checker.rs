@@ -1,11 +1,37 @@
+use std::time::Duration;
+
 use super::checker_type::{Check, Checker};
 use crate::checkers::checker_result::CheckResult;
+use crate::config::default_config;
 use lemmeknow::{Data, Identifier};
+use serde::Serialize;
 
 /// The LemmeKnow Checker checks if the text matches a known Regex pattern.
 /// This is the struct for it.
 pub struct LemmeKnow;
 
+/// The LemmeKnow pattern which matched the text
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LemmeKnowMatch {
+    /// The name of the pattern, like "Internet Protocol (IP) Address Version 4"
+    pub name: &'static str,
+    /// How unlikely the pattern is to match by chance, from 0.0 to 1.0.
+    /// Higher is rarer, so the more likely the match is what the text really is
+    pub rarity: f32,
+    /// A link to more about what was matched, if LemmeKnow has one
+    pub link: Option<&'static str>,
+}
+
+impl From<&Data> for LemmeKnowMatch {
+    fn from(data: &Data) -> Self {
+        LemmeKnowMatch {
+            name: data.name,
+            rarity: data.rarity,
+            link: data.url,
+        }
+    }
+}
+
 impl Check for Checker<LemmeKnow> {
     fn new() -> Self {
         Checker {
@@ -14,30 +40,40 @@ impl Check for Checker<LemmeKnow> {
             description: "Uses LemmeKnow to check for regex matches",
             link: "https://swanandx.github.io/lemmeknow-frontend/",
             tags: vec!["lemmeknow", "regex"],
-            expected_runtime: 0.01,
+            expected_runtime: 0.05,
             popularity: 1.0,
             lemmeknow_config: Identifier::default().min_rarity(0.1),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
             _phantom: std::marker::PhantomData,
         }
     }
 
     fn check(&self, text: &str) -> CheckResult {
         let lemmeknow_result = self.lemmeknow_config.identify(text);
-        let mut is_identified = false;
-        let mut description = "".to_string();
-        if !lemmeknow_result.is_empty() {
-            is_identified = true;
-            description = format_data_result(&lemmeknow_result[0].data)
-        }
+        let lemmeknow_match = lemmeknow_result
+            .first()
+            .map(|found| LemmeKnowMatch::from(&found.data));
+        let description = lemmeknow_result
+            .first()
+            .map_or_else(String::new, |found| format_data_result(&found.data));
+        // Rare patterns like AWS keys are unlikely to match by chance
+        let confidence = lemmeknow_result
+            .first()
+            .map_or(0.0, |found| found.data.rarity);
 
         CheckResult {
-            is_identified,
+            is_identified: lemmeknow_match.is_some(),
             text: text.to_owned(),
             checker_name: self.name,
             checker_description: self.description,
             // Returns a vector of matches
             description,
             link: self.link,
+            confidence,
+            time_taken: Duration::ZERO,
+            lemmeknow: lemmeknow_match,
         }
     }
 }
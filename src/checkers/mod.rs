@@ -2,10 +2,30 @@ use self::{
     athena::Athena,
     checker_result::CheckResult,
     checker_type::{Check, Checker},
+    composite::CompositeChecker,
+    default_checker::DefaultChecker,
     english::EnglishChecker,
+    file_signature::FileSignatureChecker,
+    flag_checker::FlagChecker,
     lemmeknow_checker::LemmeKnow,
     regex_checker::RegexChecker,
+    registry::SharedChecker,
+    secrets::SecretsChecker,
+    sensitive_data::SensitiveDataChecker,
+    statistical::StatisticalChecker,
+    structured_data::StructuredDataChecker,
+    wait_athena::WaitAthena,
+    wordlist::WordlistChecker,
 };
+use crate::config::Config;
+use crate::searchers::context::SearchContext;
+use log::debug;
+use std::sync::Arc;
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+// std can't read the clock in a browser, web-time reads it from JavaScript there
+#[cfg(feature = "wasm")]
+use web_time::Instant;
 
 /// The default checker we use which simply calls all other checkers in order.
 pub mod athena;
@@ -13,16 +33,36 @@ pub mod athena;
 pub mod checker_result;
 /// This is the base checker that all other checkers inherit from.
 pub mod checker_type;
+/// The Composite Checker combines other checkers with && (and), || (or) and ! (not)
+pub mod composite;
 /// The default checker we use which simply calls all other checkers in order.
 pub mod default_checker;
 /// The English Checker is a checker that checks if the input is English
 pub mod english;
+/// The File Signature Checker looks for the magic bytes of files like PNG and ZIP
+pub mod file_signature;
+/// The Flag Checker looks for CTF flags like flag{...}
+pub mod flag_checker;
 /// The Human Checker asks humans if the expected plaintext is real plaintext
 pub mod human_checker;
 /// The LemmeKnow Checker checks if the text matches a known Regex pattern.
 pub mod lemmeknow_checker;
 /// The Regex checker checks to see if the intended text matches the plaintext
 pub mod regex_checker;
+/// The checker registry lets library users add their own checkers
+pub mod registry;
+/// The Secrets Checker looks for private keys, SSH keys, AWS access keys and JWTs
+pub mod secrets;
+/// The Sensitive Data Checker looks for credit card numbers, IBANs and Social Security Numbers
+pub mod sensitive_data;
+/// The Statistical Checker scores text with English quadgram statistics
+pub mod statistical;
+/// The Structured Data Checker recognises JSON, TOML, XML and YAML documents
+pub mod structured_data;
+/// WaitAthena runs all other checkers, and is used when collecting every plaintext in top results mode
+pub mod wait_athena;
+/// The Wordlist Checker checks if the text is a word in the user's wordlists
+pub mod wordlist;
 
 /// CheckerTypes is a wrapper enum for Checker
 pub enum CheckerTypes {
@@ -30,32 +70,238 @@ pub enum CheckerTypes {
     CheckLemmeKnow(Checker<LemmeKnow>),
     /// Wrapper for English Checker
     CheckEnglish(Checker<EnglishChecker>),
+    /// Wrapper for the Flag Checker
+    CheckFlag(Checker<FlagChecker>),
+    /// Wrapper for the Statistical Checker
+    CheckStatistical(Checker<StatisticalChecker>),
+    /// Wrapper for the Structured Data Checker
+    CheckStructuredData(Checker<StructuredDataChecker>),
+    /// Wrapper for the File Signature Checker
+    CheckFileSignature(Checker<FileSignatureChecker>),
+    /// Wrapper for the Sensitive Data Checker
+    CheckSensitiveData(Checker<SensitiveDataChecker>),
+    /// Wrapper for the Secrets Checker
+    CheckSecrets(Checker<SecretsChecker>),
+    /// Wrapper for the Wordlist Checker
+    CheckWordlist(Checker<WordlistChecker>),
     /// Wrapper for Athena Checker
     CheckAthena(Checker<Athena>),
     /// Wrapper for Regex
     CheckRegex(Checker<RegexChecker>),
+    /// Wrapper for the Composite Checker, used when there is an exit condition
+    CheckComposite(Checker<CompositeChecker>),
+    /// Wrapper for the Default checker, which never identifies anything.
+    /// Used when we want every candidate a decoder produces, like `ares decode`.
+    CheckDefault(Checker<DefaultChecker>),
+    /// Wrapper for WaitAthena Checker, used in top results mode
+    CheckWaitAthena(Checker<WaitAthena>),
+    /// Wrapper for a checker added to the [`CheckerRegistry`](registry::CheckerRegistry)
+    CheckRegistered(SharedChecker),
 }
 
 impl CheckerTypes {
     /// This functions calls appropriate check function of Checker
+    /// and records how long it took in [`CheckResult::time_taken`]
     pub fn check(&self, text: &str) -> CheckResult {
-        match self {
+        let start = Instant::now();
+        let mut result = match self {
             CheckerTypes::CheckLemmeKnow(lemmeknow_checker) => lemmeknow_checker.check(text),
             CheckerTypes::CheckEnglish(english_checker) => english_checker.check(text),
+            CheckerTypes::CheckFlag(flag_checker) => flag_checker.check(text),
+            CheckerTypes::CheckStatistical(statistical_checker) => statistical_checker.check(text),
+            CheckerTypes::CheckStructuredData(structured_data_checker) => {
+                structured_data_checker.check(text)
+            }
+            CheckerTypes::CheckFileSignature(file_signature_checker) => {
+                file_signature_checker.check(text)
+            }
+            CheckerTypes::CheckSensitiveData(sensitive_data_checker) => {
+                sensitive_data_checker.check(text)
+            }
+            CheckerTypes::CheckSecrets(secrets_checker) => secrets_checker.check(text),
+            CheckerTypes::CheckWordlist(wordlist_checker) => wordlist_checker.check(text),
             CheckerTypes::CheckAthena(athena_checker) => athena_checker.check(text),
             CheckerTypes::CheckRegex(regex_checker) => regex_checker.check(text),
+            CheckerTypes::CheckComposite(composite_checker) => composite_checker.check(text),
+            CheckerTypes::CheckDefault(default_checker) => default_checker.check(text),
+            CheckerTypes::CheckWaitAthena(wait_athena_checker) => wait_athena_checker.check(text),
+            CheckerTypes::CheckRegistered(registered_checker) => registered_checker.check(text),
+        };
+        result.time_taken = start.elapsed();
+        debug!("{} took {:?}", result.checker_name, result.time_taken);
+        result
+    }
+
+    /// The checker to use on what `decoder` gives, if it isn't this one.
+    /// When Athena asks the human checker it shows the decoders used to get to the text,
+    /// so each decoder gets an Athena with itself on the end of the path.
+    /// Other checkers never ask, so they are shared by every decoder.
+    pub fn after_decoder(&self, decoder: &str) -> Option<CheckerTypes> {
+        let CheckerTypes::CheckAthena(athena) = self else {
+            return None;
+        };
+        if !athena.config.human_checker_on || athena.config.api_mode {
+            return None;
         }
+        let decoding_path = if athena.decoding_path.is_empty() {
+            decoder.to_string()
+        } else {
+            format!("{} → {decoder}", athena.decoding_path)
+        };
+        Some(CheckerTypes::CheckAthena(
+            Checker::<Athena>::new()
+                .with_config(athena.config.clone())
+                .with_context(athena.context.clone())
+                .with_decoding_path(decoding_path),
+        ))
+    }
+}
+
+/// The checkers Athena and WaitAthena run, in the order they run them.
+///
+/// In Ciphey if the user uses the regex checker all the other checkers turn off.
+/// This is because they are looking for one specific bit of information so will not want the other checkers.
+/// A wordlist works the same way. An exit condition, like "regex || wordlist", replaces
+/// every checker with the Composite Checker, which runs the checkers the condition names.
+///
+/// Otherwise the registered checkers with a negative priority go first, then the built in checkers
+/// from the cheapest `expected_runtime` to the most expensive, then the rest of the registered checkers.
+/// Athena stops at the first checker which identifies the text, so the expensive checkers
+/// only run when every cheap one has given up.
+///
+/// The checkers get a run of their own, Athena gives them its run with `run_checkers_by_cost`.
+pub fn checkers_by_cost(config: &Arc<Config>) -> Vec<CheckerTypes> {
+    run_checkers_by_cost(config, &Arc::default())
+}
+
+/// [`checkers_by_cost`] for a run, so the checkers share its state like its compiled regexes
+pub(crate) fn run_checkers_by_cost(
+    config: &Arc<Config>,
+    context: &Arc<SearchContext>,
+) -> Vec<CheckerTypes> {
+    if config.exit_condition.is_some() {
+        let composite = Checker::<CompositeChecker>::new()
+            .with_config(config.clone())
+            .with_context(context.clone());
+        return vec![CheckerTypes::CheckComposite(composite)];
+    }
+    if !config.regex.is_empty() {
+        let regex = Checker::<RegexChecker>::new()
+            .with_config(config.clone())
+            .with_context(context.clone());
+        return vec![CheckerTypes::CheckRegex(regex)];
+    }
+    if config.wordlists.iter().any(|wordlist| wordlist.enabled) {
+        let wordlist = Checker::<WordlistChecker>::new().with_config(config.clone());
+        return vec![CheckerTypes::CheckWordlist(wordlist)];
+    }
+
+    // Checkers which cost the same run in this order, as sort_by is stable.
+    // Secrets go before LemmeKnow, which would describe a JWT or AWS key without redacting it,
+    // and quadgrams go after English as they only catch English the dictionary missed
+    let mut built_in: Vec<(f32, CheckerTypes)> = [
+        // Only CTF players want to stop at anything shaped like a flag, so the Flag Checker
+        // runs when they ask for it with `--flag-format`, and then looks for the known formats too
+        if config.flag_formats.is_empty() {
+            None
+        } else {
+            enabled(
+                config,
+                context,
+                Checker::<FlagChecker>::new(),
+                CheckerTypes::CheckFlag,
+            )
+        },
+        enabled(
+            config,
+            context,
+            Checker::<FileSignatureChecker>::new(),
+            CheckerTypes::CheckFileSignature,
+        ),
+        enabled(
+            config,
+            context,
+            Checker::<SecretsChecker>::new(),
+            CheckerTypes::CheckSecrets,
+        ),
+        enabled(
+            config,
+            context,
+            Checker::<SensitiveDataChecker>::new(),
+            CheckerTypes::CheckSensitiveData,
+        ),
+        enabled(
+            config,
+            context,
+            Checker::<StructuredDataChecker>::new(),
+            CheckerTypes::CheckStructuredData,
+        ),
+        enabled(
+            config,
+            context,
+            Checker::<LemmeKnow>::new(),
+            CheckerTypes::CheckLemmeKnow,
+        ),
+        enabled(
+            config,
+            context,
+            Checker::<EnglishChecker>::new(),
+            CheckerTypes::CheckEnglish,
+        ),
+        enabled(
+            config,
+            context,
+            Checker::<StatisticalChecker>::new(),
+            CheckerTypes::CheckStatistical,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    built_in.sort_by(|(cost, _), (other_cost, _)| cost.total_cmp(other_cost));
+
+    let registry = &config.checker_registry;
+    registry
+        .before_built_in()
+        .map(|checker| CheckerTypes::CheckRegistered(checker.clone()))
+        .chain(built_in.into_iter().map(|(_, checker)| checker))
+        .chain(
+            registry
+                .after_built_in()
+                .map(|checker| CheckerTypes::CheckRegistered(checker.clone())),
+        )
+        .collect()
+}
+
+/// Gives the built in checker this run's config and state, and returns it with its expected runtime
+/// if the user has not turned it off, see [`Checker::is_enabled`]
+fn enabled<Type>(
+    config: &Arc<Config>,
+    context: &Arc<SearchContext>,
+    checker: Checker<Type>,
+    wrap: fn(Checker<Type>) -> CheckerTypes,
+) -> Option<(f32, CheckerTypes)> {
+    let checker = checker
+        .with_config(config.clone())
+        .with_context(context.clone());
+    if checker.is_enabled() {
+        Some((checker.expected_runtime, wrap(checker)))
+    } else {
+        None
     }
 }
 
 // test
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use crate::checkers::{
         athena::Athena,
         checker_type::{Check, Checker},
-        CheckerTypes,
+        checkers_by_cost, CheckerTypes,
     };
+    use crate::config::{default_config, Config, ExitCondition};
 
     #[test]
     fn test_check_ip_address() {
@@ -68,4 +314,72 @@ mod tests {
         let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
         assert!(athena.check("and").is_identified);
     }
+
+    #[test]
+    fn checkers_run_from_cheapest_to_most_expensive() {
+        let checkers = checkers_by_cost(&default_config());
+        assert!(matches!(
+            checkers.first(),
+            Some(CheckerTypes::CheckFileSignature(_))
+        ));
+        assert!(matches!(
+            checkers.last(),
+            Some(CheckerTypes::CheckStatistical(_))
+        ));
+    }
+
+    #[test]
+    fn disabled_checkers_are_not_run() {
+        let config = Arc::new(Config {
+            disabled_checkers: vec!["lemmeknow".to_string()],
+            ..Default::default()
+        });
+        assert!(!checkers_by_cost(&config)
+            .iter()
+            .any(|checker| matches!(checker, CheckerTypes::CheckLemmeKnow(_))));
+    }
+
+    #[test]
+    fn flags_are_only_looked_for_with_a_flag_format() {
+        let is_flag_checker =
+            |checker: &CheckerTypes| matches!(checker, CheckerTypes::CheckFlag(_));
+        assert!(!checkers_by_cost(&default_config())
+            .iter()
+            .any(is_flag_checker));
+
+        let config = Arc::new(Config {
+            flag_formats: vec!["DUCTF".to_string()],
+            ..Default::default()
+        });
+        assert!(checkers_by_cost(&config).iter().any(is_flag_checker));
+    }
+
+    #[test]
+    fn regex_replaces_every_other_checker() {
+        let config = Arc::new(Config {
+            regex: vec!["^flag".to_string()],
+            ..Default::default()
+        });
+        let checkers = checkers_by_cost(&config);
+        assert_eq!(checkers.len(), 1);
+        assert!(matches!(checkers[0], CheckerTypes::CheckRegex(_)));
+    }
+
+    #[test]
+    fn exit_condition_replaces_every_other_checker() {
+        let config = Arc::new(Config {
+            regex: vec!["^flag".to_string()],
+            exit_condition: Some(ExitCondition::parse("regex || english").unwrap()),
+            ..Default::default()
+        });
+        let checkers = checkers_by_cost(&config);
+        assert_eq!(checkers.len(), 1);
+        assert!(matches!(checkers[0], CheckerTypes::CheckComposite(_)));
+    }
+
+    #[test]
+    fn check_records_the_time_taken() {
+        let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        assert!(!athena.check("192.168.0.1").time_taken.is_zero());
+    }
 }
@@ -1,7 +1,9 @@
+use std::time::Duration;
+
 use lemmeknow::Identifier;
 
 use super::checker_type::{Check, Checker};
-use crate::{checkers::checker_result::CheckResult, config::get_config};
+use crate::{checkers::checker_result::CheckResult, config::default_config};
 use log::trace;
 use regex::Regex;
 
@@ -9,6 +11,16 @@ use regex::Regex;
 /// This is the struct for it.
 pub struct RegexChecker;
 
+/// Compiles the regexes or cribs in `Config.regex`.
+/// Each run keeps what this returns in its context, see `SearchContext::regexes`.
+///
+/// # Errors
+///
+/// Returns the error of the first pattern which isn't a valid regex
+pub fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, regex::Error> {
+    patterns.iter().map(|pattern| Regex::new(pattern)).collect()
+}
+
 impl Check for Checker<RegexChecker> {
     fn new() -> Self {
         Checker {
@@ -19,23 +31,24 @@ impl Check for Checker<RegexChecker> {
             expected_runtime: 0.01,
             popularity: 1.0,
             lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
             _phantom: std::marker::PhantomData,
         }
     }
 
     fn check(&self, text: &str) -> CheckResult {
         trace!("Checking {} with regex", text);
-        // TODO put this into a lazy static so we don't generate it everytime
-        let config = get_config();
-        let regex_to_parse = config.regex.clone();
-        let re = Regex::new(&regex_to_parse.unwrap()).unwrap();
-
-        let regex_check_result = re.is_match(text);
-        let mut plaintext_found = false;
-        let printed_name = format!("Regex matched: {re}");
-        if regex_check_result {
-            plaintext_found = true;
-        }
+        // The patterns are checked before cracking starts, so they all compile
+        let regexes = self.context.regexes(&self.config).unwrap_or_default();
+        let matched = regexes.iter().find(|re| re.is_match(text));
+
+        let plaintext_found = matched.is_some();
+        let printed_name = match matched {
+            Some(re) => format!("Regex matched: {re}"),
+            None => "No regex matched".to_string(),
+        };
 
         CheckResult {
             is_identified: plaintext_found,
@@ -44,6 +57,63 @@ impl Check for Checker<RegexChecker> {
             checker_description: self.description,
             description: printed_name,
             link: self.link,
+            // The user told us exactly what the plaintext looks like
+            confidence: if plaintext_found { 1.0 } else { 0.0 },
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        regex_checker::RegexChecker,
+    };
+    use crate::config::Config;
+    use crate::searchers::context::SearchContext;
+
+    /// A regex checker looking for these patterns
+    fn regex_checker(patterns: &[&str]) -> Checker<RegexChecker> {
+        let config = Config {
+            regex: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+            ..Default::default()
+        };
+        Checker::<RegexChecker>::new().with_config(Arc::new(config))
+    }
+
+    #[test]
+    fn matches_if_any_pattern_matches() {
+        let checker = regex_checker(&["^CTF\\{", "flag\\{.*\\}"]);
+        let result = checker.check("the flag{is_here}");
+        assert!(result.is_identified);
+        assert_eq!(result.description, "Regex matched: flag\\{.*\\}");
+    }
+
+    #[test]
+    fn patterns_are_compiled_once_a_run() {
+        let config = Config {
+            regex: vec!["^CTF\\{".to_string()],
+            ..Default::default()
+        };
+        let context = SearchContext::default();
+        let first = context.regexes(&config).unwrap();
+        let second = context.regexes(&config).unwrap();
+        assert!(std::ptr::eq(first, second));
+
+        let invalid = Config {
+            regex: vec!["(unclosed".to_string()],
+            ..Default::default()
+        };
+        assert!(SearchContext::default().regexes(&invalid).is_err());
+    }
+
+    #[test]
+    fn does_not_match_if_no_pattern_matches() {
+        let checker = regex_checker(&["^CTF\\{", "flag\\{.*\\}"]);
+        assert!(!checker.check("hello world").is_identified);
+    }
+}
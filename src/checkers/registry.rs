@@ -0,0 +1,128 @@
+//! The checker registry lets library users add their own checkers
+//! without forking Ares, just like the decoder registry does for decoders.
+//! Register your checker on the Config you give to `perform_cracking`:
+//! ```rust
+//! use ares::checkers::checker_type::{Check, Checker};
+//! use ares::checkers::regex_checker::RegexChecker;
+//! use ares::config::Config;
+//!
+//! let mut config = Config::default();
+//! // Any struct which implements Check can be registered, here we re-use a built in one.
+//! // A negative priority runs it before the built in checkers
+//! config
+//!     .checker_registry
+//!     .register_checker(Box::new(Checker::<RegexChecker>::new()), -1);
+//! assert_eq!(config.checker_registry.before_built_in().count(), 1);
+//! ```
+
+use std::sync::Arc;
+
+use super::checker_type::Check;
+
+/// A checker which can be shared between the search threads
+pub type SharedChecker = Arc<dyn Check + Sync + Send>;
+
+/// Holds the custom checkers registered by library users.
+/// Athena runs these alongside the built in checkers, ordered by their priority.
+#[derive(Default, Clone)]
+pub struct CheckerRegistry {
+    /// The registered checkers and their priorities, sorted by priority
+    checkers: Vec<(i32, SharedChecker)>,
+}
+
+impl CheckerRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        CheckerRegistry::default()
+    }
+
+    /// Registers a custom checker so Athena uses it when cracking.
+    /// Checkers with a negative priority run before the built in checkers, the rest after them.
+    /// Lower priorities run first, and checkers with the same priority run in the order they were registered.
+    pub fn register_checker(&mut self, checker: Box<dyn Check + Sync + Send>, priority: i32) {
+        let position = self
+            .checkers
+            .partition_point(|(registered, _)| *registered <= priority);
+        self.checkers
+            .insert(position, (priority, Arc::from(checker)));
+    }
+
+    /// The checkers to run before the built in checkers, in the order to run them
+    pub fn before_built_in(&self) -> impl Iterator<Item = &SharedChecker> {
+        self.checkers
+            .iter()
+            .filter(|(priority, _)| *priority < 0)
+            .map(|(_, checker)| checker)
+    }
+
+    /// The checkers to run after the built in checkers, in the order to run them
+    pub fn after_built_in(&self) -> impl Iterator<Item = &SharedChecker> {
+        self.checkers
+            .iter()
+            .filter(|(priority, _)| *priority >= 0)
+            .map(|(_, checker)| checker)
+    }
+
+    /// Is no checker registered?
+    pub fn is_empty(&self) -> bool {
+        self.checkers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{CheckerRegistry, SharedChecker};
+    use crate::checkers::{checker_result::CheckResult, checker_type::Check};
+
+    /// A custom checker which identifies everything, named so we can tell them apart
+    struct NamedChecker(&'static str);
+
+    impl Check for NamedChecker {
+        fn new() -> Self {
+            NamedChecker("Named Checker")
+        }
+
+        fn check(&self, text: &str) -> CheckResult {
+            CheckResult {
+                is_identified: true,
+                text: text.to_string(),
+                description: String::new(),
+                checker_name: self.0,
+                checker_description: "Identifies everything",
+                link: "",
+                confidence: 1.0,
+                time_taken: Duration::ZERO,
+                lemmeknow: None,
+            }
+        }
+    }
+
+    /// The names of the checkers, in order
+    fn names<'a>(checkers: impl Iterator<Item = &'a SharedChecker>) -> Vec<&'static str> {
+        checkers
+            .map(|checker| checker.check("").checker_name)
+            .collect()
+    }
+
+    #[test]
+    fn registry_starts_empty() {
+        assert!(CheckerRegistry::new().is_empty());
+    }
+
+    #[test]
+    fn checkers_run_in_priority_order() {
+        let mut registry = CheckerRegistry::new();
+        registry.register_checker(Box::new(NamedChecker("last")), 5);
+        registry.register_checker(Box::new(NamedChecker("first")), -1);
+        registry.register_checker(Box::new(NamedChecker("after")), 0);
+        registry.register_checker(Box::new(NamedChecker("also after")), 0);
+
+        assert_eq!(names(registry.before_built_in()), ["first"]);
+        assert_eq!(
+            names(registry.after_built_in()),
+            ["after", "also after", "last"]
+        );
+    }
+}
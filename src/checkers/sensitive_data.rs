@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use lemmeknow::Identifier;
+use log::trace;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::checker_type::{Check, Checker};
+use crate::{checkers::checker_result::CheckResult, config::default_config};
+
+/// The Sensitive Data Checker looks for credit card numbers, IBANs and US Social Security Numbers.
+/// Random digits often look like these, so card numbers and IBANs must pass their checksums.
+/// Useful when running Ares over dumps in forensics.
+pub struct SensitiveDataChecker;
+
+/// 13 to 19 digits, written together or in the groups printed on cards:
+/// fours like 4111 1111 1111 1111, or 4-6-5 and 4-6-4 like American Express and Diners Club.
+/// Digits split anywhere else are not card numbers, but pass the checksum one time in ten.
+static CARD_NUMBER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\b(?:\d{13,19}|\d{4}(?:[ -]\d{4}){2,3}(?:[ -]\d{1,3})?|\d{4}[ -]\d{6}[ -]\d{4,5})\b",
+    )
+    .expect("card number regex is valid")
+});
+
+/// A country code, 2 check digits and up to 30 letters or digits, optionally grouped with spaces
+static IBAN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]){11,30}\b").expect("IBAN regex is valid")
+});
+
+/// Social Security Numbers are written as AAA-GG-SSSS
+static SSN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\d{3})-(\d{2})-(\d{4})\b").expect("SSN regex is valid"));
+
+impl Check for Checker<SensitiveDataChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Sensitive Data Checker",
+            description: "Checks for credit card numbers, IBANs and Social Security Numbers",
+            link: "https://en.wikipedia.org/wiki/Luhn_algorithm",
+            tags: vec!["credit card", "iban", "ssn", "forensics"],
+            expected_runtime: 0.02,
+            popularity: 0.5,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking {} for sensitive data", text);
+        let found = sensitive_data(text);
+
+        CheckResult {
+            is_identified: found.is_some(),
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: found.map_or_else(String::new, |(kind, value)| format!("{kind} {value}")),
+            link: self.link,
+            confidence: if found.is_some() { 0.8 } else { 0.0 },
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
+        }
+    }
+}
+
+/// Returns what kind of sensitive data is in the text and the value found
+fn sensitive_data(text: &str) -> Option<(&'static str, &str)> {
+    if let Some(card) = CARD_NUMBER
+        .find_iter(text)
+        .find(|card| luhn_valid(card.as_str()))
+    {
+        return Some(("Credit card number", card.as_str()));
+    }
+    if let Some(iban) = IBAN.find_iter(text).find(|iban| iban_valid(iban.as_str())) {
+        return Some(("IBAN", iban.as_str()));
+    }
+    SSN.captures_iter(text)
+        .find(|ssn| ssn_valid(&ssn[1], &ssn[2], &ssn[3]))
+        .map(|ssn| {
+            (
+                "Social Security Number",
+                ssn.get(0).map_or("", |m| m.as_str()),
+            )
+        })
+}
+
+/// The Luhn checksum every card number passes, ignoring spaces and dashes
+fn luhn_valid(number: &str) -> bool {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// An IBAN is valid if, with its first 4 characters moved to the end
+/// and every letter turned into a number (A = 10, B = 11...), it is 1 mod 97
+fn iban_valid(iban: &str) -> bool {
+    let iban: String = iban.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&iban.len()) {
+        return false;
+    }
+    let (country, rest) = iban.split_at(4);
+    let remainder = rest
+        .chars()
+        .chain(country.chars())
+        .try_fold(0u32, |remainder, c| {
+            let value = c.to_digit(36)?;
+            let shift = if value < 10 { 10 } else { 100 };
+            Some((remainder * shift + value) % 97)
+        });
+    remainder == Some(1)
+}
+
+/// Social Security Numbers are never issued with an area of 000, 666 or 900-999,
+/// a group of 00 or a serial of 0000
+fn ssn_valid(area: &str, group: &str, serial: &str) -> bool {
+    area != "000" && area != "666" && !area.starts_with('9') && group != "00" && serial != "0000"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifies_credit_card_numbers() {
+        let checker = Checker::<SensitiveDataChecker>::new();
+        let result = checker.check("card: 4111 1111 1111 1111");
+        assert!(result.is_identified);
+        assert_eq!(result.description, "Credit card number 4111 1111 1111 1111");
+        assert!(!checker.check("4111 1111 1111 1112").is_identified);
+        assert!(checker.check("4111111111111111").is_identified);
+        assert!(checker.check("3782 822463 10005").is_identified);
+        // Passes the checksum, but no card groups its digits like this
+        assert!(!checker.check("43424 638 24 767239").is_identified);
+    }
+
+    #[test]
+    fn identifies_ibans() {
+        let checker = Checker::<SensitiveDataChecker>::new();
+        assert!(checker.check("GB82 WEST 1234 5698 7654 32").is_identified);
+        assert!(!checker.check("GB82 WEST 1234 5698 7654 33").is_identified);
+    }
+
+    #[test]
+    fn identifies_ssns() {
+        let checker = Checker::<SensitiveDataChecker>::new();
+        assert!(checker.check("SSN 123-45-6789").is_identified);
+        assert!(!checker.check("SSN 666-45-6789").is_identified);
+    }
+
+    #[test]
+    fn checksums() {
+        assert!(luhn_valid("4111-1111-1111-1111"));
+        assert!(!luhn_valid("1234567812345678"));
+        assert!(iban_valid("DE89370400440532013000"));
+        assert!(!iban_valid("DE89370400440532013001"));
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use lemmeknow::Identifier;
+use log::trace;
+
+use super::checker_type::{Check, Checker};
+use crate::{
+    checkers::checker_result::CheckResult, config::default_config,
+    storage::quadgrams::ENGLISH_QUADGRAMS,
+};
+
+/// The Statistical Checker scores text with English quadgram statistics.
+/// Unlike the English Checker it does not need spaces or dictionary words,
+/// so it finds plaintext like "attackatdawn" which ciphers often leave behind.
+pub struct StatisticalChecker;
+
+/// Text with an average quadgram log probability above this is considered English.
+/// English prose scores between -4 and -6.2, random letters score around -8.
+/// ROT13 of English and some random letters keep enough common quadgrams to score just below -6.2.
+const FITNESS_THRESHOLD: f64 = -6.2;
+
+/// Random letters score around this, so it is where confidence starts
+const RANDOM_FITNESS: f64 = -8.0;
+
+/// Short texts don't have enough quadgrams for their score to mean anything
+const MIN_LETTERS: usize = 12;
+
+impl Check for Checker<StatisticalChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Statistical Checker",
+            description: "Scores text with English quadgram statistics",
+            link: "http://practicalcryptography.com/cryptanalysis/text-characterisation/quadgrams/",
+            tags: vec!["english", "statistics", "quadgrams"],
+            expected_runtime: 0.1,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        let letters = text.chars().filter(char::is_ascii_alphabetic).count();
+        let fitness = ENGLISH_QUADGRAMS.fitness(text);
+        trace!("Quadgram fitness of {} is {:?}", text, fitness);
+        let is_identified =
+            letters >= MIN_LETTERS && fitness.is_some_and(|fitness| fitness > FITNESS_THRESHOLD);
+
+        CheckResult {
+            is_identified,
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: "English text".to_string(),
+            link: self.link,
+            // -8 (random letters) is no confidence and -4 (English prose) is full confidence
+            confidence: match fitness {
+                Some(fitness) if is_identified => {
+                    ((fitness - RANDOM_FITNESS) / 4.0).clamp(0.0, 1.0) as f32
+                }
+                _ => 0.0,
+            },
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        statistical::StatisticalChecker,
+    };
+
+    #[test]
+    fn identifies_english_without_spaces() {
+        let checker = Checker::<StatisticalChecker>::new();
+        assert!(
+            checker
+                .check("attackatdawnwewillmeetattheoldbridge")
+                .is_identified
+        );
+    }
+
+    #[test]
+    fn does_not_identify_ciphertext() {
+        let checker = Checker::<StatisticalChecker>::new();
+        assert!(
+            !checker
+                .check("Wkh txlfn eurzq ira mxpsv ryhu wkh odcb grj")
+                .is_identified
+        );
+        assert!(
+            !checker
+                .check("SGVsbG8gV29ybGQgdGhpcyBpcyBhIHRlc3Q=")
+                .is_identified
+        );
+        // ROT13 of "hello there general"
+        assert!(!checker.check("uryyb gurer trareny").is_identified);
+    }
+
+    #[test]
+    fn does_not_identify_short_text() {
+        let checker = Checker::<StatisticalChecker>::new();
+        assert!(!checker.check("the").is_identified);
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use lemmeknow::Identifier;
+use log::trace;
+
+use super::checker_type::{Check, Checker};
+use crate::{checkers::checker_result::CheckResult, config::default_config};
+
+/// The Structured Data Checker recognises JSON, TOML, XML and YAML documents.
+/// Decoded config files and API payloads are plaintext, but they are mostly
+/// punctuation and keys so the English checker misses them.
+pub struct StructuredDataChecker;
+
+/// Shorter texts are too easily valid by accident, `1` is valid JSON and `a: b` is valid YAML
+const MIN_LENGTH: usize = 10;
+
+/// A document needs this many keys or items at its root (or elements, for XML)
+/// to count, so a single value like `"hello"` is not a JSON document.
+const MIN_ENTRIES: usize = 2;
+
+impl Check for Checker<StructuredDataChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Structured Data Checker",
+            description: "Checks for JSON, TOML, XML and YAML documents",
+            link: "https://en.wikipedia.org/wiki/Serialization",
+            tags: vec!["json", "toml", "xml", "yaml"],
+            expected_runtime: 0.02,
+            popularity: 0.8,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking if {} is structured data", text);
+        let format = document_format(text.trim());
+
+        CheckResult {
+            is_identified: format.is_some(),
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: format.map_or_else(String::new, |format| format!("{format} document")),
+            link: self.link,
+            confidence: if format.is_some() { 0.9 } else { 0.0 },
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
+        }
+    }
+}
+
+/// Returns the format of the document, or None if it is not one we know.
+/// YAML is a superset of JSON, so JSON is tried first to report the more specific format.
+fn document_format(text: &str) -> Option<&'static str> {
+    if text.len() < MIN_LENGTH {
+        return None;
+    }
+
+    if text.starts_with(['{', '[']) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
+            let entries = match json {
+                serde_json::Value::Object(object) => object.len(),
+                serde_json::Value::Array(array) => array.len(),
+                _ => 0,
+            };
+            return (entries >= MIN_ENTRIES).then_some("JSON");
+        }
+    }
+
+    if text.starts_with('<') {
+        let elements = roxmltree::Document::parse(text)
+            .map(|xml| xml.descendants().filter(|node| node.is_element()).count())
+            .unwrap_or_default();
+        return (elements >= MIN_ENTRIES).then_some("XML");
+    }
+
+    if text
+        .parse::<toml::Table>()
+        .is_ok_and(|table| table.len() >= MIN_ENTRIES)
+    {
+        return Some("TOML");
+    }
+
+    let yaml_entries = match serde_yaml::from_str::<serde_yaml::Value>(text) {
+        Ok(serde_yaml::Value::Mapping(mapping)) => mapping.len(),
+        Ok(serde_yaml::Value::Sequence(sequence)) => sequence.len(),
+        _ => 0,
+    };
+    (yaml_entries >= MIN_ENTRIES).then_some("YAML")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        structured_data::StructuredDataChecker,
+    };
+
+    #[test]
+    fn identifies_each_format() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        for (text, format) in [
+            (r#"{"user": "bee", "admin": true}"#, "JSON document"),
+            ("<user><name>bee</name></user>", "XML document"),
+            ("name = \"ares\"\nversion = 1", "TOML document"),
+            ("name: ares\nlanguages:\n  - rust", "YAML document"),
+        ] {
+            let result = checker.check(text);
+            assert!(result.is_identified, "{text} is {format}");
+            assert_eq!(result.description, format);
+        }
+    }
+
+    #[test]
+    fn does_not_identify_single_values() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        assert!(!checker.check("\"just a json string\"").is_identified);
+        assert!(!checker.check("hello world, this is text").is_identified);
+        assert!(!checker.check("aGVsbG8gd29ybGQ=").is_identified);
+    }
+}
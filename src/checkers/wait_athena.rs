@@ -0,0 +1,64 @@
+use crate::{checkers::checker_result::CheckResult, config::default_config};
+use lemmeknow::Identifier;
+use log::trace;
+
+use super::{
+    checker_type::{Check, Checker},
+    run_checkers_by_cost,
+};
+/// WaitAthena is the checker used in top results mode.
+/// It runs the same checkers as Athena, but never asks the human checker.
+/// Instead the search stores every plaintext it finds and keeps going,
+/// so the user can pick the right one from all of them at the end.
+pub struct WaitAthena;
+
+impl Check for Checker<WaitAthena> {
+    fn new() -> Self {
+        Checker {
+            name: "WaitAthena Checker",
+            description: "Runs all available checkers and collects every plaintext found",
+            link: "",
+            tags: vec!["athena", "all", "top_results"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        // Same checkers in the same order as Athena, but without the human checker
+        for checker in run_checkers_by_cost(&self.config, &self.context) {
+            let result = checker.check(text);
+            if result.is_identified {
+                trace!("{} identified the text", result.checker_name);
+                return result;
+            }
+        }
+
+        CheckResult::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        wait_athena::WaitAthena,
+    };
+
+    #[test]
+    fn wait_athena_identifies_plaintext() {
+        let checker = Checker::<WaitAthena>::new();
+        assert!(checker.check("https://www.google.com").is_identified);
+    }
+
+    #[test]
+    fn wait_athena_does_not_identify_gibberish() {
+        let checker = Checker::<WaitAthena>::new();
+        assert!(!checker.check("vjkrerkdnxhrfjekfdjexk").is_identified);
+    }
+}
@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use lemmeknow::Identifier;
+use log::trace;
+
+use super::checker_type::{Check, Checker};
+use crate::{
+    checkers::checker_result::CheckResult,
+    config::{default_config, WordlistMatching},
+};
+
+/// The Wordlist Checker checks if the text is one of the words in the user's wordlists.
+/// Like the Regex Checker, when a wordlist is given the other checkers are turned off
+/// as the user knows what they are looking for.
+pub struct WordlistChecker;
+
+impl Check for Checker<WordlistChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Wordlist Checker",
+            description: "Checks if the text is a word in one of the given wordlists",
+            link: "https://en.wikipedia.org/wiki/Wordlist",
+            tags: vec!["wordlist", "crib"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            lemmeknow_config: Identifier::default(),
+            config: default_config(),
+            decoding_path: String::new(),
+            context: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking {} against the wordlists", text);
+        let matched = self
+            .config
+            .wordlists
+            .iter()
+            .filter(|wordlist| wordlist.enabled)
+            .find_map(|wordlist| {
+                let word = wordlist.find_match(text.trim(), self.config.wordlist_matching)?;
+                Some((wordlist, word))
+            });
+        // Loose matches are less likely to be what the user is looking for
+        let confidence = match (&matched, self.config.wordlist_matching) {
+            (None, _) => 0.0,
+            (Some(_), WordlistMatching::Exact) => 1.0,
+            (Some(_), WordlistMatching::Normalised) => 0.9,
+            (Some(_), WordlistMatching::Fuzzy { .. }) => 0.7,
+            (Some(_), WordlistMatching::Substring) => 0.6,
+        };
+
+        CheckResult {
+            is_identified: matched.is_some(),
+            text: text.to_string(),
+            checker_name: self.name,
+            checker_description: self.description,
+            description: matched.map_or_else(String::new, |(wordlist, word)| {
+                if self.config.wordlist_matching == WordlistMatching::Exact {
+                    format!("Found in the wordlist {}", wordlist.name)
+                } else {
+                    format!("Matches {word} in the wordlist {}", wordlist.name)
+                }
+            }),
+            link: self.link,
+            confidence,
+            time_taken: Duration::ZERO,
+            lemmeknow: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        wordlist::WordlistChecker,
+    };
+    use crate::config::{Config, WordlistMatching};
+    use crate::storage::wordlists::Wordlist;
+
+    fn checker(wordlists: Vec<Wordlist>) -> Checker<WordlistChecker> {
+        let config = Config {
+            wordlists,
+            ..Default::default()
+        };
+        Checker::<WordlistChecker>::new().with_config(Arc::new(config))
+    }
+
+    #[test]
+    fn reports_which_wordlist_matched() {
+        let checker = checker(vec![
+            Wordlist::new("passwords", ["hunter2"]),
+            Wordlist::new("usernames", ["bee"]),
+        ]);
+        let result = checker.check("bee");
+        assert!(result.is_identified);
+        assert_eq!(result.description, "Found in the wordlist usernames");
+        assert!(!checker.check("hello").is_identified);
+    }
+
+    #[test]
+    fn fuzzy_matching_reports_the_word() {
+        let config = Config {
+            wordlists: vec![Wordlist::new("passwords", ["hunter2"])],
+            wordlist_matching: WordlistMatching::Fuzzy { max_distance: 1 },
+            ..Default::default()
+        };
+        let checker = Checker::<WordlistChecker>::new().with_config(Arc::new(config));
+        let result = checker.check("Hunter3");
+        assert!(result.is_identified);
+        assert_eq!(
+            result.description,
+            "Matches hunter2 in the wordlist passwords"
+        );
+    }
+
+    #[test]
+    fn ignores_disabled_wordlists() {
+        let mut passwords = Wordlist::new("passwords", ["hunter2"]);
+        passwords.enabled = false;
+        assert!(!checker(vec![passwords]).check("hunter2").is_identified);
+    }
+}
@@ -0,0 +1,76 @@
+//! Why a file given on the command line couldn't be used, in words users understand.
+//! `std::io::Error` alone says "No such file or directory (os error 2)" without the file.
+
+use std::fmt;
+use std::io::ErrorKind;
+
+/// Why a file couldn't be read or written
+#[derive(Debug)]
+pub enum FileError {
+    /// The file doesn't exist
+    NotFound(String),
+    /// The file had to be new, but it already exists
+    AlreadyExists(String),
+    /// The user isn't allowed to read or write the file
+    PermissionDenied(String),
+    /// The file had to be text, but isn't UTF-8
+    NotUtf8(String),
+    /// Anything else, like the disk being full
+    Io(String, std::io::Error),
+}
+
+impl FileError {
+    /// Explains the error `std::fs` gave for `file`
+    pub fn new(file: impl Into<String>, error: std::io::Error) -> Self {
+        let file = file.into();
+        match error.kind() {
+            ErrorKind::NotFound => FileError::NotFound(file),
+            ErrorKind::AlreadyExists => FileError::AlreadyExists(file),
+            ErrorKind::PermissionDenied => FileError::PermissionDenied(file),
+            // `read_to_string` gives InvalidData when the file isn't UTF-8
+            ErrorKind::InvalidData => FileError::NotUtf8(file),
+            _ => FileError::Io(file, error),
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::NotFound(file) => write!(f, "{file} does not exist"),
+            FileError::AlreadyExists(file) => write!(f, "{file} already exists"),
+            FileError::PermissionDenied(file) => {
+                write!(f, "you do not have permission to use {file}")
+            }
+            FileError::NotUtf8(file) => write!(f, "{file} is not UTF-8 text"),
+            FileError::Io(file, error) => write!(f, "{file} could not be used: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_say_which_file_and_why() {
+        let missing = std::fs::read_to_string("this file does not exist.txt").unwrap_err();
+        let error = FileError::new("this file does not exist.txt", missing);
+        assert!(matches!(error, FileError::NotFound(_)));
+        assert_eq!(
+            error.to_string(),
+            "this file does not exist.txt does not exist"
+        );
+
+        let path = std::env::temp_dir().join("ares_file_error_test.bin");
+        std::fs::write(&path, b"\xff\xfe").unwrap();
+        let not_utf8 = std::fs::read_to_string(&path).unwrap_err();
+        assert!(matches!(
+            FileError::new(path.to_string_lossy(), not_utf8),
+            FileError::NotUtf8(_)
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
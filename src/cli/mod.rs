@@ -1,16 +1,52 @@
-use std::{fs::File, io::Read};
+use std::{
+    fs::File,
+    io::{IsTerminal, Read},
+    sync::Mutex,
+};
+
+use crate::{
+    checkers::{
+        checker_type::{Check, Checker},
+        default_checker::DefaultChecker,
+        CheckerTypes,
+    },
+    cli_input_parser::{extract_printable_strings, hex_encode},
+    cli_pretty_printing::{
+        batch_results, cli_error, exit_with_error, list_decoders,
+        panic_failure_both_input_and_fail_provided, panic_failure_no_input_provided,
+        single_decoder_outputs, wordlist_is_a_bloom_filter,
+    },
+    config::{
+        Config, ExitCondition, OutputFileMode, OutputFormat, SearchStrategy, Wordlist,
+        WordlistMatching,
+    },
+    decoders::DECODER_MAP,
+    perform_batch_cracking, CrackOutcome,
+};
+/// Why a file given on the command line couldn't be used
+mod file_error;
+/// Named sets of options from the config file, picked with `--profile`
+mod profile;
+
+pub use self::file_error::FileError;
 
-use crate::{cli_pretty_printing::panic_failure_both_input_and_fail_provided, config::Config};
 /// This doc string acts as a help message when the uses run '--help' in CLI mode
 /// as do all doc strings on fields
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use lemmeknow::Identifier;
 use log::trace;
+use tracing_subscriber::fmt::{format::FmtSpan, writer::BoxMakeWriter};
+use tracing_subscriber::EnvFilter;
 
 /// The struct for Clap CLI arguments
 #[derive(Parser)]
 #[command(author = "Bee <bee@skerritt.blog>", about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Opts {
+    /// Run one of Ares' other commands instead of cracking
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// Some input. Because this isn't an Option<T> it's required to be used
     #[arg(short, long)]
     text: Option<String>,
@@ -18,59 +54,396 @@ pub struct Opts {
     /// A level of verbosity, and can be used multiple times
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
+    /// Write the logs to this file instead of stderr, so long runs can be debugged afterwards
+    #[arg(long, value_name = "FILE")]
+    log_file: Option<String>,
+    /// How to write the logs. `json` writes one object per line, with the search node and decoder it came from
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
 
     /// Turn off human checker, perfect for APIs where you don't want input from humans
     #[arg(short, long)]
     disable_human_checker: bool,
 
     /// Set timeout, if it is not decrypted after this time, it will return an error.
-    /// Default is 5 seconds.
+    /// Default is 5 seconds. 0 means no timeout, Ares searches until it runs out of things to decode.
     // If we want to call it `timeout`, the short argument contends with the one for Text `ares -t`.
     // I propose we just call it `cracking_timeout`.
     #[arg(short, long)]
     cracking_timeout: Option<u32>,
+    /// Use the options in this profile of the config file, like `--profile ctf` for `[profile.ctf]`.
+    /// Options given on the command line win over the profile
+    #[arg(long)]
+    profile: Option<String>,
+    /// The config file profiles are read from.
+    /// Defaults to `~/.config/ares/config.toml`
+    #[arg(long, value_name = "FILE", requires = "profile")]
+    config_file: Option<String>,
+    /// Pick the timeout from the length of the text and how many decoders are used.
+    /// Short texts fail fast, long ones get longer. Shown with `-v`
+    #[arg(long, conflicts_with = "cracking_timeout")]
+    auto_timeout: bool,
     /// Run in API mode, this will return the results instead of printing them.
     /// Default is false
     #[arg(short, long)]
     api_mode: Option<bool>,
     /// Opens a file for decoding
     /// Use instead of `--text`
-    #[arg(short, long)]
+    #[arg(short, long, alias = "input-file")]
     file: Option<String>,
+    /// Treat each line of `--file` as a separate input, or each file if `--file` is a directory.
+    /// They are cracked in parallel and the results printed in the same order
+    #[arg(long, requires = "file", conflicts_with = "top_results")]
+    batch: bool,
+    /// What to crack when `--file` is not text. `strings` cracks the printable strings in it,
+    /// `hex` cracks the whole file as hexadecimal
+    #[arg(long, value_enum, default_value_t = BinaryFileStrategy::Strings)]
+    binary_strategy: BinaryFileStrategy,
     /// If you have a crib (you know a piece of information in the plaintext)
     /// Or you want to create a custom regex to check against, you can use the Regex checker below.
     /// This turns off other checkers (English, LemmeKnow)
+    /// Can be used multiple times, the text is plaintext if any of them match
     #[arg(short, long)]
-    regex: Option<String>,
+    regex: Vec<String>,
+    /// A file of regexes or cribs, one per line, to use with the Regex checker.
+    /// Lines which aren't valid regexes are matched as plain text, so `flag{` works as a crib
+    #[arg(long)]
+    crib_file: Option<String>,
+    /// A wordlist with one word per line. The text is plaintext if it is one of the words,
+    /// and like `--regex` this turns off the other checkers. Can be used multiple times
+    #[arg(long)]
+    wordlist: Vec<String>,
+    /// Ignore case and whitespace when comparing the text to the wordlists
+    #[arg(long)]
+    wordlist_normalise: bool,
+    /// The text is plaintext if it contains any word from the wordlists, not just if it is one.
+    /// Fast even with huge wordlists
+    #[arg(long, conflicts_with_all = ["wordlist_normalise", "wordlist_distance"])]
+    wordlist_substring: bool,
+    /// The most memory in megabytes each wordlist can use. Bigger wordlists are stored
+    /// as Bloom filters, which fit but sometimes match words which aren't in the wordlist
+    #[arg(long, conflicts_with_all = ["wordlist_normalise", "wordlist_distance", "wordlist_substring"])]
+    wordlist_max_memory: Option<usize>,
+    /// Also match words up to this many character edits away, so near misses like
+    /// a Caesar decoding with one wrong character are found. Implies `--wordlist-normalise`
+    #[arg(long)]
+    wordlist_distance: Option<usize>,
+    /// Look for CTF flags in this format as well as flag{...}, CTF{...}, HTB{...} and picoCTF{...}
+    /// For example `--flag-format DUCTF` finds `DUCTF{...}`. Can be used multiple times.
+    /// Flags are only looked for when this is given, `--flag-format flag` looks for just the known ones
+    #[arg(long)]
+    flag_format: Vec<String>,
+    /// Combine checkers into the condition for plaintext, with && (and), || (or), ! (not) and brackets.
+    /// For example `--exit-condition "regex || wordlist"` or `--exit-condition "flag && english"`.
+    /// This replaces the other checkers, like `--regex` does
+    #[arg(long)]
+    exit_condition: Option<String>,
+    /// Only use these checkers. Takes a comma separated list of checker names or tags
+    /// For example `--only-checkers english,regex`
+    #[arg(long, value_delimiter = ',')]
+    only_checkers: Vec<String>,
+    /// Never use these checkers. Takes a comma separated list of checker names or tags
+    /// For example `--exclude-checkers lemmeknow` when it finds too much, or `english` for foreign languages
+    #[arg(long, value_delimiter = ',')]
+    exclude_checkers: Vec<String>,
+    /// Only use these decoders. Takes a comma separated list of decoder names or tags
+    /// For example `--only base64,hex`, `--only base` for every base decoder or `--only cipher`
+    #[arg(long, value_delimiter = ',')]
+    only: Vec<String>,
+    /// Never use these decoders. Takes a comma separated list of decoder names or tags
+    /// For example `--exclude caesar,reverse`, or `--exclude slow` for the audio and image decoders
+    #[arg(long, value_delimiter = ',')]
+    exclude: Vec<String>,
+    /// How to print the result. `json` prints the full result, including the path of decoders
+    /// `jsonl` prints one result per line, as soon as it is found in top results mode
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    format: OutputFormat,
+    /// Keep searching after the first plaintext is found and show every plaintext found before the timeout,
+    /// best first. Each one is shown as soon as it is found.
+    /// Give a number, like `--top-results 5`, to stop once that many have been found
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    top_results: Option<Option<u64>>,
+    /// Replay a CyberChef recipe saved as JSON on the input instead of searching
+    /// Useful for checking a solution someone shared with you
+    #[arg(long)]
+    recipe: Option<String>,
+    /// After a plaintext is found, keep decoding it until it stops decoding into another plaintext
+    #[arg(long, conflicts_with_all = ["top_results", "recipe"])]
+    keep_going: bool,
+    /// Accept plaintext Ares is more than this percent confident about without asking you.
+    /// Plaintext it is less sure of is still shown to the human checker
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+    auto_accept: Option<u8>,
+    /// Write the plaintext to this file, or the JSON result with `--format json`.
+    /// Decoded files like PNGs are written as their bytes. Existing files are only
+    /// written to with `--overwrite` or `--append`
+    #[arg(short, long, value_name = "FILE", alias = "output-file")]
+    output: Option<String>,
+    /// Replace the `--output` file if it already exists
+    #[arg(long, requires = "output", conflicts_with = "append")]
+    overwrite: bool,
+    /// Add to the end of the `--output` file if it already exists
+    #[arg(long, requires = "output")]
+    append: bool,
+    /// Print secrets like private keys, AWS access keys and JWTs in full instead of redacting them
+    #[arg(long)]
+    show_secrets: bool,
+    /// The most decoders Ares will chain together before giving up on a path
+    #[arg(long)]
+    max_depth: Option<u32>,
+    /// The most decodings Ares keeps waiting to be decoded. Past this it forgets
+    /// the ones which look least like plaintext, so huge inputs can't use up all your memory
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    max_nodes: Option<u64>,
+    /// How many of the texts one decoder returns are decoded further, like the 25 shifts
+    /// Caesar tries. The ones which look most like English are kept. Defaults to all of them
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    candidates_per_decoder: Option<u64>,
+    /// Roughly the most memory in megabytes the decodings waiting to be decoded can use.
+    /// Past this Ares forgets the ones which look least like plaintext
+    #[arg(long)]
+    max_memory: Option<usize>,
+    /// How many threads to decode on. Defaults to one for every CPU.
+    /// Useful for leaving some CPU free on shared machines
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    threads: Option<u64>,
+    /// At the end, print how many times each decoder ran and how long it took
+    #[arg(long)]
+    stats: bool,
+    /// Ring the terminal bell when Ares has finished, for long searches you switch away from
+    #[arg(long)]
+    bell: bool,
+    /// Always find the same plaintext by the same path for the same input, for debugging and CI.
+    /// Slower, as every decoder runs even after one finds the plaintext
+    #[arg(long)]
+    deterministic: bool,
+    /// At the end, write every decoding the search explored to this file.
+    /// Graphviz DOT if the file ends in .dot, otherwise JSON
+    #[arg(long, value_name = "FILE")]
+    export_tree: Option<String>,
+    /// Save where the search is to this file after each depth, so a long search can be
+    /// carried on with --resume if it is interrupted
+    #[arg(long, value_name = "FILE", conflicts_with = "iterative_deepening")]
+    checkpoint: Option<String>,
+    /// Carry on the search saved with --checkpoint, the text has to be the same
+    #[arg(long, value_name = "FILE", conflicts_with = "iterative_deepening")]
+    resume: Option<String>,
+    /// Use beam search, only keeping this many of the most promising decodings at each depth.
+    /// Faster on huge inputs, but it might miss the plaintext
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    beam_width: Option<u64>,
+    /// Search 1 decoder deep, then 2, then 3... showing the most likely decoding after each depth.
+    /// Useful with short timeouts or with `--top-results`
+    #[arg(long, conflicts_with = "beam_width")]
+    iterative_deepening: bool,
+    /// Load a decoder plugin compiled to WebAssembly. Can be used multiple times
+    /// Only available when Ares is built with the `wasm-plugins` feature
+    #[cfg(feature = "wasm-plugins")]
+    #[arg(long)]
+    plugin: Vec<String>,
+    /// Read the text to crack from the clipboard. Use instead of `--text` or `--file`
+    /// Only available when Ares is built with the `clipboard` feature
+    #[cfg(feature = "clipboard")]
+    #[arg(long, conflicts_with_all = ["text", "file"])]
+    clipboard_in: bool,
+    /// Copy the plaintext to the clipboard when Ares finds it
+    /// Only available when Ares is built with the `clipboard` feature
+    #[cfg(feature = "clipboard")]
+    #[arg(long)]
+    clipboard_out: bool,
+    /// Show a desktop notification when Ares has finished, for long searches you switch away from
+    /// Only available when Ares is built with the `notify` feature
+    #[cfg(feature = "notify")]
+    #[arg(long)]
+    notify: bool,
+    /// Download the text to crack from a URL, like a pastebin. Use instead of `--text` or `--file`
+    /// Only available when Ares is built with the `url` feature
+    #[cfg(feature = "url")]
+    #[arg(long, conflicts_with_all = ["text", "file"])]
+    url: Option<String>,
+    /// Remove the HTML tags from the page downloaded with `--url`, leaving only its text
+    #[cfg(feature = "url")]
+    #[arg(long, requires = "url")]
+    strip_html: bool,
+}
+
+/// The formats the logs can be written in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// One line of text per log
+    #[default]
+    Text,
+    /// One JSON object per log, with the spans it is in
+    Json,
+}
+
+/// What to crack when `--file` is not UTF-8 text, like an executable or a memory dump
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BinaryFileStrategy {
+    /// Crack the printable strings in the file, one per line, like the `strings` command
+    #[default]
+    Strings,
+    /// Crack the whole file as hexadecimal
+    Hex,
+}
+
+/// The exit codes of the CLI, so shell scripts can branch on how Ares finished
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The plaintext was found. In batch mode, every input was cracked
+    Cracked = 0,
+    /// The plaintext wasn't found before the timeout, or the search ran out of decodings
+    Failed = 1,
+    /// The input could not be read, or there wasn't exactly one input
+    InputError = 2,
+    /// An option is invalid, like a regex that doesn't compile or a wordlist that can't be read
+    ConfigError = 3,
+}
+
+impl ExitStatus {
+    /// Exits the program with this status
+    pub fn exit(self) -> ! {
+        std::process::exit(self as i32)
+    }
+}
+
+impl From<ExitStatus> for std::process::ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        std::process::ExitCode::from(status as u8)
+    }
+}
+
+/// The subcommands Ares supports. Running `ares` without one cracks the input.
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Print every decoder with its tags, popularity, description and link
+    ListDecoders {
+        /// Print the decoders as JSON, for scripts and the docs site
+        #[arg(long)]
+        json: bool,
+    },
+    /// Run exactly one decoder on the text and print every output it gives.
+    /// There is no search and no checkers, useful for debugging and scripts
+    Decode {
+        /// The name of the decoder, as shown by `ares list-decoders`
+        decoder: String,
+        /// The text to decode
+        #[arg(short, long)]
+        text: String,
+    },
+    /// Run Ares as a REST API. `POST /crack` cracks a text and `GET /decoders` lists the decoders
+    /// Only available when Ares is built with the `server` feature
+    #[cfg(feature = "server")]
+    Serve {
+        /// The port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// How many texts can be cracked at the same time, requests over this are turned away
+        #[arg(long, default_value_t = 4)]
+        max_concurrent: usize,
+        /// The longest timeout in seconds a request can ask for
+        #[arg(long, default_value_t = 30)]
+        max_timeout: u32,
+    },
+    /// Run Ares as a gRPC service, which streams each plaintext as it is found.
+    /// The service is defined in proto/ares.proto
+    /// Only available when Ares is built with the `grpc` feature
+    #[cfg(feature = "grpc")]
+    Grpc {
+        /// The port to listen on
+        #[arg(long, default_value_t = 50051)]
+        port: u16,
+        /// How many texts can be cracked at the same time, requests over this are turned away
+        #[arg(long, default_value_t = 4)]
+        max_concurrent: usize,
+        /// The longest timeout in seconds a request can ask for
+        #[arg(long, default_value_t = 30)]
+        max_timeout: u32,
+    },
+}
+
+/// What the command line asked Ares to do
+pub enum CliAction {
+    /// Crack the text with the config
+    Crack(String, Config),
+    /// Crack every input of the file with the config, see `--batch`
+    Batch(Vec<String>, Config),
+    /// Run a subcommand, which never cracks the input
+    Subcommand(Commands),
 }
 
 /// Parse CLI Arguments turns a Clap Opts struct, seen above
 /// Into a library Struct for use within the program
 /// The library struct can be found in the [config](../config) folder.
+/// Exits with `ExitStatus::InputError` when it gets both a file and text input at the same time,
+/// or neither. Subcommands and batch mode are returned for the caller to run.
 /// # Panics
-/// This function can panic when it gets both a file and text input at the same time.
-pub fn parse_cli_args() -> (String, Config) {
+/// Batch mode panics without a file, but clap makes `--batch` require `--file`.
+pub fn parse_cli_args() -> CliAction {
     let mut opts: Opts = Opts::parse();
-    let min_log_level = match opts.verbose {
-        0 => "Warn",
-        1 => "Info",
-        2 => "Debug",
-        _ => "Trace",
-    };
-    env_logger::init_from_env(
-        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, min_log_level),
-    );
+    init_logging(&opts);
+
+    // Subcommands do their own thing, they never crack the input
+    if let Some(command) = opts.command.take() {
+        return CliAction::Subcommand(command);
+    }
+
+    if opts.batch {
+        let inputs = read_batch_inputs(opts.file.take().unwrap(), opts.binary_strategy)
+            .unwrap_or_else(|error| {
+                exit_with_error(
+                    ExitStatus::InputError,
+                    &format!("Failed -- could not read the inputs: {error}"),
+                )
+            });
+        let (_, mut config) = cli_args_into_config_struct(opts, String::new());
+        // Many inputs are cracked at once, so we print nothing until they are all done
+        config.api_mode = true;
+        config.human_checker_on = false;
+        return CliAction::Batch(inputs, config);
+    }
+
+    #[cfg(feature = "clipboard")]
+    if opts.clipboard_in {
+        opts.text = Some(
+            crate::cli_input_parser::read_clipboard().unwrap_or_else(|| {
+                exit_with_error(
+                    ExitStatus::InputError,
+                    "Failed -- could not read any text from the clipboard",
+                )
+            }),
+        );
+    }
 
-    // If both the file and text are proivded, panic because we're not sure which one to use
+    #[cfg(feature = "url")]
+    if let Some(url) = opts.url.take() {
+        opts.text = Some(
+            crate::cli_input_parser::read_url(&url, opts.strip_html).unwrap_or_else(|error| {
+                exit_with_error(
+                    ExitStatus::InputError,
+                    &format!("Failed -- could not download {url}: {error}"),
+                )
+            }),
+        );
+    }
+
+    // If both the file and text are proivded, exit because we're not sure which one to use
     if opts.file.is_some() && opts.text.is_some() {
         panic_failure_both_input_and_fail_provided();
     }
 
     let input_text: String = if opts.file.is_some() {
-        read_and_parse_file(opts.file.unwrap())
+        read_and_parse_file_with_strategy(opts.file.unwrap(), opts.binary_strategy).unwrap_or_else(
+            |error| {
+                exit_with_error(
+                    ExitStatus::InputError,
+                    &format!("Failed -- could not read the file: {error}"),
+                )
+            },
+        )
     } else {
         opts.text
-            .expect("Error. No input was provided. Please use ares --help")
+            .unwrap_or_else(|| panic_failure_no_input_provided())
     };
 
     // Fixes bug where opts.text and opts.file are partially borrowed
@@ -81,48 +454,527 @@ pub fn parse_cli_args() -> (String, Config) {
     trace!("Parsed the arguments");
     trace!("The inputted text is {}", &input_text);
 
-    cli_args_into_config_struct(opts, input_text)
+    let (text, config) = cli_args_into_config_struct(opts, input_text);
+    CliAction::Crack(text, config)
+}
+
+/// Sends the logs to stderr, or to `--log-file`, in the `--log-format`.
+/// `-v` sets the level, unless `RUST_LOG` is set.
+/// The `log` records of the rest of Ares are forwarded to `tracing`, and with `-vv` or more
+/// every search node expansion and decoder call is a span, so long runs can be debugged afterwards.
+fn init_logging(opts: &Opts) {
+    let min_log_level = match opts.verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(min_log_level));
+    let writer = match &opts.log_file {
+        Some(file) => match File::create(file) {
+            Ok(file) => BoxMakeWriter::new(Mutex::new(file)),
+            Err(error) => exit_with_error(
+                ExitStatus::ConfigError,
+                &format!("Failed -- could not create the log file {file}: {error}"),
+            ),
+        },
+        None => BoxMakeWriter::new(std::io::stderr),
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE);
+    match opts.log_format {
+        LogFormat::Text => builder
+            .with_ansi(opts.log_file.is_none())
+            .with_writer(writer)
+            .init(),
+        LogFormat::Json => builder.json().with_writer(writer).init(),
+    }
+}
+
+/// Cracks every input of `--batch` and prints the results once they are all done.
+/// Returns `ExitStatus::Cracked` if every input was cracked, and `ExitStatus::ConfigError`
+/// if the config stopped the inputs being cracked at all, like a regex that doesn't compile.
+pub fn run_batch(inputs: &[String], config: Config) -> ExitStatus {
+    let config = std::sync::Arc::new(config);
+    crate::config::set_global_config(config.clone());
+    let outcomes = perform_batch_cracking(inputs, config.clone());
+    // Every input has the same config, so an error for one is an error for all of them
+    let status = match outcomes.iter().find_map(|outcome| outcome.as_ref().err()) {
+        Some(error) => {
+            cli_error(&format!(
+                "Failed -- could not crack the inputs because {error}"
+            ));
+            ExitStatus::ConfigError
+        }
+        None => {
+            let results: Vec<_> = outcomes
+                .into_iter()
+                .map(|outcome| outcome.ok().and_then(CrackOutcome::into_plaintext))
+                .collect();
+            batch_results(inputs, &results);
+            if results.iter().all(Option::is_some) {
+                ExitStatus::Cracked
+            } else {
+                ExitStatus::Failed
+            }
+        }
+    };
+    if config.bell {
+        crate::cli_pretty_printing::ring_bell();
+    }
+    #[cfg(feature = "notify")]
+    if config.notify {
+        crate::cli_pretty_printing::notify_finished(status);
+    }
+    status
+}
+
+/// Runs a subcommand such as `ares list-decoders`, returning the status to exit with
+pub fn run_subcommand(command: Commands) -> ExitStatus {
+    match command {
+        Commands::ListDecoders { json } => list_decoders(json),
+        Commands::Decode { decoder, text } => {
+            let outputs = decode_with_one_decoder(&decoder, &text);
+            single_decoder_outputs(&decoder, outputs);
+        }
+        #[cfg(feature = "server")]
+        Commands::Serve {
+            port,
+            max_concurrent,
+            max_timeout,
+        } => {
+            if let Err(error) = crate::server::serve(crate::server::ServerOptions {
+                port,
+                max_concurrent,
+                max_timeout,
+            }) {
+                cli_error(&format!("Failed -- could not start the server: {error}"));
+                return ExitStatus::ConfigError;
+            }
+        }
+        #[cfg(feature = "grpc")]
+        Commands::Grpc {
+            port,
+            max_concurrent,
+            max_timeout,
+        } => {
+            if let Err(error) = crate::grpc::serve(crate::grpc::GrpcOptions {
+                port,
+                max_concurrent,
+                max_timeout,
+            }) {
+                cli_error(&format!(
+                    "Failed -- could not start the gRPC service: {error}"
+                ));
+                return ExitStatus::ConfigError;
+            }
+        }
+    }
+    ExitStatus::Cracked
+}
+
+/// Runs the decoder called `decoder_name` from the `DECODER_MAP` once on the text.
+/// Returns None if there is no decoder with that name,
+/// otherwise every output of the decoder (which is empty if it failed).
+/// No checkers run, so decoders which produce many outputs (like Caesar) return all of them.
+pub fn decode_with_one_decoder(decoder_name: &str, text: &str) -> Option<Vec<String>> {
+    let decoder = DECODER_MAP
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(decoder_name))
+        .map(|(_, decoder)| decoder.clone())?;
+    trace!("Running only the {} decoder", decoder.get_name());
+    let checker = CheckerTypes::CheckDefault(Checker::<DefaultChecker>::new());
+    Some(
+        decoder
+            .crack(text, &checker)
+            .unencrypted_text
+            .unwrap_or_default(),
+    )
+}
+
+/// Reads the inputs for batch mode.
+/// If the path is a directory every file in it is an input, sorted by name.
+/// Otherwise every non-empty line of the file is an input.
+/// # Errors
+/// Returns why if the path or one of the files in it can't be read
+pub fn read_batch_inputs(
+    path: String,
+    binary_strategy: BinaryFileStrategy,
+) -> Result<Vec<String>, FileError> {
+    if std::path::Path::new(&path).is_dir() {
+        let mut files: Vec<_> = std::fs::read_dir(&path)
+            .map_err(|error| FileError::new(&path, error))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.is_file())
+            .collect();
+        files.sort();
+        return files
+            .into_iter()
+            .map(|file| {
+                read_and_parse_file_with_strategy(
+                    file.to_string_lossy().into_owned(),
+                    binary_strategy,
+                )
+            })
+            .collect();
+    }
+
+    Ok(std::fs::read_to_string(&path)
+        .map_err(|error| FileError::new(path, error))?
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
 }
 
 /// When the CLI is called with `-f` to open a file
 /// this function opens it
-/// # Panics
-/// This can panic when opening a file which does not exist!
-pub fn read_and_parse_file(file_path: String) -> String {
-    // TODO pretty match on the errors to provide better output
-    // Else it'll panic
-    let mut file = File::open(file_path).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
+/// If Ares is built with the `barcode` feature and the file is an image,
+/// we decode the barcodes in it and crack their contents instead.
+/// With the `audio` feature, WAV files are passed to the audio decoder.
+/// Files which are not UTF-8 text have their printable strings cracked.
+/// # Errors
+/// Returns why if the file can't be read, like when it does not exist
+pub fn read_and_parse_file(file_path: String) -> Result<String, FileError> {
+    read_and_parse_file_with_strategy(file_path, BinaryFileStrategy::default())
+}
+
+/// Like `read_and_parse_file`, but files which are not UTF-8 text
+/// are turned into text with the given `binary_strategy`.
+/// # Errors
+/// Returns why if the file can't be read, like when it does not exist
+pub fn read_and_parse_file_with_strategy(
+    file_path: String,
+    binary_strategy: BinaryFileStrategy,
+) -> Result<String, FileError> {
+    #[cfg(feature = "barcode")]
+    if let Some(payload) = crate::cli_input_parser::read_barcodes_from_image(&file_path) {
+        return Ok(payload);
+    }
+    #[cfg(feature = "audio")]
+    if let Some(data_uri) = crate::cli_input_parser::read_wav_as_data_uri(&file_path) {
+        return Ok(data_uri);
+    }
+
+    let mut bytes = Vec::new();
+    File::open(&file_path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|error| FileError::new(file_path, error))?;
+    let contents = match String::from_utf8(bytes) {
+        Ok(contents) => contents,
+        Err(error) => {
+            trace!("The file is not UTF-8, treating it as binary");
+            let bytes = error.into_bytes();
+            let strings = extract_printable_strings(&bytes);
+            // A binary file with no printable strings in it can still be cracked as hex
+            return Ok(
+                if binary_strategy == BinaryFileStrategy::Hex || strings.is_empty() {
+                    hex_encode(&bytes)
+                } else {
+                    strings
+                },
+            );
+        }
+    };
     // We can just put the file into the `Opts.text` and the program will work as normal
     // On Unix systems a line is defined as "\n{text}\n"
     // https://stackoverflow.com/a/729795
     // Which means if a user creates a file on Unix, it'll have a new line appended.
     // This is probably not what they wanted to decode (it is not what I wanted) so we are removing them
-    if contents.ends_with(['\n', '\r']) {
-        contents.strip_suffix(['\n', '\r']).unwrap().to_owned()
-    } else {
-        contents
+    Ok(match contents.strip_suffix(['\n', '\r']) {
+        Some(stripped) => stripped.to_owned(),
+        None => contents,
+    })
+}
+
+/// Reads the regexes and cribs given with `--crib-file`, one per line.
+/// Lines which are not valid regexes are escaped so they match exactly.
+/// Exits with `ExitStatus::ConfigError` if the crib file cannot be read
+fn read_crib_file(file_path: String) -> Vec<String> {
+    let contents = std::fs::read_to_string(&file_path).unwrap_or_else(|error| {
+        exit_with_error(
+            ExitStatus::ConfigError,
+            &format!(
+                "Failed -- could not read the crib file: {}",
+                FileError::new(&file_path, error)
+            ),
+        )
+    });
+    cribs_to_patterns(&contents)
+}
+
+/// Parses `--exit-condition`, exiting if it isn't valid
+fn parse_exit_condition(expression: &str) -> ExitCondition {
+    ExitCondition::parse(expression).unwrap_or_else(|error| {
+        exit_with_error(
+            ExitStatus::ConfigError,
+            &format!("Failed -- the exit condition {expression} is not valid: {error}"),
+        )
+    })
+}
+
+/// Turns the lines of a crib file into regexes, skipping blank lines
+fn cribs_to_patterns(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match regex::Regex::new(line) {
+            Ok(_) => line.to_string(),
+            Err(_) => regex::escape(line),
+        })
+        .collect()
+}
+
+/// Reads a wordlist given with `--wordlist`, using at most `max_memory` megabytes
+/// Exits with `ExitStatus::ConfigError` if the wordlist cannot be read
+fn read_wordlist(file_path: String, max_memory: Option<usize>) -> Wordlist {
+    let memory_limit = max_memory.map(|megabytes| megabytes.saturating_mul(1024 * 1024));
+    let wordlist = Wordlist::from_file(&file_path, memory_limit).unwrap_or_else(|error| {
+        exit_with_error(
+            ExitStatus::ConfigError,
+            &format!(
+                "Failed -- could not read the wordlist: {}",
+                FileError::new(&file_path, error)
+            ),
+        )
+    });
+    if wordlist.false_positive_rate() > 0.0 {
+        wordlist_is_a_bloom_filter(&wordlist.name, wordlist.false_positive_rate());
     }
+    wordlist
+}
+
+/// Reads the CyberChef recipe given with `--recipe`
+/// Exits with `ExitStatus::ConfigError` if the recipe file cannot be read
+fn read_recipe_file(file_path: String) -> String {
+    std::fs::read_to_string(&file_path).unwrap_or_else(|error| {
+        exit_with_error(
+            ExitStatus::ConfigError,
+            &format!(
+                "Failed -- could not read the recipe: {}",
+                FileError::new(&file_path, error)
+            ),
+        )
+    })
+}
+
+/// Fills in the options which weren't given on the command line from the `--profile`
+/// Exits with `ExitStatus::ConfigError` if the profile cannot be read
+fn apply_profile(opts: &mut Opts) {
+    let Some(name) = &opts.profile else {
+        return;
+    };
+    let profile = profile::read_profile(opts.config_file.take(), name).unwrap_or_else(|error| {
+        exit_with_error(
+            ExitStatus::ConfigError,
+            &format!("Failed -- could not use the profile {name}: {error}"),
+        )
+    });
+    trace!("Using the profile {}: {:?}", name, profile);
+    let fill = |option: &mut Vec<String>, from_profile: Vec<String>| {
+        if option.is_empty() {
+            *option = from_profile;
+        }
+    };
+    fill(&mut opts.only, profile.only);
+    fill(&mut opts.exclude, profile.exclude);
+    fill(&mut opts.only_checkers, profile.only_checkers);
+    fill(&mut opts.exclude_checkers, profile.exclude_checkers);
+    fill(&mut opts.wordlist, profile.wordlist);
+    opts.cracking_timeout = opts.cracking_timeout.or(profile.timeout);
+    opts.exit_condition = opts.exit_condition.take().or(profile.exit_condition);
 }
 
 /// Turns our CLI arguments into a config stuct
-fn cli_args_into_config_struct(opts: Opts, text: String) -> (String, Config) {
-    (
-        text,
-        Config {
-            verbose: opts.verbose,
-            lemmeknow_config: Identifier::default(),
-            // default is false, we want default to be true
-            human_checker_on: !opts.disable_human_checker,
-            // These if statements act as defaults
-            timeout: if opts.cracking_timeout.is_none() {
-                30
-            } else {
-                opts.cracking_timeout.unwrap()
+fn cli_args_into_config_struct(mut opts: Opts, text: String) -> (String, Config) {
+    apply_profile(&mut opts);
+    #[allow(unused_mut)]
+    let mut config = Config {
+        verbose: opts.verbose,
+        lemmeknow_config: Identifier::default(),
+        // These if statements act as defaults
+        timeout: if opts.cracking_timeout.is_none() {
+            30
+        } else {
+            opts.cracking_timeout.unwrap()
+        },
+        auto_timeout: opts.auto_timeout,
+        // JSON output is read by scripts, so nothing else is printed and the human checker is off
+        api_mode: opts.api_mode.is_some() || opts.format != OutputFormat::Pretty,
+        regex: opts
+            .regex
+            .into_iter()
+            .chain(opts.crib_file.map(read_crib_file).unwrap_or_default())
+            .collect(),
+        exit_condition: opts.exit_condition.as_deref().map(parse_exit_condition),
+        flag_formats: opts.flag_format,
+        wordlists: opts
+            .wordlist
+            .into_iter()
+            .map(|path| read_wordlist(path, opts.wordlist_max_memory))
+            .collect(),
+        wordlist_matching: match opts.wordlist_distance {
+            Some(max_distance) => WordlistMatching::Fuzzy { max_distance },
+            None if opts.wordlist_substring => WordlistMatching::Substring,
+            None if opts.wordlist_normalise => WordlistMatching::Normalised,
+            None => WordlistMatching::Exact,
+        },
+        enabled_checkers: opts.only_checkers,
+        disabled_checkers: opts.exclude_checkers,
+        enabled_decoders: opts.only,
+        disabled_decoders: opts.exclude,
+        output_format: opts.format,
+        // The human checker would ask about every plaintext, so it is off in top results mode.
+        // Without a terminal nobody can answer it, and it would wait forever
+        human_checker_on: !opts.disable_human_checker
+            && opts.top_results.is_none()
+            && std::io::stdin().is_terminal(),
+        auto_accept_confidence: opts.auto_accept.map(|percent| f32::from(percent) / 100.0),
+        top_results: opts.top_results.is_some(),
+        top_results_limit: opts.top_results.flatten().map(|limit| limit as usize),
+        recipe: opts.recipe.map(read_recipe_file),
+        output_file: opts.output,
+        output_file_mode: if opts.append {
+            OutputFileMode::Append
+        } else if opts.overwrite {
+            OutputFileMode::Overwrite
+        } else {
+            OutputFileMode::CreateNew
+        },
+        keep_going: opts.keep_going,
+        max_depth: opts.max_depth,
+        max_nodes: opts.max_nodes.map(|max_nodes| max_nodes as usize),
+        max_memory: opts
+            .max_memory
+            .map(|megabytes| megabytes.saturating_mul(1024 * 1024)),
+        threads: opts.threads.map(|threads| threads as usize),
+        stats: opts.stats,
+        bell: opts.bell,
+        deterministic: opts.deterministic,
+        export_search_tree: opts.export_tree,
+        checkpoint: opts.checkpoint,
+        resume: opts.resume,
+        show_secrets: opts.show_secrets,
+        search_strategy: match opts.beam_width {
+            Some(width) => SearchStrategy::Beam {
+                width: width as usize,
             },
-            api_mode: opts.api_mode.is_some(),
-            regex: opts.regex,
+            None if opts.iterative_deepening => SearchStrategy::IterativeDeepening,
+            None => SearchStrategy::Bfs,
         },
-    )
+        ..Default::default()
+    };
+
+    config.candidates_per_decoder = opts.candidates_per_decoder.map(|count| count as usize);
+
+    #[cfg(feature = "clipboard")]
+    {
+        config.clipboard_out = opts.clipboard_out;
+    }
+    #[cfg(feature = "notify")]
+    {
+        config.notify = opts.notify;
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    for path in &opts.plugin {
+        match crate::decoders::wasm_plugin::WasmDecoder::from_file(path) {
+            Ok(decoder) => config.decoder_registry.register_decoder(Box::new(decoder)),
+            Err(error) => crate::cli_pretty_printing::panic_failure_plugin_could_not_be_loaded(
+                path,
+                &error.to_string(),
+            ),
+        }
+    }
+
+    (text, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cribs_to_patterns, decode_with_one_decoder, read_and_parse_file,
+        read_and_parse_file_with_strategy, read_batch_inputs, BinaryFileStrategy, ExitStatus,
+        FileError, Opts,
+    };
+    use clap::Parser;
+
+    #[test]
+    fn exit_codes_are_the_documented_ones() {
+        // Scripts depend on these, so they must never change
+        assert_eq!(ExitStatus::Cracked as i32, 0);
+        assert_eq!(ExitStatus::Failed as i32, 1);
+        assert_eq!(ExitStatus::InputError as i32, 2);
+        assert_eq!(ExitStatus::ConfigError as i32, 3);
+    }
+
+    #[test]
+    fn top_results_takes_an_optional_count() {
+        let opts = Opts::try_parse_from(["ares", "-t", "aGk=", "--top-results", "5"]).unwrap();
+        assert_eq!(opts.top_results, Some(Some(5)));
+        let opts = Opts::try_parse_from(["ares", "--top-results", "-t", "aGk="]).unwrap();
+        assert_eq!(opts.top_results, Some(None));
+        assert!(Opts::try_parse_from(["ares", "-t", "aGk=", "--top-results", "0"]).is_err());
+    }
+
+    #[test]
+    fn cribs_which_are_not_regexes_are_escaped() {
+        let patterns = cribs_to_patterns("^CTF\\{.*\\}\n\nflag{\n");
+        assert_eq!(patterns, vec!["^CTF\\{.*\\}", "flag\\{"]);
+    }
+
+    #[test]
+    fn binary_files_are_read_with_the_chosen_strategy() {
+        let path = std::env::temp_dir().join("ares_binary_file_test.bin");
+        std::fs::write(&path, b"\xff\xfe\x00aGVsbG8=\x00").unwrap();
+        let file_path = path.to_string_lossy().into_owned();
+        assert_eq!(read_and_parse_file(file_path.clone()).unwrap(), "aGVsbG8=");
+        assert_eq!(
+            read_and_parse_file_with_strategy(file_path, BinaryFileStrategy::Hex).unwrap(),
+            "fffe00614756736247383d00"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn files_which_do_not_exist_are_an_error() {
+        let error = read_and_parse_file("this file does not exist.txt".to_string()).unwrap_err();
+        assert!(matches!(error, FileError::NotFound(_)));
+    }
+
+    #[test]
+    fn batch_inputs_are_the_non_empty_lines_of_a_file() {
+        let path = std::env::temp_dir().join("ares_batch_inputs_test.txt");
+        std::fs::write(&path, "aGVsbG8=\n\n  68656c6c6f  \n").unwrap();
+        let inputs = read_batch_inputs(
+            path.to_string_lossy().into_owned(),
+            BinaryFileStrategy::Strings,
+        )
+        .unwrap();
+        assert_eq!(inputs, vec!["aGVsbG8=", "68656c6c6f"]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn decode_runs_only_the_named_decoder() {
+        let outputs = decode_with_one_decoder("base64", "aGVsbG8gd29ybGQ=").unwrap();
+        assert_eq!(outputs, vec!["hello world".to_string()]);
+    }
+
+    #[test]
+    fn decode_returns_every_output_without_checking() {
+        // Caesar gives back one output per rotation as no checker stops it early
+        let outputs = decode_with_one_decoder("Caesar Cipher", "uryyb").unwrap();
+        assert!(outputs.contains(&"hello".to_string()));
+        assert!(outputs.len() > 1);
+    }
+
+    #[test]
+    fn decode_with_unknown_decoder_returns_none() {
+        assert!(decode_with_one_decoder("not a real decoder", "hello").is_none());
+    }
 }
@@ -0,0 +1,116 @@
+//! Profiles are named sets of options in the config file, like `[profile.ctf]` or `[profile.forensics]`,
+//! so users can switch between them with `--profile ctf` instead of juggling config files.
+//! ```toml
+//! [profile.ctf]
+//! timeout = 60
+//! only-checkers = ["flag", "regex"]
+//! exclude = ["braille"]
+//! wordlist = ["/usr/share/wordlists/rockyou.txt"]
+//! exit-condition = "flag || regex"
+//! ```
+//! Options given on the command line win over the profile.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use super::FileError;
+
+/// The options a profile can set, named like their CLI arguments
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Profile {
+    /// Like `--only`
+    pub only: Vec<String>,
+    /// Like `--exclude`
+    pub exclude: Vec<String>,
+    /// Like `--only-checkers`
+    pub only_checkers: Vec<String>,
+    /// Like `--exclude-checkers`
+    pub exclude_checkers: Vec<String>,
+    /// Like `--cracking-timeout`
+    pub timeout: Option<u32>,
+    /// Like `--wordlist`
+    pub wordlist: Vec<String>,
+    /// Like `--exit-condition`
+    #[serde(alias = "exit_condition")]
+    pub exit_condition: Option<String>,
+}
+
+/// The parts of the config file we read
+#[derive(Deserialize)]
+struct ConfigFile {
+    /// Every profile, by name
+    #[serde(default)]
+    profile: HashMap<String, Profile>,
+}
+
+/// Where the config file is when `--config-file` isn't given,
+/// `$XDG_CONFIG_HOME/ares/config.toml` or `~/.config/ares/config.toml`
+fn default_config_file() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("ares").join("config.toml"))
+}
+
+/// Finds the profile called `name` in the contents of a config file
+///
+/// # Errors
+///
+/// Returns why if the config file isn't valid or doesn't have the profile
+pub fn parse_profile(contents: &str, name: &str) -> Result<Profile, String> {
+    let mut config_file: ConfigFile =
+        toml::from_str(contents).map_err(|error| error.to_string())?;
+    config_file
+        .profile
+        .remove(name)
+        .ok_or_else(|| format!("there is no [profile.{name}]"))
+}
+
+/// Reads the profile called `name` from `file`, or from the default config file if it is None
+///
+/// # Errors
+///
+/// Returns why if the config file can't be found or read, isn't valid, or doesn't have the profile
+pub fn read_profile(file: Option<String>, name: &str) -> Result<Profile, String> {
+    let file = file
+        .map(PathBuf::from)
+        .or_else(default_config_file)
+        .ok_or("could not find your config directory, use --config-file")?;
+    let contents = std::fs::read_to_string(&file)
+        .map_err(|error| FileError::new(file.display().to_string(), error).to_string())?;
+    parse_profile(&contents, name).map_err(|error| format!("{}: {error}", file.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_are_read_by_name() {
+        let contents = r#"
+            [profile.ctf]
+            timeout = 60
+            only-checkers = ["flag", "regex"]
+
+            [profile.forensics]
+            exclude = ["caesar"]
+            exit_condition = "wordlist || flag"
+        "#;
+        let ctf = parse_profile(contents, "ctf").unwrap();
+        assert_eq!(ctf.timeout, Some(60));
+        assert_eq!(ctf.only_checkers, vec!["flag", "regex"]);
+        assert!(ctf.exclude.is_empty());
+        assert_eq!(
+            parse_profile(contents, "forensics").unwrap().exclude,
+            vec!["caesar"]
+        );
+        assert_eq!(
+            parse_profile(contents, "forensics").unwrap().exit_condition,
+            Some("wordlist || flag".to_string())
+        );
+        assert!(parse_profile(contents, "pwn").is_err());
+    }
+}
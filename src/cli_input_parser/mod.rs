@@ -9,9 +9,223 @@ and so on.
 // build new library_input
 
 use crate::api_library_input_struct::LibraryInput;
+#[cfg(feature = "barcode")]
+use log::debug;
+#[cfg(any(feature = "barcode", feature = "url"))]
+use log::trace;
 
 /// This creates a new LibraryInput struct and sets it to a default.
 /// added _ before name to let clippy know that they aren't used
 fn _main() {
     let _options = LibraryInput::default();
 }
+
+/// The file extensions we treat as images when looking for barcodes.
+#[cfg(feature = "barcode")]
+const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+
+/// When the user gives us an image with `--file`, we look for QR codes,
+/// Aztec codes, Code128 barcodes etc in it.
+/// If we find any, their payloads (one per line) become the text we crack.
+/// Returns None if the file is not an image or no barcode was found,
+/// in which case the file is read as text like normal.
+#[cfg(feature = "barcode")]
+pub fn read_barcodes_from_image(file_path: &str) -> Option<String> {
+    let extension = std::path::Path::new(file_path)
+        .extension()?
+        .to_str()?
+        .to_ascii_lowercase();
+    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+
+    trace!("Looking for barcodes in the image {}", file_path);
+    let barcodes = match rxing::helpers::detect_multiple_in_file(file_path) {
+        Ok(barcodes) => barcodes,
+        Err(e) => {
+            debug!("Could not find any barcodes in {}: {}", file_path, e);
+            return None;
+        }
+    };
+
+    let payloads: Vec<&str> = barcodes
+        .iter()
+        .inspect(|barcode| {
+            debug!(
+                "Found a {} barcode containing {}",
+                barcode.getBarcodeFormat(),
+                barcode.getText()
+            )
+        })
+        .map(|barcode| barcode.getText())
+        .filter(|text| !text.is_empty())
+        .collect();
+
+    if payloads.is_empty() {
+        return None;
+    }
+    Some(payloads.join("\n"))
+}
+
+/// When the user gives us a WAV file with `--file`, we can't read it as text.
+/// Instead we wrap it in a data URI which the audio decoder knows how to listen to.
+/// Returns None if the file is not a `.wav` file or can't be read.
+#[cfg(feature = "audio")]
+pub fn read_wav_as_data_uri(file_path: &str) -> Option<String> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let extension = std::path::Path::new(file_path).extension()?.to_str()?;
+    if !extension.eq_ignore_ascii_case("wav") {
+        return None;
+    }
+    let bytes = std::fs::read(file_path).ok()?;
+    Some(format!(
+        "{}{}",
+        crate::decoders::audio_decoder::WAV_DATA_URI_PREFIXES[0],
+        general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Reads the text to crack from the clipboard for `--clipboard-in`.
+/// Returns None if the clipboard can't be opened or has no text in it.
+#[cfg(feature = "clipboard")]
+pub fn read_clipboard() -> Option<String> {
+    let mut clipboard = arboard::Clipboard::new().ok()?;
+    let text = clipboard.get_text().ok()?;
+    // Copied text often ends in a new line, which is not part of what we want to decode
+    let text = text.trim_end_matches(['\n', '\r']).to_string();
+    if text.is_empty() {
+        return None;
+    }
+    Some(text)
+}
+
+/// Pages bigger than this are not going to be a ciphertext, so we don't download them.
+#[cfg(feature = "url")]
+const MAX_URL_BODY_BYTES: u64 = 10 * 1024 * 1024;
+
+/// How long we wait for a page to download before giving up.
+#[cfg(feature = "url")]
+const URL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Downloads the text to crack for `--url`, like a pastebin hosted challenge.
+/// With `strip_html` the HTML tags are removed so only the text of the page is cracked.
+/// # Errors
+/// Returns an error if the page can't be downloaded or is bigger than `MAX_URL_BODY_BYTES`.
+#[cfg(feature = "url")]
+pub fn read_url(url: &str, strip_html: bool) -> Result<String, String> {
+    use std::io::Read;
+
+    trace!("Downloading the text to crack from {}", url);
+    let agent = ureq::AgentBuilder::new().timeout(URL_TIMEOUT).build();
+    let response = agent.get(url).call().map_err(|error| error.to_string())?;
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_URL_BODY_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|error| error.to_string())?;
+    if body.len() as u64 > MAX_URL_BODY_BYTES {
+        return Err(format!(
+            "the page is bigger than {} MB",
+            MAX_URL_BODY_BYTES / 1024 / 1024
+        ));
+    }
+
+    let body = String::from_utf8_lossy(&body);
+    if strip_html {
+        return Ok(strip_html_tags(&body));
+    }
+    Ok(body.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Removes scripts, styles and tags from HTML and unescapes the common entities,
+/// returning the non-empty lines of text left over.
+#[cfg(feature = "url")]
+fn strip_html_tags(html: &str) -> String {
+    let scripts_and_styles = regex::Regex::new(r"(?is)<(script|style)\b.*?</(script|style)\s*>")
+        .expect("the script regex is valid");
+    let tags = regex::Regex::new(r"(?s)<[^>]*>").expect("the tag regex is valid");
+
+    let text = scripts_and_styles.replace_all(html, "");
+    let text = tags.replace_all(&text, "");
+    // &amp; goes last so we don't unescape things twice
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Printable runs shorter than this are almost always noise in binary files.
+const MIN_STRING_LENGTH: usize = 4;
+
+/// Returns every run of printable ASCII in the bytes, one per line, like the `strings` command.
+/// This is used when `--file` is not UTF-8 text, so we still have something to crack.
+pub fn extract_printable_strings(bytes: &[u8]) -> String {
+    bytes
+        .split(|byte| !(byte.is_ascii_graphic() || *byte == b' ' || *byte == b'\t'))
+        .filter(|run| run.len() >= MIN_STRING_LENGTH)
+        .map(|run| String::from_utf8_lossy(run).trim().to_string())
+        .filter(|run| !run.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Hex encodes the bytes so a binary file can be cracked by the hexadecimal decoder and friends.
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_printable_strings, hex_encode};
+
+    #[test]
+    fn printable_strings_are_extracted_from_binary() {
+        let bytes = b"\x00\x01aGVsbG8=\xff\xfe\x02ab\x00secret message\x00";
+        assert_eq!(extract_printable_strings(bytes), "aGVsbG8=\nsecret message");
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn html_is_stripped_to_its_text() {
+        let html = "<html><head><style>p { color: red; }</style></head>\n<body><pre>aGVsbG8=</pre>\n<p>a &amp; b</p><script>let x = 1;</script></body></html>";
+        assert_eq!(super::strip_html_tags(html), "aGVsbG8=\na & b");
+    }
+
+    #[test]
+    fn binary_is_hex_encoded() {
+        assert_eq!(hex_encode(&[0x00, 0xff, 0x41]), "00ff41");
+    }
+
+    #[cfg(feature = "barcode")]
+    #[test]
+    fn barcodes_are_read_from_images() {
+        assert_eq!(
+            super::read_barcodes_from_image("tests/test_fixtures/qr_code_of_base64.png").unwrap(),
+            "aGVsbG8gdGhlcmUgZ2VuZXJhbA=="
+        );
+    }
+
+    #[cfg(feature = "barcode")]
+    #[test]
+    fn images_without_barcodes_are_read_like_other_files() {
+        assert!(
+            super::read_barcodes_from_image("tests/test_fixtures/image_with_no_barcode.png")
+                .is_none()
+        );
+        // Only images are looked at, even if the file is there
+        assert!(super::read_barcodes_from_image(
+            "tests/test_fixtures/base64_3_times_with_no_new_line"
+        )
+        .is_none());
+    }
+}
@@ -2,23 +2,109 @@
 /// and make sure each one is up to our standards. Previously a rogue print statement that went off at an edge case
 /// would look a bit ugly and not the same UI as others.
 /// We can also do things like check for logic or share information / functions which would be a bit messy in the main code.
-use crate::DecoderResult;
+use crate::checkers::checker_result::CheckResult;
+use crate::checkers::file_signature::{file_signature, text_to_bytes};
+use crate::checkers::lemmeknow_checker::LemmeKnowMatch;
+use crate::checkers::secrets::redact_secrets;
+use crate::cli::{ExitStatus, FileError};
+use crate::config::{Config, OutputFileMode, OutputFormat};
+use crate::decoders::interface::DecoderMetadata;
+use crate::decoders::registry::SharedDecoder;
+use crate::decoders::DECODER_MAP;
+use crate::{CrackOutcome, CrackReport, DecoderResult, DecoderStats};
+use regex::Regex;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::Duration;
+
+/// What `--format json` prints when Ares decodes the text
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    /// The plaintext and every decoder used to get to it
+    #[serde(flatten)]
+    result: &'a DecoderResult,
+    /// How long it took to decode the text
+    time_taken_ms: u128,
+}
+
+/// What `--format json` prints when Ares fails to decode the text,
+/// so scripts can tell how far the search got
+#[derive(Serialize)]
+struct JsonFailure<'a> {
+    /// Why the search stopped, `timed_out`, `exhausted` or `cancelled`
+    failure: &'static str,
+    /// How long the search ran for
+    time_taken_ms: u128,
+    /// How many decoders deep the search got
+    depth_reached: u32,
+    /// Whether the search stopped at `--max-depth`
+    depth_limit_hit: bool,
+    /// How many decodings were forgotten to stay within `--max-nodes` and `--max-memory`
+    decodings_pruned: usize,
+    /// How many times each decoder ran, the one which took the longest first
+    decoders: Vec<DecoderAttempts>,
+    /// The decodings which looked most like plaintext, best first
+    closest_attempts: Vec<ClosestAttempt<'a>>,
+}
+
+/// How many times a decoder ran, for [`JsonFailure`]
+#[derive(Serialize)]
+struct DecoderAttempts {
+    /// The name of the decoder
+    name: String,
+    /// How many times it ran
+    invocations: u64,
+}
+
+/// A decoding which looked like plaintext, for [`JsonFailure`]
+#[derive(Serialize)]
+struct ClosestAttempt<'a> {
+    /// How much it looks like plaintext, from 0.0 to 1.0
+    confidence: f32,
+    /// The text and every decoder used to get to it
+    #[serde(flatten)]
+    result: &'a DecoderResult,
+}
 
 /// The output function is used to print the output of the program.
-/// If the API mode is on, it will not print.
-pub fn program_exiting_successful_decoding(result: DecoderResult) {
+/// With `--format json` it prints the whole result as JSON.
+/// Otherwise if the API mode is on, it will not print.
+/// # Panics
+/// Panics if the result cannot be turned into JSON, which should never happen.
+pub fn program_exiting_successful_decoding(result: DecoderResult, time_taken: Duration) {
     let config = crate::config::get_config();
+    #[cfg(feature = "clipboard")]
+    if config.clipboard_out {
+        copy_plaintext_to_clipboard(&result.text[0]);
+    }
+    if let Some(file) = &config.output_file {
+        save_plaintext(&result, time_taken, file, config);
+    }
+    if config.output_format == OutputFormat::Csv {
+        let input = result
+            .path
+            .first()
+            .map_or(result.text[0].as_str(), |c| c.encrypted_text.as_str());
+        println!("{CSV_HEADER}\n{}", csv_row(input, Some(&result)));
+        return;
+    }
+    if config.output_format != OutputFormat::Pretty {
+        let output = JsonOutput {
+            result: &result,
+            time_taken_ms: time_taken.as_millis(),
+        };
+        println!(
+            "{}",
+            serde_json::to_string(&output).expect("results are valid JSON")
+        );
+        return;
+    }
     if config.api_mode {
         return;
     }
-    let plaintext = result.text;
-    // calculate path
-    let decoded_path = result
-        .path
-        .iter()
-        .map(|c| c.decoder)
-        .collect::<Vec<_>>()
-        .join(" → ");
+    let plaintext = &result.text;
+    let decoded_path = decoder_path(&result);
 
     let decoded_path_coloured = ansi_term::Colour::Yellow.bold().paint(&decoded_path);
     let decoded_path_string = if !decoded_path.contains('→') {
@@ -27,23 +113,118 @@ pub fn program_exiting_successful_decoding(result: DecoderResult) {
     } else {
         format!("the decoders used are {decoded_path_coloured}")
     };
+    let bytes = text_to_bytes(&plaintext[0]);
+    if let Some(signature) = file_signature(&bytes) {
+        // Printing a binary file would mess up the terminal, so we offer to save it instead
+        println!(
+            "The plaintext is a {} file\nand {}",
+            ansi_term::Colour::Yellow.bold().paint(signature.name),
+            decoded_path_string
+        );
+        if config.output_file.is_none() {
+            println!(
+                "Use {} to save it",
+                ansi_term::Colour::Yellow
+                    .bold()
+                    .paint(format!("--output ares_output.{}", signature.extension))
+            );
+        }
+        return;
+    }
+    let plaintext = if config.show_secrets {
+        plaintext[0].clone()
+    } else {
+        redact_secrets(&plaintext[0])
+    };
     println!(
         "The plaintext is: \n{}\nand {}",
-        ansi_term::Colour::Yellow.bold().paint(&plaintext[0]),
+        ansi_term::Colour::Yellow.bold().paint(plaintext),
         decoded_path_string
     );
+    if let Some(found) = result.path.last().and_then(|crack| crack.lemmeknow) {
+        println!("{}", lemmeknow_details(&found));
+    }
+}
+
+/// Says what LemmeKnow identified the plaintext as, how rare that is and where to read more
+fn lemmeknow_details(found: &LemmeKnowMatch) -> String {
+    let mut details = format!(
+        "LemmeKnow identified it as {} (rarity {:.2})",
+        ansi_term::Colour::Yellow.bold().paint(found.name),
+        found.rarity
+    );
+    if let Some(link) = found.link {
+        details.push_str(&format!(", see {link}"));
+    }
+    details
+}
+
+/// Writes the plaintext to `--output`, in the `--format` the CLI prints it in.
+/// In the pretty format decoded files like PNGs are written as their bytes.
+/// Errors go to stderr so they aren't mistaken for the plaintext.
+/// # Panics
+/// Panics if the result cannot be turned into JSON, which should never happen.
+fn save_plaintext(result: &DecoderResult, time_taken: Duration, file: &str, config: &Config) {
+    let mut options = OpenOptions::new();
+    match config.output_file_mode {
+        OutputFileMode::CreateNew => options.write(true).create_new(true),
+        OutputFileMode::Overwrite => options.write(true).create(true).truncate(true),
+        OutputFileMode::Append => options.append(true).create(true),
+    };
+    let saved = options.open(file).and_then(|mut opened| {
+        let contents = match config.output_format {
+            OutputFormat::Pretty => {
+                let bytes = text_to_bytes(&result.text[0]);
+                if file_signature(&bytes).is_some() {
+                    bytes
+                } else {
+                    result.text[0].as_bytes().to_vec()
+                }
+            }
+            OutputFormat::Json | OutputFormat::Jsonl => {
+                let output = JsonOutput {
+                    result,
+                    time_taken_ms: time_taken.as_millis(),
+                };
+                let json = serde_json::to_string(&output).expect("results are valid JSON");
+                format!("{json}\n").into_bytes()
+            }
+            OutputFormat::Csv => {
+                let input = result
+                    .path
+                    .first()
+                    .map_or(result.text[0].as_str(), |c| c.encrypted_text.as_str());
+                let row = csv_row(input, Some(result));
+                // Appending to a CSV file which already has rows only adds the row
+                if opened.metadata()?.len() > 0 {
+                    format!("{row}\n").into_bytes()
+                } else {
+                    format!("{CSV_HEADER}\n{row}\n").into_bytes()
+                }
+            }
+        };
+        opened.write_all(&contents)
+    });
+    match saved.map_err(|error| FileError::new(file, error)) {
+        Ok(()) if config.api_mode => {}
+        Ok(()) => println!("💾 Saved the plaintext to {file}"),
+        Err(error @ FileError::AlreadyExists(_)) => eprintln!(
+            "⛔️ Could not save the plaintext because {error}. Use --overwrite to replace it or --append to add to it"
+        ),
+        Err(error) => eprintln!("⛔️ Could not save the plaintext because {error}"),
+    }
 }
 
 /// The output function is used to print the output of the program.
-pub fn decoded_how_many_times(depth: u32) {
-    let config = crate::config::get_config();
+pub fn decoded_how_many_times(depth: u32, config: &Config) {
     if config.api_mode {
         return;
     }
 
     // Gets how many decoders we have
     // Then we add 25 for Caesar
-    let decoders = crate::filtration_system::filter_and_get_decoders(&DecoderResult::default());
+    let decoders =
+        crate::filtration_system::filter_and_get_decoders(&DecoderResult::default(), config);
     let decoded_times_int = depth * (decoders.components.len() as u32 + 25);
 
     let time_took = calculate_time_took(decoded_times_int);
@@ -55,24 +236,402 @@ pub fn decoded_how_many_times(depth: u32) {
 /// Whenever the human checker checks for text, this function is run.
 /// The human checker checks to see if API mdoe is runnign inside of it
 /// rather than doing it here at the printing level
-pub fn human_checker_check(description: &str, text: &str) {
+/// `text` is the text to show, which may have had its secrets redacted,
+/// and any cribs in `regex` which match it are highlighted.
+pub fn human_checker_check(
+    result: &CheckResult,
+    text: &str,
+    decoding_path: &str,
+    regex: &[String],
+) {
     println!(
-        "🕵️ I think the plaintext is {}.\nPossible plaintext: '{}' (y/N): ",
-        ansi_term::Colour::Yellow.bold().paint(description),
-        ansi_term::Colour::Yellow.bold().paint(text)
-    )
+        "🕵️ I think the plaintext is {}, found by the {}.",
+        ansi_term::Colour::Yellow.bold().paint(&result.description),
+        result.checker_name
+    );
+    if let Some(found) = &result.lemmeknow {
+        println!("{}", lemmeknow_details(found));
+    }
+    if !decoding_path.is_empty() {
+        println!(
+            "Decoded with: {}",
+            ansi_term::Colour::Yellow.paint(decoding_path)
+        );
+    }
+    println!(
+        "Possible plaintext: '{}' (y/N): ",
+        highlight_cribs(text, regex)
+    );
+}
+
+/// Paints the text yellow, with every part of it matching one of the cribs in green
+/// so the human checker can see why it was flagged. Cribs which aren't valid regexes are ignored.
+fn highlight_cribs(text: &str, regex: &[String]) -> String {
+    let mut matches: Vec<(usize, usize)> = regex
+        .iter()
+        .filter_map(|crib| Regex::new(crib).ok())
+        .flat_map(|crib| {
+            crib.find_iter(text)
+                .map(|found| (found.start(), found.end()))
+                .collect::<Vec<_>>()
+        })
+        .filter(|(start, end)| start < end)
+        .collect();
+    matches.sort_unstable();
+
+    let plain = ansi_term::Colour::Yellow.bold();
+    let crib = ansi_term::Colour::Green.bold().underline();
+    let mut highlighted = String::new();
+    let mut printed = 0;
+    for (start, end) in matches {
+        if end <= printed {
+            continue;
+        }
+        let start = start.max(printed);
+        highlighted.push_str(&plain.paint(&text[printed..start]).to_string());
+        highlighted.push_str(&crib.paint(&text[start..end]).to_string());
+        printed = end;
+    }
+    highlighted.push_str(&plain.paint(&text[printed..]).to_string());
+    highlighted
+}
+
+/// In top results mode this is called every time the search finds a new plaintext,
+/// so the user sees them while Ares is still searching instead of only at the end.
+/// With `--format jsonl` it is printed as one line of JSON, so other tools can use it straight away.
+/// # Panics
+/// Panics if the result cannot be turned into JSON, which should never happen.
+pub fn top_result_found(result: &DecoderResult, config: &Config) {
+    match config.output_format {
+        OutputFormat::Jsonl => println!(
+            "{}",
+            serde_json::to_string(result).expect("results are valid JSON")
+        ),
+        OutputFormat::Pretty if !config.api_mode => println!(
+            "🔎 Found a possible plaintext {} using {}",
+            ansi_term::Colour::Yellow.bold().paint(&result.text[0]),
+            ansi_term::Colour::Yellow.bold().paint(decoder_path(result))
+        ),
+        OutputFormat::Pretty | OutputFormat::Json | OutputFormat::Csv => {}
+    }
+}
+
+/// After each sweep of iterative deepening we print the decoding which looks most like plaintext,
+/// so the user has something to go on even if the search times out.
+pub fn best_candidate_so_far(depth: u32, result: &DecoderResult, config: &Config) {
+    if config.api_mode {
+        return;
+    }
+    println!(
+        "🔎 The most likely decoding {} decoders deep is {} using {}",
+        depth,
+        ansi_term::Colour::Yellow.bold().paint(&result.text[0]),
+        ansi_term::Colour::Yellow.bold().paint(decoder_path(result))
+    );
+}
+
+/// When the timer runs out before we find the plaintext, we print the decoding which looked most like it.
+/// No checker identified it, so we make clear it's only a guess.
+pub fn best_guess_when_timed_out(result: &DecoderResult) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    println!(
+        "🤔 Ares ran out of time. The decoding which looked most like plaintext was {} using {}, but no checker confirmed it.",
+        ansi_term::Colour::Yellow.bold().paint(&result.text[0]),
+        ansi_term::Colour::Yellow.bold().paint(decoder_path(result))
+    );
+}
+
+/// Prints every plaintext found in top results mode once the search has finished, best first.
+/// With `--format json` they are printed as a JSON array.
+/// With `--format jsonl` nothing is printed as each one was printed when it was found.
+/// If there are none we say why using the rest of the report.
+/// # Panics
+/// Panics if the results cannot be turned into JSON, which should never happen.
+pub fn display_top_results(report: &CrackReport) {
+    let results = &report.top_results;
+    let config = crate::config::get_config();
+    match config.output_format {
+        OutputFormat::Jsonl => return,
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string(results).expect("results are valid JSON")
+            );
+            return;
+        }
+        OutputFormat::Pretty | OutputFormat::Csv => {}
+    }
+    if config.api_mode {
+        return;
+    }
+    if results.is_empty() {
+        failed_to_decode(report);
+        return;
+    }
+
+    println!("🥳 Ares found {} possible plaintexts:", results.len());
+    for (i, result) in results.iter().enumerate() {
+        let decoded_path = decoder_path(result);
+        println!(
+            "{}. {}\n   the decoders used are {}",
+            i + 1,
+            ansi_term::Colour::Yellow.bold().paint(&result.text[0]),
+            ansi_term::Colour::Yellow.bold().paint(&decoded_path)
+        );
+    }
+}
+
+/// Cracking could not start, like when `--recipe` could not be replayed or `--regex` is not valid
+pub fn cracking_failed_with_error(error: &crate::AresError) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    println!("⛔️ Ares could not crack the text because {error}.");
+}
+
+/// Copies the plaintext to the clipboard for `--clipboard-out`.
+/// Tells the user if it worked, unless the output is meant for scripts.
+#[cfg(feature = "clipboard")]
+fn copy_plaintext_to_clipboard(plaintext: &str) {
+    let copied = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(plaintext))
+        .is_ok();
+    let config = crate::config::get_config();
+    if config.api_mode || config.output_format != OutputFormat::Pretty {
+        return;
+    }
+    if copied {
+        println!("📋 Copied the plaintext to your clipboard");
+    } else {
+        println!("⛔️ Could not copy the plaintext to your clipboard");
+    }
+}
+
+/// What `--format json` and `--format jsonl` print for each input in batch mode
+#[derive(Serialize)]
+struct BatchOutput<'a> {
+    /// The text we tried to crack
+    input: &'a str,
+    /// The plaintext and every decoder used to get to it, or null if we could not crack it
+    result: Option<&'a DecoderResult>,
+}
+
+/// The first row of `--format csv`
+const CSV_HEADER: &str = "input,plaintext,decoders";
+
+/// Prints the result of every input in batch mode, in the same order as the inputs.
+/// # Panics
+/// Panics if the results cannot be turned into JSON, which should never happen.
+pub fn batch_results(inputs: &[String], results: &[Option<DecoderResult>]) {
+    let config = crate::config::get_config();
+    let outputs: Vec<BatchOutput> = inputs
+        .iter()
+        .zip(results)
+        .map(|(input, result)| BatchOutput {
+            input,
+            result: result.as_ref(),
+        })
+        .collect();
+
+    match config.output_format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string(&outputs).expect("results are valid JSON")
+        ),
+        OutputFormat::Jsonl => {
+            for output in &outputs {
+                println!(
+                    "{}",
+                    serde_json::to_string(output).expect("results are valid JSON")
+                );
+            }
+        }
+        OutputFormat::Csv => {
+            println!("{CSV_HEADER}");
+            for output in &outputs {
+                println!("{}", csv_row(output.input, output.result));
+            }
+        }
+        OutputFormat::Pretty => {
+            for (i, output) in outputs.iter().enumerate() {
+                match output.result {
+                    Some(result) => println!(
+                        "{}. {} → {}\n   the decoders used are {}",
+                        i + 1,
+                        output.input,
+                        ansi_term::Colour::Yellow.bold().paint(&result.text[0]),
+                        ansi_term::Colour::Yellow.bold().paint(decoder_path(result))
+                    ),
+                    None => println!("{}. {} → ⛔️ could not decode", i + 1, output.input),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the decoders used to get to the plaintext with their keys, like "Base64 → Caesar Cipher(shift=13)"
+fn decoder_path(result: &DecoderResult) -> String {
+    result
+        .path
+        .iter()
+        .map(|c| c.decoder_with_key())
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// Returns one row of `--format csv`. Uncracked inputs have an empty plaintext and path.
+fn csv_row(input: &str, result: Option<&DecoderResult>) -> String {
+    let (plaintext, decoders) = match result {
+        Some(result) => (result.text[0].clone(), decoder_path(result)),
+        None => (String::new(), String::new()),
+    };
+    [input, &plaintext, &decoders]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Quotes a CSV field if it contains a comma, quote or new line
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Prints a table of how many times each decoder ran and how long it took, for `--stats`.
+/// It goes to stderr so it doesn't get mixed up with the plaintext or `--format json`.
+pub fn decoder_stats(stats: &[DecoderStats]) {
+    if stats.is_empty() {
+        eprintln!("No decoders ran.");
+        return;
+    }
+    let name_width = stats
+        .iter()
+        .map(|decoder| decoder.name.len())
+        .max()
+        .unwrap_or_default()
+        .max("Decoder".len());
+    eprintln!(
+        "{:<name_width$}  {:>6}  {:>12}",
+        "Decoder", "Runs", "Time taken"
+    );
+    for decoder in stats {
+        eprintln!(
+            "{:<name_width$}  {:>6}  {:>12}",
+            decoder.name,
+            decoder.invocations,
+            format!("{:.2?}", decoder.time_taken)
+        );
+    }
+}
+
+/// When the search finishes without finding the plaintext, this says why.
+/// With `--format json` or `jsonl` it prints a [`JsonFailure`] for scripts,
+/// otherwise the closest attempts and, if it timed out, the best guess.
+/// # Panics
+/// Panics if the failure cannot be turned into JSON, which should never happen.
+pub fn search_failed(report: &CrackReport, time_taken: Duration) {
+    let config = crate::config::get_config();
+    if matches!(
+        config.output_format,
+        OutputFormat::Json | OutputFormat::Jsonl
+    ) {
+        let failure = JsonFailure {
+            failure: match report.outcome {
+                CrackOutcome::TimedOut { .. } => "timed_out",
+                CrackOutcome::Cancelled => "cancelled",
+                _ => "exhausted",
+            },
+            time_taken_ms: time_taken.as_millis(),
+            depth_reached: report.depth_reached,
+            depth_limit_hit: report.depth_limit_hit,
+            decodings_pruned: report.decodings_pruned,
+            decoders: report
+                .decoder_stats
+                .iter()
+                .map(|stats| DecoderAttempts {
+                    name: stats.name.clone(),
+                    invocations: stats.invocations,
+                })
+                .collect(),
+            closest_attempts: report
+                .closest_attempts
+                .iter()
+                .map(|attempt| ClosestAttempt {
+                    confidence: crate::searchers::plaintext_confidence(&attempt.text[0]),
+                    result: attempt,
+                })
+                .collect(),
+        };
+        println!(
+            "{}",
+            serde_json::to_string(&failure).expect("failures are valid JSON")
+        );
+        return;
+    }
+    failed_to_decode(report);
+    if let CrackOutcome::TimedOut {
+        best_guess: Some(best_guess),
+        ..
+    } = &report.outcome
+    {
+        best_guess_when_timed_out(best_guess);
+    }
 }
 
 /// When Ares has failed to decode something, print this message
-pub fn failed_to_decode() {
+/// with what the report says about how far the search got
+pub fn failed_to_decode(report: &CrackReport) {
     let config = crate::config::get_config();
     if config.api_mode {
         return;
     }
 
     println!("⛔️ Ares has failed to decode the text.");
+    if let Some(max_depth) = config.max_depth {
+        if report.depth_limit_hit {
+            println!("Ares stopped after chaining {max_depth} decoders because of the maximum depth, try a bigger --max-depth.");
+        }
+    }
+    let pruned = report.decodings_pruned;
+    if pruned > 0 {
+        println!("Ares forgot {pruned} decodings which looked least like plaintext to stay within --max-nodes and --max-memory, try raising them.");
+    }
+    if !report.closest_attempts.is_empty() {
+        println!("The closest attempts were:");
+        for attempt in &report.closest_attempts {
+            println!(
+                "  {:.0}% {} using {}",
+                crate::searchers::plaintext_confidence(&attempt.text[0]) * 100.0,
+                ansi_term::Colour::Yellow
+                    .bold()
+                    .paint(shortened(&attempt.text[0], CLOSEST_ATTEMPT_LENGTH)),
+                decoder_path(attempt)
+            );
+        }
+    }
     println!("If you want more help, please ask in #coded-messages in our Discord http://discord.skerritt.blog");
 }
+/// How many characters of each closest attempt we print when we fail to decode the text
+const CLOSEST_ATTEMPT_LENGTH: usize = 80;
+
+/// Cuts the text down to `length` characters, so long texts fit on one line
+fn shortened(text: &str, length: usize) -> String {
+    if text.chars().count() <= length {
+        return text.to_string();
+    }
+    let mut shortened: String = text.chars().take(length).collect();
+    shortened.push('…');
+    shortened
+}
+
 /// Calculate how long it would take to decode this in Ciphey
 fn calculate_time_took(decoded_times_int: u32) -> String {
     // TODO if we grab how long the programs been running for (see timer) we can make some nice stats like:
@@ -101,8 +660,7 @@ fn calculate_time_took(decoded_times_int: u32) -> String {
 /// Every second the timer ticks once
 /// If the timer hits our countdown, we exit the program.
 /// This function prints the countdown to let the user know the program is still running.
-pub fn countdown_until_program_ends(seconds_spent_running: u32, duration: u32) {
-    let config = crate::config::get_config();
+pub fn countdown_until_program_ends(seconds_spent_running: u32, duration: u32, config: &Config) {
     if config.api_mode {
         return;
     }
@@ -117,32 +675,229 @@ pub fn countdown_until_program_ends(seconds_spent_running: u32, duration: u32) {
 
 /// The input given to Ares is already plaintext
 /// So we do not need to do anything
-pub fn return_early_because_input_text_is_plaintext() {
-    let config = crate::config::get_config();
+pub fn return_early_because_input_text_is_plaintext(config: &Config) {
     if config.api_mode {
         return;
     }
     println!("Your input text is the plaintext 🥳");
 }
 
-/// The user has provided both textual input and file input
+/// Prints why the CLI can't carry on to stderr.
+/// This function is only used in the CLI.
+pub fn cli_error(message: &str) {
+    eprintln!("⛔️ {message}");
+}
+
+/// Prints why the CLI can't carry on to stderr and exits with `status`.
+/// This function is only used in the CLI.
+pub fn exit_with_error(status: ExitStatus, message: &str) -> ! {
+    cli_error(message);
+    status.exit()
+}
+
+/// The user has provided both textual input and file input.
+/// This function exits and is only used in the CLI.
+pub fn panic_failure_both_input_and_fail_provided() -> ! {
+    exit_with_error(
+        ExitStatus::InputError,
+        "Failed -- both file and text were provided. Please only use one.",
+    )
+}
+
+/// The user has not provided any input.
+/// This function exits and is only used in the CLI.
+pub fn panic_failure_no_input_provided() -> ! {
+    exit_with_error(
+        ExitStatus::InputError,
+        "Failed -- no input was provided. Please use -t for text or -f for files.",
+    )
+}
+
+/// Warns that a wordlist went over `--wordlist-max-memory`, so it is a Bloom filter
+/// which can match words that aren't in it. This goes to stderr as the output
+/// format isn't known until every argument is parsed.
+pub fn wordlist_is_a_bloom_filter(name: &str, false_positive_rate: f64) {
+    eprintln!(
+        "⚠️ The wordlist {} is too big for its memory limit, so about {:.2}% of texts which aren't in it will match it",
+        ansi_term::Colour::Yellow.bold().paint(name),
+        false_positive_rate * 100.0
+    );
+}
+
+/// Rings the terminal bell for `--bell`.
+/// This goes to stderr so it doesn't end up in the plaintext when stdout is piped.
+pub fn ring_bell() {
+    eprint!("\x07");
+    std::io::stderr().flush().ok();
+}
+
+/// Shows a desktop notification saying how Ares finished, for `--notify`.
+/// Tells the user on stderr if it couldn't be shown, like when there is no notification daemon.
+#[cfg(feature = "notify")]
+pub fn notify_finished(status: ExitStatus) {
+    let body = match status {
+        ExitStatus::Cracked => "Ares found the plaintext 🥳",
+        _ => "Ares could not find the plaintext",
+    };
+    if notify_rust::Notification::new()
+        .summary("Ares has finished")
+        .body(body)
+        .show()
+        .is_err()
+    {
+        eprintln!("⛔️ Could not show a desktop notification");
+    }
+}
+
+/// The file given with `--export-tree` could not be written.
+/// This goes to stderr so it doesn't get mixed up with the plaintext.
+pub fn search_tree_could_not_be_written(file: &str, error: std::io::Error) {
+    eprintln!(
+        "⚠️ Could not write the search tree because {}",
+        FileError::new(file, error)
+    );
+}
+
+/// A WASM plugin given with `--plugin` could not be loaded.
+/// This function exits and is only used in the CLI.
+#[cfg(feature = "wasm-plugins")]
+pub fn panic_failure_plugin_could_not_be_loaded(path: &str, error: &str) -> ! {
+    exit_with_error(
+        ExitStatus::ConfigError,
+        &format!("Failed -- could not load the WASM plugin {path}: {error}"),
+    )
+}
+
+/// What `ares list-decoders --json` prints for each decoder
+#[derive(Serialize)]
+pub(crate) struct DecoderListing<'a> {
+    /// The name of the decoder
+    name: &'a str,
+    /// The tags of the decoder, used by `--only` and `--exclude`
+    tags: &'a [&'a str],
+    /// How popular the decoder is, popular decoders run first
+    popularity: f32,
+    /// What kind of decoder it is, also used by `--only` and `--exclude`
+    metadata: DecoderMetadata,
+    /// A short description of the decoder
+    description: &'a str,
+    /// A link to more info about the decoder
+    link: &'a str,
+}
+
+/// Every decoder in the `DECODER_MAP`, sorted by name.
+/// `ares serve` returns these from `GET /decoders` too.
+pub(crate) fn decoder_listings() -> Vec<DecoderListing<'static>> {
+    let mut decoders: Vec<&SharedDecoder> = DECODER_MAP.values().collect();
+    decoders.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+    decoders
+        .iter()
+        .map(|decoder| DecoderListing {
+            name: decoder.get_name(),
+            tags: decoder.get_tags(),
+            popularity: decoder.get_popularity(),
+            metadata: decoder.get_metadata(),
+            description: decoder.get_description(),
+            link: decoder.get_link(),
+        })
+        .collect()
+}
+
+/// Prints every decoder in the `DECODER_MAP`, sorted by name.
+/// With `json` it prints a JSON array so scripts and the docs site can read it.
+/// This is only used by the `ares list-decoders` subcommand.
 /// # Panics
-/// This function panics and is only used in the CLI.
-pub fn panic_failure_both_input_and_fail_provided() {
-    let config = crate::config::get_config();
-    if config.api_mode {
+/// Panics if the listings cannot be turned into JSON, which should never happen.
+pub fn list_decoders(json: bool) {
+    let listings = decoder_listings();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&listings).expect("decoder listings are valid JSON")
+        );
         return;
     }
-    panic!("Failed -- both file and text were provided. Please only use one.")
+
+    for listing in listings {
+        println!(
+            "{} (popularity {})\ntags: {}\nkind: {}\n{}\n{}\n",
+            ansi_term::Colour::Yellow.bold().paint(listing.name),
+            listing.popularity,
+            listing.tags.join(", "),
+            listing.metadata.labels().join(", "),
+            listing.description,
+            listing.link
+        );
+    }
 }
 
-/// The user has not provided any input.
-/// # Panics
-/// This function panics and is only used in the CLI.
-pub fn panic_failure_no_input_provided() {
-    let config = crate::config::get_config();
-    if config.api_mode {
+/// Prints every output of `ares decode`, one per line so it can be piped into other tools.
+/// Exits with `ExitStatus::ConfigError` if there is no decoder with that name, as this is only used in the CLI.
+pub fn single_decoder_outputs(decoder_name: &str, outputs: Option<Vec<String>>) {
+    let Some(outputs) = outputs else {
+        exit_with_error(
+            ExitStatus::ConfigError,
+            &format!(
+                "Failed -- there is no decoder called {decoder_name}. Use `ares list-decoders` to see them all."
+            ),
+        )
+    };
+    if outputs.is_empty() {
+        eprintln!("⛔️ The {decoder_name} decoder could not decode the text.");
         return;
     }
-    panic!("Failed -- no input was provided. Please use -t for text or -f for files.")
+    for output in outputs {
+        println!("{output}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_field, csv_row, highlight_cribs, lemmeknow_details};
+    use crate::checkers::lemmeknow_checker::LemmeKnowMatch;
+    use crate::DecoderResult;
+
+    #[test]
+    fn cribs_are_highlighted_in_the_human_checker_prompt() {
+        let crib = ansi_term::Colour::Green.bold().underline();
+        let highlighted = highlight_cribs("the flag is flag{hi}", &["flag\\{.*\\}".to_string()]);
+        assert!(highlighted.contains(&crib.paint("flag{hi}").to_string()));
+        assert!(!highlighted.contains(&crib.paint("the flag is").to_string()));
+        assert_eq!(
+            highlight_cribs("hello", &[]),
+            ansi_term::Colour::Yellow.bold().paint("hello").to_string()
+        );
+    }
+
+    #[test]
+    fn lemmeknow_details_have_the_rarity_and_link() {
+        let mut found = LemmeKnowMatch {
+            name: "Bitcoin Wallet Address",
+            rarity: 0.5,
+            link: Some("https://www.blockchain.com/btc/address/"),
+        };
+        let details = lemmeknow_details(&found);
+        assert!(details.contains("(rarity 0.50)"));
+        assert!(details.ends_with(", see https://www.blockchain.com/btc/address/"));
+        found.link = None;
+        assert!(lemmeknow_details(&found).ends_with("(rarity 0.50)"));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("hello"), "hello");
+        assert_eq!(csv_field("hello, world"), "\"hello, world\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn csv_row_of_uncracked_input_is_empty() {
+        assert_eq!(csv_row("abc", None), "abc,,");
+        assert_eq!(
+            csv_row("abc", Some(&DecoderResult::_new("hello"))),
+            "abc,hello,Default decoder"
+        );
+    }
 }
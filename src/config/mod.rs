@@ -1,6 +1,15 @@
 /// import general checker
 use lemmeknow::Identifier;
-use once_cell::sync::OnceCell;
+
+pub use crate::checkers::composite::ExitCondition;
+pub use crate::checkers::human_checker::CandidateInfo;
+use crate::checkers::registry::CheckerRegistry;
+use crate::decoders::registry::DecoderRegistry;
+pub use crate::storage::wordlists::Wordlist;
+use crate::DecoderResult;
+use clap::ValueEnum;
+use once_cell::sync::{Lazy, OnceCell};
+use std::sync::Arc;
 
 /// Library input is the default API input
 /// The CLI turns its arguments into a LibraryInput struct
@@ -22,29 +31,234 @@ pub struct Config {
     /// Should the human checker be on?
     /// This asks yes/no for plaintext. Turn off for API
     pub human_checker_on: bool,
+    /// Asked instead of stdin when the human checker is on, so library users can confirm
+    /// plaintexts in their own UI. Returning true accepts the plaintext.
+    pub human_checker_callback: Option<HumanCheckerCallback>,
+    /// Plaintext we are more confident than this about, from 0.0 to 1.0, is accepted without
+    /// asking the human checker, while borderline plaintext is still asked about. None always asks.
+    /// The confidence is the one the checker which identified the plaintext gives it.
+    pub auto_accept_confidence: Option<f32>,
     /// The timeout threshold before Ares quites
-    /// This is in seconds
+    /// This is in seconds. 0 means there is no timeout, the search runs
+    /// until it has decoded everything it can or is cancelled.
     pub timeout: u32,
+    /// Ignore `timeout` and pick one from the length of the input and how many decoders are enabled,
+    /// so short texts fail fast and long ones get longer. The timeout picked is logged.
+    pub auto_timeout: bool,
     /// Is the program being run in API mode?
     /// This is used to determine if we should print to stdout
     /// Or return the values
     pub api_mode: bool,
-    /// Regex enables the user to search for a specific regex or crib
-    pub regex: Option<String>,
+    /// Regex enables the user to search for specific regexes or cribs.
+    /// The text is plaintext if any of them match. If this is empty the regex checker is off,
+    /// otherwise it is the only checker used, by the search, top results mode and the input check alike,
+    /// unless there is an `exit_condition`.
+    pub regex: Vec<String>,
+    /// Combines checkers into one exit condition, like "regex || wordlist" or "flag && english",
+    /// see [`ExitCondition`]. When this is set it decides what is plaintext instead of the other checkers.
+    pub exit_condition: Option<ExitCondition>,
+    /// Flag formats to look for on top of the known ones (flag, CTF, HTB and picoCTF).
+    /// A format like "DUCTF" matches flags like `DUCTF{...}`.
+    /// The Flag Checker only runs when this isn't empty, or when an exit condition names it,
+    /// so even the known formats are only looked for then. `vec!["flag".into()]` looks for just the known ones.
+    pub flag_formats: Vec<String>,
+    /// Wordlists the plaintext could be a word from, see [`Wordlist`].
+    /// If any are enabled the Wordlist Checker is used instead of the other checkers.
+    pub wordlists: Vec<Wordlist>,
+    /// How close the text has to be to a word in a wordlist, see [`WordlistMatching`]
+    pub wordlist_matching: WordlistMatching,
+    /// Only use checkers with these names or tags, like "lemmeknow" or "english".
+    /// If this is empty, every checker is used.
+    pub enabled_checkers: Vec<String>,
+    /// Never use checkers with these names or tags, like "lemmeknow" when it is too noisy
+    /// or "english" when the plaintext is in another language.
+    /// This is applied after `enabled_checkers`.
+    pub disabled_checkers: Vec<String>,
+    /// Only use decoders with these names or tags, like "base64" or "base".
+    /// If this is empty, every decoder is used.
+    pub enabled_decoders: Vec<String>,
+    /// Never use decoders with these names or tags.
+    /// This is applied after `enabled_decoders`.
+    pub disabled_decoders: Vec<String>,
+    /// Custom decoders registered by library users.
+    /// These are used alongside the built in decoders.
+    pub decoder_registry: DecoderRegistry,
+    /// Custom checkers registered by library users.
+    /// Athena runs these before or after the built in checkers, depending on their priority.
+    pub checker_registry: CheckerRegistry,
+    /// How the CLI prints its results, see [`OutputFormat`]
+    pub output_format: OutputFormat,
+    /// Top results mode keeps searching after the first plaintext is found,
+    /// collecting every plaintext until the timeout so the user can pick the right one.
+    pub top_results: bool,
+    /// In top results mode, stop searching once this many different plaintexts have been found.
+    /// None keeps searching until the timeout or until there is nothing left to decode.
+    pub top_results_limit: Option<usize>,
+    /// Called with each new plaintext as it is found in top results mode,
+    /// so library users can stream them instead of waiting for the search to end.
+    pub top_result_callback: Option<TopResultCallback>,
+    /// A CyberChef recipe as JSON. If this is set we replay the recipe
+    /// on the input instead of searching, see the `cyberchef` module.
+    pub recipe: Option<String>,
+    /// Write the plaintext to this file when the CLI finds it, in the `output_format`.
+    /// In the pretty format decoded files like PNGs are written as their bytes.
+    pub output_file: Option<String>,
+    /// What to do when `output_file` already exists, see [`OutputFileMode`]
+    pub output_file_mode: OutputFileMode,
+    /// Copy the plaintext to the clipboard when the CLI finds it.
+    /// Only used when Ares is built with the `clipboard` feature.
+    pub clipboard_out: bool,
+    /// Keep decoding the plaintext after it is found, until it stops decoding into another plaintext.
+    /// Useful when a plaintext is itself still encoded, like base64 revealing hex.
+    pub keep_going: bool,
+    /// The most decoders the search will chain together, None means there is no limit.
+    /// This stops pathological deep chains and makes runs reproducible.
+    pub max_depth: Option<u32>,
+    /// The most decodings the search keeps waiting to be decoded, None means there is no limit.
+    /// Past this the decodings which look least like plaintext are forgotten.
+    pub max_nodes: Option<usize>,
+    /// Roughly how many bytes of text the decodings waiting to be decoded can use, None means there is no limit.
+    /// Past this the decodings which look least like plaintext are forgotten.
+    pub max_memory: Option<usize>,
+    /// How the searcher explores the decodings, see [`SearchStrategy`]
+    pub search_strategy: SearchStrategy,
+    /// Decodings with fewer printable characters than this, from 0.0 to 1.0,
+    /// look like random binary and are not decoded any further.
+    pub min_printable_ratio: f32,
+    /// Decodings with more Shannon entropy than this, in bits per byte up to 8.0,
+    /// look like random binary and are not decoded any further.
+    pub max_entropy: f32,
+    /// How many decoder results the search remembers, so when two paths reach the same text
+    /// we reuse what each decoder gave for it instead of running the decoder again.
+    /// The least recently used results are forgotten first. 0 turns the cache off.
+    pub decoding_cache_size: usize,
+    /// How many of the texts one decoder returns are decoded further, the ones which look most like English.
+    /// Decoders which try every key return lots of texts, like Caesar which returns 25.
+    /// None decodes every text further, the ones which look most like English first,
+    /// as the right text doesn't always look the most like English.
+    pub candidates_per_decoder: Option<usize>,
+    /// How many threads each search runs its decoders on, in a rayon pool of its own.
+    /// None uses rayon's global pool, which has a thread for every CPU.
+    pub threads: Option<usize>,
+    /// Print how many times each decoder ran and how long it took at the end of the run
+    pub stats: bool,
+    /// Ring the terminal bell when the CLI has finished, so users who switched away during a long search know
+    pub bell: bool,
+    /// Show a desktop notification when the CLI has finished, like `bell`.
+    /// Only used when Ares is built with the `notify` feature.
+    pub notify: bool,
+    /// Handle the results of the decoders in the order the decoders are sorted in, rather than
+    /// the order they finish in, so the same input always finds the same plaintext by the same path.
+    /// Every decoder runs even after one finds the plaintext, so this is slower.
+    pub deterministic: bool,
+    /// Write every decoding the search explored to this file at the end of the run,
+    /// as Graphviz DOT if it ends in `.dot` and JSON otherwise.
+    /// The search only records the tree when this is set, see [`crate::CrackReport::search_tree`].
+    pub export_search_tree: Option<String>,
+    /// Save where the breadth first or beam search is to this file after each depth,
+    /// so it can be resumed with `resume` if it is interrupted
+    pub checkpoint: Option<String>,
+    /// Carry on the breadth first or beam search saved in this checkpoint file
+    /// instead of starting from the input, see `checkpoint`
+    pub resume: Option<String>,
+    /// Print secrets like private keys and AWS access keys in full.
+    /// They are redacted by default so they don't end up in terminal scrollback or screenshots.
+    pub show_secrets: bool,
+}
+
+/// Decides whether a possible plaintext is the real one, see [`Config::human_checker_callback`]
+pub type HumanCheckerCallback = Arc<dyn Fn(&CandidateInfo) -> bool + Send + Sync>;
+
+/// Told about each plaintext found in top results mode, see [`Config::top_result_callback`]
+pub type TopResultCallback = Arc<dyn Fn(&DecoderResult) + Send + Sync>;
+
+/// The formats the CLI can print its results in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Coloured, human readable output
+    #[default]
+    Pretty,
+    /// The full `DecoderResult` as JSON, for scripts
+    Json,
+    /// One JSON object per line.
+    /// In top results mode every plaintext is printed as soon as it is found.
+    Jsonl,
+    /// Comma separated values with the input, plaintext and decoders used.
+    /// Mostly useful in batch mode, where there is one row per input.
+    Csv,
+}
+
+/// What to do when `Config.output_file` already exists
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFileMode {
+    /// Don't write to it, so results are never lost by accident
+    #[default]
+    CreateNew,
+    /// Replace what is in it
+    Overwrite,
+    /// Add to the end of it, useful for collecting the results of many runs
+    Append,
 }
 
-/// Cell for storing global Config
-static CONFIG: OnceCell<Config> = OnceCell::new();
+/// How the searcher explores the decodings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchStrategy {
+    /// Breadth first search, every decoding at a depth is tried before going deeper
+    #[default]
+    Bfs,
+    /// Like breadth first search, but only the `width` most promising decodings
+    /// at each depth are kept. Much faster on huge inputs, but it can miss the plaintext.
+    Beam {
+        /// How many decodings to keep at each depth
+        width: usize,
+    },
+    /// Searches every decoding 1 decoder deep, then 2, then 3... and after each sweep
+    /// reports the most likely decoding so far, so short timeouts still show something useful.
+    IterativeDeepening,
+}
+
+/// How the Wordlist Checker compares the text to the words in the wordlists
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WordlistMatching {
+    /// The text has to be exactly one of the words
+    #[default]
+    Exact,
+    /// Case and whitespace are ignored, so "Hunter 2" matches "hunter2"
+    Normalised,
+    /// Like `Normalised`, but the text can also be up to `max_distance` edits
+    /// (Levenshtein distance) away from a word. This finds near misses, like a
+    /// Caesar decoding with one character shifted wrong.
+    Fuzzy {
+        /// The most single character insertions, deletions or substitutions allowed
+        max_distance: usize,
+    },
+    /// The text only has to contain one of the words, so "the password is hunter2" matches "hunter2".
+    /// This uses an Aho-Corasick automaton, so it stays fast with huge wordlists and long texts.
+    Substring,
+}
+
+/// Cell for storing global Config.
+/// Cracking passes its config through the searchers and checkers, so this is only
+/// a shim for the CLI, whose printers read the config it parsed from here.
+static CONFIG: OnceCell<Arc<Config>> = OnceCell::new();
+
+/// The default config, shared by checkers which are not given one
+static DEFAULT_CONFIG: Lazy<Arc<Config>> = Lazy::new(|| Arc::new(Config::default()));
 
 /// To initialize global config with custom values
-pub fn set_global_config(config: Config) {
-    CONFIG.set(config).ok(); // ok() used to make compiler happy about using Result
+pub fn set_global_config(config: impl Into<Arc<Config>>) {
+    CONFIG.set(config.into()).ok(); // ok() used to make compiler happy about using Result
 }
 
 /// Get the global config.
 /// This will return default config if the config wasn't already initialized
 pub fn get_config() -> &'static Config {
-    CONFIG.get_or_init(Config::default)
+    CONFIG.get_or_init(default_config)
+}
+
+/// Returns the default config, which checkers use until they are given the config of a run
+pub fn default_config() -> Arc<Config> {
+    DEFAULT_CONFIG.clone()
 }
 
 /// Creates a default lemmeknow config
@@ -63,9 +277,48 @@ impl Default for Config {
             verbose: 0,
             lemmeknow_config: LEMMEKNOW_DEFAULT_CONFIG,
             human_checker_on: false,
+            human_checker_callback: None,
+            auto_accept_confidence: None,
             timeout: 5,
+            auto_timeout: false,
             api_mode: true,
-            regex: None,
+            regex: vec![],
+            exit_condition: None,
+            flag_formats: vec![],
+            wordlists: vec![],
+            wordlist_matching: WordlistMatching::Exact,
+            enabled_checkers: vec![],
+            disabled_checkers: vec![],
+            enabled_decoders: vec![],
+            disabled_decoders: vec![],
+            decoder_registry: DecoderRegistry::new(),
+            checker_registry: CheckerRegistry::new(),
+            output_format: OutputFormat::Pretty,
+            top_results: false,
+            top_results_limit: None,
+            top_result_callback: None,
+            recipe: None,
+            output_file: None,
+            output_file_mode: OutputFileMode::CreateNew,
+            clipboard_out: false,
+            keep_going: false,
+            max_depth: None,
+            max_nodes: None,
+            max_memory: None,
+            search_strategy: SearchStrategy::Bfs,
+            min_printable_ratio: 0.7,
+            max_entropy: 7.0,
+            decoding_cache_size: 10_000,
+            candidates_per_decoder: None,
+            threads: None,
+            stats: false,
+            bell: false,
+            notify: false,
+            deterministic: false,
+            export_search_tree: None,
+            checkpoint: None,
+            resume: None,
+            show_secrets: false,
         }
     }
 }
@@ -0,0 +1,224 @@
+//! Replays CyberChef recipes with our decoders.
+//! CyberChef (https://gchq.github.io/CyberChef/) can save a recipe as JSON, like:
+//! `[{"op": "From Base64", "args": ["A-Za-z0-9+/=", true]}, {"op": "ROT13", "args": [true, true, false, 13]}]`
+//! With `--recipe file.json` we run each operation in order on the input, without searching.
+//! This lets users check a solution someone has shared with them.
+
+use std::fmt;
+use std::sync::Arc;
+
+use log::trace;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::checkers::{
+    checker_type::{Check, Checker},
+    default_checker::DefaultChecker,
+    CheckerTypes,
+};
+use crate::decoders::caesar_decoder::{caesar, CaesarDecoder};
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{Crack, Decoder};
+use crate::decoders::railfence_decoder::{railfence_decoder, RailfenceDecoder};
+use crate::decoders::DECODER_MAP;
+use crate::DecoderResult;
+
+/// One operation of a CyberChef recipe
+#[derive(Deserialize)]
+struct Operation {
+    /// The name of the operation, like "From Base64"
+    op: String,
+    /// The arguments of the operation, like the alphabet or the key
+    #[serde(default)]
+    args: Vec<Value>,
+}
+
+/// The ways replaying a recipe can fail
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecipeError {
+    /// The recipe is not valid CyberChef JSON
+    InvalidRecipe(String),
+    /// Ares has no decoder for this CyberChef operation
+    UnsupportedOperation(String),
+    /// The operation could not decode the text it was given
+    OperationFailed(String),
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::InvalidRecipe(error) => {
+                write!(f, "the recipe is not a valid CyberChef recipe: {error}")
+            }
+            RecipeError::UnsupportedOperation(op) => {
+                write!(f, "Ares does not support the CyberChef operation {op}")
+            }
+            RecipeError::OperationFailed(op) => write!(f, "{op} could not decode the text"),
+        }
+    }
+}
+
+/// What we run for each CyberChef operation
+enum Step {
+    /// Runs the decoder with this name from the `DECODER_MAP`
+    Decoder(&'static str),
+    /// Rotates the text forwards by this many letters
+    Caesar(u8),
+    /// Decodes a Rail Fence cipher with this many rails and this offset
+    Railfence(usize, usize),
+}
+
+/// Replays the CyberChef recipe on the text and returns the final text
+/// along with a `CrackResult` for each operation, just like a normal search would.
+/// # Errors
+/// Returns an error if the recipe is not valid JSON, uses an operation we do not support
+/// or one of the operations fails to decode its input.
+pub fn replay_recipe(recipe: &str, text: &str) -> Result<DecoderResult, RecipeError> {
+    let operations: Vec<Operation> = serde_json::from_str(recipe)
+        .map_err(|error| RecipeError::InvalidRecipe(error.to_string()))?;
+
+    let mut current_text = text.to_string();
+    let mut path: Vec<Arc<CrackResult>> = Vec::with_capacity(operations.len());
+    for operation in &operations {
+        trace!("Replaying CyberChef operation {}", operation.op);
+        let mut result = run_step(operation_to_step(operation)?, &current_text);
+        current_text = result
+            .unencrypted_text
+            .as_ref()
+            .and_then(|outputs| outputs.first().cloned())
+            .ok_or_else(|| RecipeError::OperationFailed(operation.op.clone()))?;
+        result.unencrypted_text = Some(vec![current_text.clone()]);
+        if let Some(first) = result.candidates.drain(..).next() {
+            result.key = first.key;
+        }
+        path.push(Arc::new(result));
+    }
+
+    Ok(DecoderResult {
+        text: vec![current_text],
+        path,
+        // Nothing checked the output, we only replayed the recipe
+        confidence: 0.0,
+    })
+}
+
+/// Works out which of our decoders matches the CyberChef operation
+fn operation_to_step(operation: &Operation) -> Result<Step, RecipeError> {
+    let first_arg = operation.args.first().and_then(Value::as_str).unwrap_or("");
+    let step = match operation.op.as_str() {
+        "From Base64" if first_arg.contains("-_") => Step::Decoder("Base64 URL"),
+        "From Base64" => Step::Decoder("Base64"),
+        "From Base32" => Step::Decoder("Base32"),
+        "From Base58" if first_arg.starts_with("rpshnaf") => Step::Decoder("Base58 Ripple"),
+        "From Base58" if first_arg.starts_with("123456789abc") => Step::Decoder("Base58 Flickr"),
+        "From Base58" => Step::Decoder("Base58 Bitcoin"),
+        "From Base91" => Step::Decoder("Base91"),
+        "From Hex" => Step::Decoder("Hexadecimal"),
+        "From Binary" => Step::Decoder("Binary"),
+        "From Morse Code" => Step::Decoder("Morse Code"),
+        "URL Decode" => Step::Decoder("URL"),
+        "Reverse" => Step::Decoder("Reverse"),
+        "Atbash Cipher" => Step::Decoder("Atbash"),
+        "A1Z26 Cipher Decode" => Step::Decoder("A1Z26"),
+        "Citrix CTX1 Decode" => Step::Decoder("Citrix Ctx1"),
+        "ROT13" => {
+            // The amount is the last argument, older versions of CyberChef have fewer arguments before it
+            let amount = operation
+                .args
+                .iter()
+                .rev()
+                .find_map(Value::as_i64)
+                .unwrap_or(13);
+            Step::Caesar(amount.rem_euclid(26) as u8)
+        }
+        "Rail Fence Cipher Decode" => {
+            let key = operation.args.first().and_then(Value::as_u64).unwrap_or(2);
+            let offset = operation.args.get(1).and_then(Value::as_u64).unwrap_or(0);
+            if key < 2 {
+                return Err(RecipeError::OperationFailed(operation.op.clone()));
+            }
+            Step::Railfence(key as usize, offset as usize)
+        }
+        _ => return Err(RecipeError::UnsupportedOperation(operation.op.clone())),
+    };
+    Ok(step)
+}
+
+/// Runs one step on the text.
+/// No checkers run, we only want the output of the decoder.
+fn run_step(step: Step, text: &str) -> CrackResult {
+    match step {
+        Step::Decoder(name) => {
+            let checker = CheckerTypes::CheckDefault(Checker::<DefaultChecker>::new());
+            DECODER_MAP
+                .get(name)
+                .expect("every decoder a recipe uses is in the DECODER_MAP")
+                .crack(text, &checker)
+        }
+        Step::Caesar(shift) => {
+            let mut result = CrackResult::new(&Decoder::<CaesarDecoder>::new(), text.to_string());
+            result.unencrypted_text = Some(vec![caesar(text, shift)]);
+            result.key = Some(format!("shift={shift}"));
+            result
+        }
+        Step::Railfence(rails, offset) => {
+            let mut result =
+                CrackResult::new(&Decoder::<RailfenceDecoder>::new(), text.to_string());
+            result.unencrypted_text = Some(vec![railfence_decoder(text, rails, offset)]);
+            result.key = Some(format!("rails={rails}, offset={offset}"));
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_a_chain_of_decoders() {
+        // "hello world" -> ROT13 -> To Base64
+        let recipe = r#"[
+            {"op": "From Base64", "args": ["A-Za-z0-9+/=", true]},
+            {"op": "ROT13", "args": [true, true, false, 13]}
+        ]"#;
+        let result = replay_recipe(recipe, "dXJ5eWIgamJleXE=").unwrap();
+        assert_eq!(result.text[0], "hello world");
+        let decoders: Vec<&str> = result.path.iter().map(|c| c.decoder).collect();
+        assert_eq!(decoders, vec!["Base64", "Caesar Cipher"]);
+    }
+
+    #[test]
+    fn replays_railfence_with_its_key() {
+        let recipe = r#"[{"op": "Rail Fence Cipher Decode", "args": [3, 0]}]"#;
+        let result = replay_recipe(recipe, "Hoo!el,Wrdl l").unwrap();
+        assert_eq!(result.text[0], "Hello, World!");
+        assert_eq!(result.path[0].key.as_deref(), Some("rails=3, offset=0"));
+    }
+
+    #[test]
+    fn unsupported_operations_are_an_error() {
+        let recipe = r#"[{"op": "AES Decrypt", "args": []}]"#;
+        assert_eq!(
+            replay_recipe(recipe, "hello").unwrap_err(),
+            RecipeError::UnsupportedOperation("AES Decrypt".to_string())
+        );
+    }
+
+    #[test]
+    fn failing_operations_are_an_error() {
+        let recipe = r#"[{"op": "From Hex", "args": ["Auto"]}]"#;
+        assert_eq!(
+            replay_recipe(recipe, "not hex at all").unwrap_err(),
+            RecipeError::OperationFailed("From Hex".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert!(matches!(
+            replay_recipe("not json", "hello"),
+            Err(RecipeError::InvalidRecipe(_))
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
 use regex::Regex;
@@ -19,6 +20,7 @@ impl Crack for Decoder<A1Z26Decoder> {
             link: "https://dadstuffsite.com/a1z26-cipher-what-it-is-and-how-to-teach-your-kids/",
             tags: vec!["A1Z26", "substitution", "decoder"],
             popularity: 0.5,
+            expected_runtime: 0.05,
             phantom: std::marker::PhantomData,
         }
     }
@@ -79,6 +81,31 @@ impl Crack for Decoder<A1Z26Decoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Digits,
+            runtime: RuntimeClass::Medium,
+        }
+    }
 }
 
 /// This function does the actual decoding
@@ -4,6 +4,7 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{info, trace};
 
@@ -18,6 +19,7 @@ impl Crack for Decoder<AtbashDecoder> {
             link: "https://en.wikipedia.org/wiki/Atbash",
             tags: vec!["atbash", "substitution", "decoder", "reciprocal"],
             popularity: 1.0,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -55,6 +57,31 @@ impl Crack for Decoder<AtbashDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Cipher,
+            reversibility: Reversibility::SelfInverse,
+            charset: Charset::Letters,
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 /// Maps atbash to the alphabet
@@ -0,0 +1,549 @@
+//! Extract Morse code and DTMF tones from WAV audio
+//! Performs error handling and returns a string
+//! Call audio_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! This decoder is only compiled with the `audio` feature.
+//! It accepts WAV files as data URIs, the CLI turns `--file audio.wav` into one for us.
+
+use crate::checkers::CheckerTypes;
+use base64::{engine::general_purpose, Engine as _};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
+
+use log::{debug, trace};
+
+/// The data URI prefixes we accept for WAV files.
+/// The first one is what the CLI uses when given a `.wav` file.
+pub const WAV_DATA_URI_PREFIXES: [&str; 3] = [
+    "data:audio/wav;base64,",
+    "data:audio/x-wav;base64,",
+    "data:audio/wave;base64,",
+];
+
+/// DTMF rows, the low frequency group in Hz
+const DTMF_LOW_FREQUENCIES: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+/// DTMF columns, the high frequency group in Hz
+const DTMF_HIGH_FREQUENCIES: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+/// The keypad, indexed by [low frequency][high frequency]
+const DTMF_KEYPAD: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+/// Length of each block we run Goertzel over when looking for DTMF, in milliseconds.
+/// 25ms is long enough to tell apart the 2 closest DTMF frequencies.
+const DTMF_BLOCK_MS: u32 = 25;
+/// Length of each block when looking for Morse, in milliseconds.
+/// Morse needs finer timing than DTMF, but only has 1 frequency to find.
+const MORSE_BLOCK_MS: u32 = 10;
+/// Blocks quieter than this (on a scale of 0 to 1) are treated as silence
+const SILENCE_THRESHOLD: f32 = 0.01;
+/// The highest sample rate we accept, in Hz. Studio audio tops out at 384kHz,
+/// so anything above this is a corrupt or crafted header.
+const MAX_SAMPLE_RATE: u32 = 768_000;
+
+/// The audio decoder, call:
+/// `let audio_decoder = Decoder::<AudioDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = audio_decoder.crack(input)` to turn a WAV data URI into Morse code or DTMF digits
+/// The struct generated by new() comes from interface.rs
+pub struct AudioDecoder;
+
+impl Crack for Decoder<AudioDecoder> {
+    fn new() -> Decoder<AudioDecoder> {
+        Decoder {
+            name: "Audio Morse / DTMF",
+            description: "Listens to a WAV file for Morse code tones or DTMF (touch-tone telephone) digits using the Goertzel algorithm and turns them into text.",
+            link: "https://en.wikipedia.org/wiki/Goertzel_algorithm",
+            tags: vec!["audio", "morseCode", "dtmf", "signals", "decoder"],
+            popularity: 0.3,
+            expected_runtime: 0.5,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying audio decoding with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let audio = match decode_wav_data_uri(text) {
+            Some(audio) => audio,
+            None => {
+                debug!("Failed to decode audio because the input is not a WAV data URI");
+                return results;
+            }
+        };
+
+        let candidates: Vec<String> = [extract_dtmf(&audio), extract_morse(&audio)]
+            .into_iter()
+            .flatten()
+            .collect();
+        if candidates.is_empty() {
+            debug!("Failed to find any Morse code or DTMF tones in the audio");
+            return results;
+        }
+
+        for candidate in &candidates {
+            let checker_result = checker.check(candidate);
+            if checker_result.is_identified {
+                results.unencrypted_text = Some(vec![candidate.to_string()]);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        }
+
+        // The symbols are usually another encoding (Morse), so the search carries on with them
+        results.unencrypted_text = Some(candidates);
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Media,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Printable,
+            runtime: RuntimeClass::Slow,
+        }
+    }
+}
+
+/// Mono audio with samples between -1 and 1
+struct Audio {
+    /// How many samples there are per second
+    sample_rate: u32,
+    /// The samples, with all channels mixed down into one
+    samples: Vec<f32>,
+}
+
+/// Turns a WAV data URI into mono audio.
+/// Returns None if it is not a WAV data URI, or the WAV is not 8 or 16 bit PCM.
+fn decode_wav_data_uri(text: &str) -> Option<Audio> {
+    let text = text.trim();
+    let encoded = WAV_DATA_URI_PREFIXES
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))?;
+    let bytes = general_purpose::STANDARD.decode(encoded.as_bytes()).ok()?;
+    parse_wav(&bytes)
+}
+
+/// Parses the RIFF chunks of a WAV file.
+fn parse_wav(bytes: &[u8]) -> Option<Audio> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return None;
+    }
+
+    // (channels, sample rate, bits per sample)
+    let mut format: Option<(u16, u32, u16)> = None;
+    let mut data: Option<&[u8]> = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let chunk_id = &bytes[offset..offset + 4];
+        let chunk_size =
+            u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().ok()?) as usize;
+        let chunk_start = offset + 8;
+        let chunk_end = chunk_start.checked_add(chunk_size)?.min(bytes.len());
+        let chunk = &bytes[chunk_start..chunk_end];
+
+        if chunk_id == b"fmt " && chunk.len() >= 16 {
+            let audio_format = u16::from_le_bytes([chunk[0], chunk[1]]);
+            // 1 is uncompressed PCM, which is all we support
+            if audio_format != 1 {
+                return None;
+            }
+            let channels = u16::from_le_bytes([chunk[2], chunk[3]]);
+            let sample_rate = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+            let bits_per_sample = u16::from_le_bytes([chunk[14], chunk[15]]);
+            format = Some((channels, sample_rate, bits_per_sample));
+        } else if chunk_id == b"data" {
+            data = Some(chunk);
+        }
+        // Chunks are padded to an even number of bytes
+        offset = chunk_start + chunk_size + (chunk_size % 2);
+    }
+
+    let (channels, sample_rate, bits_per_sample) = format?;
+    let data = data?;
+    if channels == 0 || sample_rate == 0 || sample_rate > MAX_SAMPLE_RATE {
+        return None;
+    }
+
+    let samples: Vec<f32> = match bits_per_sample {
+        // 8 bit WAV is unsigned
+        8 => data.iter().map(|&s| (s as f32 - 128.0) / 128.0).collect(),
+        16 => data
+            .chunks_exact(2)
+            .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0)
+            .collect(),
+        _ => return None,
+    };
+
+    // Mix all channels down to mono
+    let samples = samples
+        .chunks_exact(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+
+    Some(Audio {
+        sample_rate,
+        samples,
+    })
+}
+
+/// The Goertzel algorithm tells us how much of one frequency is in a block of samples.
+/// It's like computing a single bin of a Fourier transform, which is all we need.
+/// Returns the amplitude of the frequency, on the same scale as the samples.
+fn goertzel(samples: &[f32], sample_rate: u32, frequency: f32) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let omega = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+    let coefficient = 2.0 * omega.cos();
+    let mut previous = 0.0;
+    let mut previous2 = 0.0;
+    for &sample in samples {
+        let current = sample + coefficient * previous - previous2;
+        previous2 = previous;
+        previous = current;
+    }
+    let power = previous2 * previous2 + previous * previous - coefficient * previous * previous2;
+    2.0 * power.max(0.0).sqrt() / samples.len() as f32
+}
+
+/// Root mean square, how loud a block of samples is
+fn root_mean_square(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Splits the audio into blocks of the given length in milliseconds
+fn blocks(audio: &Audio, block_ms: u32) -> std::slice::Chunks<'_, f32> {
+    // In u64 so a sample rate from a crafted header can't overflow
+    let block_size = (audio.sample_rate as u64 * block_ms as u64 / 1000).max(1) as usize;
+    audio.samples.chunks(block_size)
+}
+
+/// Returns the index and amplitude of the loudest frequency,
+/// but only if it is clearly louder than the rest of its group.
+fn strongest_frequency(
+    samples: &[f32],
+    sample_rate: u32,
+    frequencies: &[f32],
+) -> Option<(usize, f32)> {
+    let mut amplitudes: Vec<(usize, f32)> = frequencies
+        .iter()
+        .map(|&f| goertzel(samples, sample_rate, f))
+        .enumerate()
+        .collect();
+    amplitudes.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let (index, amplitude) = amplitudes[0];
+    if amplitudes[1].1 > amplitude / 2.0 {
+        return None;
+    }
+    Some((index, amplitude))
+}
+
+/// Finds the DTMF key pressed in a block of samples, if any
+fn detect_dtmf_key(samples: &[f32], sample_rate: u32) -> Option<char> {
+    let loudness = root_mean_square(samples);
+    if loudness < SILENCE_THRESHOLD {
+        return None;
+    }
+    let (row, low_amplitude) = strongest_frequency(samples, sample_rate, &DTMF_LOW_FREQUENCIES)?;
+    let (column, high_amplitude) =
+        strongest_frequency(samples, sample_rate, &DTMF_HIGH_FREQUENCIES)?;
+    // A real key press is made of just these 2 tones, so they should carry most of the energy
+    if low_amplitude < loudness / 2.0 || high_amplitude < loudness / 2.0 {
+        return None;
+    }
+    Some(DTMF_KEYPAD[row][column])
+}
+
+/// Turns DTMF tones into the digits that were pressed.
+/// A key held across many blocks is only counted once.
+fn extract_dtmf(audio: &Audio) -> Option<String> {
+    let mut digits = String::new();
+    let mut previous_key = None;
+    for block in blocks(audio, DTMF_BLOCK_MS) {
+        let key = detect_dtmf_key(block, audio.sample_rate);
+        if let Some(key) = key {
+            if previous_key != Some(key) {
+                digits.push(key);
+            }
+        }
+        previous_key = key;
+    }
+    trace!("Found DTMF digits {:?}", digits);
+    if digits.is_empty() {
+        return None;
+    }
+    Some(digits)
+}
+
+/// Turns Morse code tones into dots and dashes, with spaces between letters
+/// and ` / ` between words, which is what the Morse code decoder understands.
+fn extract_morse(audio: &Audio) -> Option<String> {
+    let tone = find_morse_tone(audio)?;
+    let amplitudes: Vec<f32> = blocks(audio, MORSE_BLOCK_MS)
+        .map(|block| goertzel(block, audio.sample_rate, tone))
+        .collect();
+    let loudest = amplitudes.iter().cloned().fold(0.0, f32::max);
+    if loudest < SILENCE_THRESHOLD {
+        return None;
+    }
+
+    // Run length encode the on / off keying, (is the tone on, for how many blocks)
+    let mut runs: Vec<(bool, usize)> = Vec::new();
+    for amplitude in amplitudes {
+        let on = amplitude > loudest / 2.0;
+        match runs.last_mut() {
+            Some((last_on, length)) if *last_on == on => *length += 1,
+            _ => runs.push((on, 1)),
+        }
+    }
+
+    // The shortest tone is a dot, which is 1 unit of time in Morse
+    let unit = runs
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, length)| *length)
+        .min()?;
+
+    let mut morse = String::new();
+    for (on, length) in runs {
+        if on {
+            // Dashes are 3 units long
+            morse.push(if length >= 2 * unit { '-' } else { '.' });
+        } else if morse.is_empty() {
+            // Silence before the first tone
+            continue;
+        } else if length >= 5 * unit {
+            // Gaps between words are 7 units long
+            morse.push_str(" / ");
+        } else if length >= 2 * unit {
+            // Gaps between letters are 3 units long
+            morse.push(' ');
+        }
+    }
+    let morse = morse.trim_end_matches([' ', '/']).to_string();
+    trace!("Found Morse code {:?} at {}Hz", morse, tone);
+    Some(morse)
+}
+
+/// Morse can be sent at any pitch, so we find the loudest frequency in the
+/// loudest block of the audio and assume that is the tone.
+fn find_morse_tone(audio: &Audio) -> Option<f32> {
+    let loudest_block = blocks(audio, DTMF_BLOCK_MS)
+        .max_by(|a, b| root_mean_square(a).total_cmp(&root_mean_square(b)))?;
+    if root_mean_square(loudest_block) < SILENCE_THRESHOLD {
+        return None;
+    }
+    // Morse is usually sent somewhere between 300Hz and 1500Hz
+    (300..=1500).step_by(10).map(|f| f as f32).max_by(|&a, &b| {
+        goertzel(loudest_block, audio.sample_rate, a).total_cmp(&goertzel(
+            loudest_block,
+            audio.sample_rate,
+            b,
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    const SAMPLE_RATE: u32 = 8000;
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    // helper which appends `ms` milliseconds of the given frequencies (or silence) to the samples
+    fn push_tone(samples: &mut Vec<i16>, frequencies: &[f32], ms: u32) {
+        let start = samples.len();
+        for i in 0..(SAMPLE_RATE * ms / 1000) as usize {
+            let t = (start + i) as f32 / SAMPLE_RATE as f32;
+            let value: f32 = frequencies
+                .iter()
+                .map(|f| (2.0 * std::f32::consts::PI * f * t).sin() * 0.4)
+                .sum();
+            samples.push((value * i16::MAX as f32) as i16);
+        }
+    }
+
+    // helper which wraps 16 bit mono samples in a WAV file and returns it as a data URI
+    fn wav_data_uri(samples: &[i16]) -> String {
+        wav_data_uri_at(samples, SAMPLE_RATE)
+    }
+
+    // helper like `wav_data_uri` which says the samples were recorded at the given sample rate
+    fn wav_data_uri_at(samples: &[i16], sample_rate: u32) -> String {
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut wav: Vec<u8> = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.wrapping_mul(2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+        format!(
+            "{}{}",
+            WAV_DATA_URI_PREFIXES[0],
+            general_purpose::STANDARD.encode(wav)
+        )
+    }
+
+    #[test]
+    fn goertzel_finds_its_frequency() {
+        let mut samples = Vec::new();
+        push_tone(&mut samples, &[1000.0], 50);
+        let samples: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        assert!(goertzel(&samples, SAMPLE_RATE, 1000.0) > 0.3);
+        assert!(goertzel(&samples, SAMPLE_RATE, 1500.0) < 0.05);
+    }
+
+    #[test]
+    fn extracts_dtmf_digits() {
+        let mut samples = Vec::new();
+        // 1, 3, 3 then #
+        for (low, high) in [
+            (697.0, 1209.0),
+            (697.0, 1477.0),
+            (697.0, 1477.0),
+            (941.0, 1477.0),
+        ] {
+            push_tone(&mut samples, &[low, high], 100);
+            push_tone(&mut samples, &[], 100);
+        }
+        let audio = decode_wav_data_uri(&wav_data_uri(&samples)).unwrap();
+        assert_eq!(extract_dtmf(&audio).unwrap(), "133#");
+    }
+
+    #[test]
+    fn extracts_morse_code() {
+        let mut samples = Vec::new();
+        let unit = 60;
+        push_tone(&mut samples, &[], unit * 3);
+        // S O S, then E
+        for (i, letter) in ["...", "---", "..."].iter().enumerate() {
+            if i > 0 {
+                push_tone(&mut samples, &[], unit * 2);
+            }
+            for symbol in letter.chars() {
+                let length = if symbol == '.' { unit } else { unit * 3 };
+                push_tone(&mut samples, &[700.0], length);
+                push_tone(&mut samples, &[], unit);
+            }
+        }
+        push_tone(&mut samples, &[], unit * 6);
+        push_tone(&mut samples, &[700.0], unit);
+        push_tone(&mut samples, &[], unit * 3);
+
+        let audio = decode_wav_data_uri(&wav_data_uri(&samples)).unwrap();
+        assert_eq!(extract_morse(&audio).unwrap(), "... --- ... / .");
+    }
+
+    #[test]
+    fn audio_decoder_emits_symbols() {
+        let mut samples = Vec::new();
+        push_tone(&mut samples, &[852.0, 1336.0], 100);
+        let audio_decoder = Decoder::<AudioDecoder>::new();
+        let result = audio_decoder
+            .crack(&wav_data_uri(&samples), &get_athena_checker())
+            .unencrypted_text
+            .unwrap();
+        assert!(result.contains(&"8".to_string()));
+    }
+
+    #[test]
+    fn audio_decoder_ignores_non_wav_input() {
+        let audio_decoder = Decoder::<AudioDecoder>::new();
+        let result = audio_decoder
+            .crack(
+                "data:audio/wav;base64,aGVsbG8gd29ybGQ=",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn audio_decoder_rejects_huge_sample_rates() {
+        let mut samples = Vec::new();
+        push_tone(&mut samples, &[852.0, 1336.0], 100);
+        assert!(decode_wav_data_uri(&wav_data_uri_at(&samples, u32::MAX)).is_none());
+        let audio_decoder = Decoder::<AudioDecoder>::new();
+        let result = audio_decoder
+            .crack(&wav_data_uri_at(&samples, u32::MAX), &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn blocks_do_not_overflow_with_huge_sample_rates() {
+        let audio = Audio {
+            sample_rate: u32::MAX,
+            samples: vec![0.0; 10],
+        };
+        // 4294967295 samples a second is 107374182 samples in 25ms, more than we have
+        assert_eq!(blocks(&audio, DTMF_BLOCK_MS).count(), 1);
+    }
+
+    #[test]
+    fn audio_decode_empty_string() {
+        let audio_decoder = Decoder::<AudioDecoder>::new();
+        let result = audio_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -9,9 +9,16 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::Preconditions;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use data_encoding::BASE32_NOPAD;
 use log::{debug, info, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Every character base32 can decode, as we only accept uppercase base32
+static BASE32_CHARSET: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Z2-7=]+$").unwrap());
 
 /// The Base32 decoder, call:
 /// `let base32_decoder = Decoder::<Base32Decoder>::new()` to create a new instance
@@ -41,6 +48,7 @@ impl Crack for Decoder<Base32Decoder> {
             link: "https://en.wikipedia.org/wiki/Base32",
             tags: vec!["base32", "decoder", "base"],
             popularity: 0.8,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -82,6 +90,39 @@ impl Crack for Decoder<Base32Decoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Alphanumeric,
+            runtime: RuntimeClass::Fast,
+        }
+    }
+    /// Gets the text the current decoder can decode
+    fn get_preconditions(&self) -> Preconditions {
+        Preconditions {
+            min_length: 2,
+            charset: Some(&BASE32_CHARSET),
+            ..Default::default()
+        }
+    }
 }
 
 /// helper function
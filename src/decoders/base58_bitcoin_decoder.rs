@@ -9,8 +9,15 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::Preconditions;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Every character in the Bitcoin Base58 alphabet
+static BASE58_CHARSET: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[1-9A-HJ-NP-Za-km-z]+$").unwrap());
 
 /// The Base58_bitcoin decoder, call:
 /// `let base58_bitcoin_decoder = Decoder::<Base58BitcoinDecoder>::new()` to create a new instance
@@ -40,6 +47,7 @@ impl Crack for Decoder<Base58BitcoinDecoder> {
             link: "https://en.wikipedia.org/wiki/Base58",
             tags: vec!["base58_bitcoin", "base58", "bitcoin", "cryptocurrency", "decoder", "base"],
             popularity: 0.8,
+            expected_runtime: 0.02,
             phantom: std::marker::PhantomData,
         }
     }
@@ -81,6 +89,39 @@ impl Crack for Decoder<Base58BitcoinDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Alphanumeric,
+            runtime: RuntimeClass::Fast,
+        }
+    }
+    /// Gets the text the current decoder can decode
+    fn get_preconditions(&self) -> Preconditions {
+        Preconditions {
+            min_length: 1,
+            charset: Some(&BASE58_CHARSET),
+            ..Default::default()
+        }
+    }
 }
 
 /// helper function
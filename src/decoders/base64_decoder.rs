@@ -3,15 +3,23 @@
 //! Call base64_decoder.crack to use. It returns option<String> and check with
 //! `result.is_some()` to see if it returned okay.
 
+use crate::checkers::file_signature::binary_file_as_text;
 use crate::checkers::CheckerTypes;
 use crate::decoders::interface::check_string_success;
-use base64::{engine::general_purpose, Engine as _};
+use base64_simd::Base64;
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::Preconditions;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Every character base64 can decode
+static BASE64_CHARSET: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9+/=]+$").unwrap());
 
 /// The Base64 decoder, call:
 /// `let base64_decoder = Decoder::<Base64Decoder>::new()` to create a new instance
@@ -41,6 +49,7 @@ impl Crack for Decoder<Base64Decoder> {
             link: "https://en.wikipedia.org/wiki/Base64",
             tags: vec!["base64", "decoder", "base"],
             popularity: 1.0,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -82,18 +91,75 @@ impl Crack for Decoder<Base64Decoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Alphanumeric,
+            runtime: RuntimeClass::Fast,
+        }
+    }
+    /// Gets the text the current decoder can decode
+    fn get_preconditions(&self) -> Preconditions {
+        Preconditions {
+            min_length: 2,
+            charset: Some(&BASE64_CHARSET),
+            ..Default::default()
+        }
+    }
 }
 
+/// How many base64 characters are decoded at once, a multiple of 4 so every chunk
+/// but the last decodes to whole bytes
+const CHUNK_SIZE: usize = 1024 * 1024;
+
 /// helper function
 fn decode_base64_no_error_handling(text: &str) -> Option<String> {
-    // Strip all padding
-    let text = text.replace('=', "");
     // Runs the code to decode base64
     // Doesn't perform error handling, call from_base64
-    general_purpose::STANDARD_NO_PAD
-        .decode(text.as_bytes())
-        .ok()
-        .map(|inner| String::from_utf8(inner).ok())?
+    decode_base64_in_chunks(text, &base64_simd::STANDARD_NO_PAD).map(|inner| {
+        match String::from_utf8(inner) {
+            Ok(text) => Some(text),
+            // Binary isn't text, unless it is a file like a PNG the File Signature Checker can find
+            Err(error) => binary_file_as_text(error.as_bytes()),
+        }
+    })?
+}
+
+/// Decodes base64 without padding a chunk at a time, skipping any padding in the text.
+/// This never copies the whole text, so very large inputs only cost the decoded bytes.
+/// base64 runs on nearly every text we search, so the chunks are decoded with SIMD.
+pub(crate) fn decode_base64_in_chunks(text: &str, base64: &Base64) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(text.len() / 4 * 3 + 3);
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE.min(text.len()));
+    for byte in text.bytes().filter(|&byte| byte != b'=') {
+        chunk.push(byte);
+        if chunk.len() == CHUNK_SIZE {
+            decoded.extend_from_slice(&base64.decode_to_vec(&chunk).ok()?);
+            chunk.clear();
+        }
+    }
+    if !chunk.is_empty() {
+        decoded.extend_from_slice(&base64.decode_to_vec(&chunk).ok()?);
+    }
+    Some(decoded)
 }
 
 #[cfg(test)]
@@ -107,6 +173,7 @@ mod tests {
         },
         decoders::interface::{Crack, Decoder},
     };
+    use base64::{engine::general_purpose, Engine};
 
     // helper for tests
     fn get_athena_checker() -> CheckerTypes {
@@ -192,4 +259,15 @@ mod tests {
             assert_eq!(true, true);
         }
     }
+
+    #[test]
+    fn base64_longer_than_a_chunk_decodes_the_same() {
+        let plaintext = "hello world ".repeat(super::CHUNK_SIZE / 8);
+        let encoded = general_purpose::STANDARD.encode(&plaintext);
+        assert!(encoded.len() > super::CHUNK_SIZE);
+        assert_eq!(
+            super::decode_base64_no_error_handling(&encoded),
+            Some(plaintext)
+        );
+    }
 }
@@ -3,15 +3,23 @@
 //! Call base64_url_decoder.crack to use. It returns option<String> and check with
 //! `result.is_some()` to see if it returned okay.
 
+use crate::checkers::file_signature::binary_file_as_text;
 use crate::checkers::CheckerTypes;
 use crate::decoders::interface::check_string_success;
-use base64::{engine::general_purpose, Engine as _};
 
+use super::base64_decoder::decode_base64_in_chunks;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::Preconditions;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Every character URL safe base64 can decode
+static BASE64_URL_CHARSET: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9_\-=]+$").unwrap());
 
 /// The base64_url decoder, call:
 /// `let base64_url_decoder = Decoder::<Base64URLDecoder>::new()` to create a new instance
@@ -41,6 +49,7 @@ impl Crack for Decoder<Base64URLDecoder> {
             link: "https://en.wikipedia.org/wiki/Base64#URL_applications",
             tags: vec!["base64_url", "base64", "url", "decoder", "base"],
             popularity: 0.9,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -82,18 +91,52 @@ impl Crack for Decoder<Base64URLDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Alphanumeric,
+            runtime: RuntimeClass::Fast,
+        }
+    }
+    /// Gets the text the current decoder can decode
+    fn get_preconditions(&self) -> Preconditions {
+        Preconditions {
+            min_length: 2,
+            charset: Some(&BASE64_URL_CHARSET),
+            ..Default::default()
+        }
+    }
 }
 
 /// helper function
 fn decode_base64_url_no_error_handling(text: &str) -> Option<String> {
-    // Strip all padding
-    let text = text.replace('=', "");
     // Runs the code to decode base64_url
     // Doesn't perform error handling, call from_base64_url
-    general_purpose::URL_SAFE_NO_PAD
-        .decode(text.as_bytes())
-        .ok()
-        .map(|inner| String::from_utf8(inner).ok())?
+    decode_base64_in_chunks(text, &base64_simd::URL_SAFE_NO_PAD).map(|inner| {
+        match String::from_utf8(inner) {
+            Ok(text) => Some(text),
+            // Binary isn't text, unless it is a file like a PNG the File Signature Checker can find
+            Err(error) => binary_file_as_text(error.as_bytes()),
+        }
+    })?
 }
 
 #[cfg(test)]
@@ -9,6 +9,7 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
 
@@ -40,6 +41,7 @@ impl Crack for Decoder<Base65536Decoder> {
             link: "https://github.com/qntm/base65536",
             tags: vec!["base65536", "decoder", "base"],
             popularity: 0.1,
+            expected_runtime: 0.02,
             phantom: std::marker::PhantomData,
         }
     }
@@ -83,6 +85,31 @@ impl Crack for Decoder<Base65536Decoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Unicode,
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 /// helper function
@@ -9,6 +9,7 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
 
@@ -40,6 +41,7 @@ impl Crack for Decoder<Base91Decoder> {
             link: "https://base91.sourceforge.net/",
             tags: vec!["base91", "decoder", "base"],
             popularity: 0.3,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -81,6 +83,31 @@ impl Crack for Decoder<Base91Decoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Printable,
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 /// helper function
@@ -4,6 +4,7 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
 
@@ -18,6 +19,7 @@ impl Crack for Decoder<BinaryDecoder> {
             link: "https://en.wikipedia.org/wiki/Binary_code",
             tags: vec!["binary", "base", "decoder"],
             popularity: 1.0,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -47,11 +49,18 @@ impl Crack for Decoder<BinaryDecoder> {
             if checker_result.is_identified {
                 info!("Found a match with binary bit {}", shift);
                 results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
+                results.key = Some(format!("bits={shift}"));
                 results.update_checker(&checker_result);
                 return results;
             }
         }
-        results.unencrypted_text = Some(decoded_strings);
+        results.set_candidates(
+            decoded_strings
+                .into_iter()
+                .zip(1..)
+                .map(|(text, bits)| (text, Some(format!("bits={bits}"))))
+                .collect(),
+        );
         results
     }
     /// Gets all tags for this decoder
@@ -62,6 +71,31 @@ impl Crack for Decoder<BinaryDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Digits,
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 /// Decodes binary to string
@@ -9,6 +9,7 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{info, trace};
 
@@ -42,6 +43,7 @@ impl Crack for Decoder<CaesarDecoder> {
             link: "https://en.wikipedia.org/wiki/Caesar_cipher",
             tags: vec!["caesar", "decryption", "classic", "reciprocal"],
             popularity: 1.0,
+            expected_runtime: 0.1,
             phantom: std::marker::PhantomData,
         }
     }
@@ -69,11 +71,18 @@ impl Crack for Decoder<CaesarDecoder> {
             if checker_result.is_identified {
                 trace!("Found a match with caesar shift {}", shift);
                 results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
+                results.key = Some(format!("shift={shift}"));
                 results.update_checker(&checker_result);
                 return results;
             }
         }
-        results.unencrypted_text = Some(decoded_strings);
+        results.set_candidates(
+            decoded_strings
+                .into_iter()
+                .zip(1..)
+                .map(|(text, shift)| (text, Some(format!("shift={shift}"))))
+                .collect(),
+        );
         results
     }
     /// Gets all tags for this decoder
@@ -84,10 +93,35 @@ impl Crack for Decoder<CaesarDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Cipher,
+            reversibility: Reversibility::KeySearch,
+            charset: Charset::Letters,
+            runtime: RuntimeClass::Medium,
+        }
+    }
 }
 
 /// Caesar cipher to rotate cipher text by shift and return an owned String.
-fn caesar(cipher: &str, shift: u8) -> String {
+pub(crate) fn caesar(cipher: &str, shift: u8) -> String {
     cipher
         .chars()
         .map(|c| {
@@ -110,6 +144,7 @@ mod tests {
         checkers::{
             athena::Athena,
             checker_type::{Check, Checker},
+            default_checker::DefaultChecker,
             CheckerTypes,
         },
         decoders::interface::{Crack, Decoder},
@@ -143,6 +178,23 @@ mod tests {
         assert_eq!(result.unencrypted_text.unwrap()[0], "attack");
     }
 
+    #[test]
+    fn successful_decoding_reports_the_shift() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let result = caesar_decoder.crack("fyyfhp", &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("shift=21"));
+    }
+
+    #[test]
+    fn every_shift_is_a_candidate_with_its_key() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let checker = CheckerTypes::CheckDefault(Checker::<DefaultChecker>::new());
+        let result = caesar_decoder.crack("uryyb", &checker);
+        assert_eq!(result.candidates.len(), 25);
+        assert_eq!(result.candidates[12].text, "hello");
+        assert_eq!(result.candidates[12].key.as_deref(), Some("shift=13"));
+    }
+
     #[test]
     fn successful_decoding_one_step_forward() {
         let caesar_decoder = Decoder::<CaesarDecoder>::new();
@@ -4,6 +4,8 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::Preconditions;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
 
@@ -31,6 +33,7 @@ impl Crack for Decoder<CitrixCTX1Decoder> {
             link: "https://www.remkoweijnen.nl/blog/2012/05/13/encoding-and-decoding-citrix-passwords/",
             tags: vec!["citrix_ctx1", "citrix", "passwords", "decoder"],
             popularity: 0.1,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -75,6 +78,39 @@ impl Crack for Decoder<CitrixCTX1Decoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Cipher,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Letters,
+            runtime: RuntimeClass::Fast,
+        }
+    }
+    /// Gets the text the current decoder can decode
+    fn get_preconditions(&self) -> Preconditions {
+        Preconditions {
+            min_length: 4,
+            length_multiple: 4,
+            ..Default::default()
+        }
+    }
 }
 
 /// Decodes Citrix CTX1
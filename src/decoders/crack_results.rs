@@ -1,53 +1,199 @@
-//! This module contains CrackSuccess and CrackFailure
-use crate::checkers::checker_result::CheckResult;
-
-use super::interface::Decoder;
-
-/// Every cracker returns this object which
-/// Either indicates success or failure among other things.
-#[derive(Debug, Clone)]
-pub struct CrackResult {
-    /// If our checkers return success, we change this bool to True
-    pub success: bool,
-    /// Encrypted text is the text _before_ we decrypt it.
-    pub encrypted_text: String,
-    /// Unencrypted text is what it looks like after.
-    /// if decoder failed, this will be None
-    pub unencrypted_text: Option<Vec<String>>,
-    /// Decoder is the function we used to decode the text
-    pub decoder: &'static str,
-    /// Checker which identified the text
-    pub checker_name: &'static str,
-    /// Description is a short description of the checker
-    pub checker_description: &'static str,
-    /// Key is optional as decoders do not use keys.
-    pub key: Option<&'static str>,
-    /// Description is a short description of the decoder
-    pub description: &'static str,
-    /// Link is a link to more info about the decoder
-    pub link: &'static str,
-}
-
-impl CrackResult {
-    /// This function returns a new CrackResult
-    pub fn new<T>(decoder_used: &Decoder<T>, text: String) -> Self {
-        CrackResult {
-            success: false,
-            encrypted_text: text,
-            unencrypted_text: None,
-            decoder: decoder_used.name,
-            checker_name: "",
-            checker_description: "",
-            key: None,
-            description: decoder_used.description,
-            link: decoder_used.link,
-        }
-    }
-
-    /// Updates the checker information
-    pub fn update_checker(&mut self, checker_result: &CheckResult) {
-        self.checker_name = checker_result.checker_name;
-        self.checker_description = checker_result.checker_description;
-        self.success = checker_result.is_identified;
-    }
-}
+//! This module contains CrackSuccess and CrackFailure
+use crate::checkers::checker_result::CheckResult;
+use crate::checkers::lemmeknow_checker::LemmeKnowMatch;
+use crate::storage::quadgrams::ENGLISH_QUADGRAMS;
+
+use super::interface::Decoder;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// Every cracker returns this object which
+/// Either indicates success or failure among other things.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrackResult {
+    /// If our checkers return success, we change this bool to True
+    pub success: bool,
+    /// Encrypted text is the text _before_ we decrypt it.
+    pub encrypted_text: String,
+    /// Unencrypted text is what it looks like after.
+    /// if decoder failed, this will be None
+    pub unencrypted_text: Option<Vec<String>>,
+    /// The same texts as `unencrypted_text` with the key and score of each,
+    /// for decoders which try several keys. See [`CrackResult::set_candidates`]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<Candidate>,
+    /// Decoder is the function we used to decode the text
+    pub decoder: &'static str,
+    /// Checker which identified the text
+    pub checker_name: &'static str,
+    /// Description is a short description of the checker
+    pub checker_description: &'static str,
+    /// How confident the checker is that the text is plaintext, from 0.0 to 1.0
+    pub confidence: f32,
+    /// What LemmeKnow identified the text as, if the LemmeKnow Checker identified it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lemmeknow: Option<LemmeKnowMatch>,
+    /// The key, shift or other setting the decoder used, like `shift=13` for the Caesar Cipher.
+    /// None for decoders which don't need one, like Base64.
+    pub key: Option<String>,
+    /// Description is a short description of the decoder
+    pub description: &'static str,
+    /// Link is a link to more info about the decoder
+    pub link: &'static str,
+}
+
+impl CrackResult {
+    /// This function returns a new CrackResult
+    pub fn new<T>(decoder_used: &Decoder<T>, text: String) -> Self {
+        CrackResult {
+            success: false,
+            encrypted_text: text,
+            unencrypted_text: None,
+            candidates: Vec::new(),
+            decoder: decoder_used.name,
+            checker_name: "",
+            checker_description: "",
+            confidence: 0.0,
+            lemmeknow: None,
+            key: None,
+            description: decoder_used.description,
+            link: decoder_used.link,
+        }
+    }
+
+    /// Updates the checker information
+    pub fn update_checker(&mut self, checker_result: &CheckResult) {
+        self.checker_name = checker_result.checker_name;
+        self.checker_description = checker_result.checker_description;
+        self.confidence = checker_result.confidence;
+        self.lemmeknow = checker_result.lemmeknow;
+        self.success = checker_result.is_identified;
+    }
+
+    /// Returns the decoder with the key it used, like `Caesar Cipher(shift=13)`,
+    /// which is what users need to repeat this step in another tool
+    pub fn decoder_with_key(&self) -> String {
+        match &self.key {
+            Some(key) => format!("{}({key})", self.decoder),
+            None => self.decoder.to_string(),
+        }
+    }
+
+    /// Sets the texts the decoder returned along with the key which gave each of them
+    pub fn set_candidates(&mut self, candidates: Vec<(String, Option<String>)>) {
+        self.candidates = candidates
+            .into_iter()
+            .map(|(text, key)| Candidate::new(text, key))
+            .collect();
+        self.unencrypted_text = Some(
+            self.candidates
+                .iter()
+                .map(|candidate| candidate.text.clone())
+                .collect(),
+        );
+    }
+
+    /// Splits the result into one result for each text the decoder returned,
+    /// the texts which look most like English first.
+    /// With a `limit` only that many of them are kept.
+    /// Each result has the key which gave its text, so the path shows how it was decoded.
+    pub fn into_best_candidates(mut self, limit: Option<usize>) -> Vec<CrackResult> {
+        let mut candidates = std::mem::take(&mut self.candidates);
+        let texts = self.unencrypted_text.take().unwrap_or_default();
+        if candidates.is_empty() {
+            candidates = texts
+                .into_iter()
+                .map(|text| Candidate::new(text, self.key.clone()))
+                .collect();
+        }
+        // The sort is stable, so texts which score the same stay in the decoder's order
+        candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        if let Some(limit) = limit {
+            candidates.truncate(limit);
+        }
+        candidates
+            .into_iter()
+            .map(|candidate| CrackResult {
+                unencrypted_text: Some(vec![candidate.text]),
+                key: candidate.key,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// One of the texts a decoder returned, like one of the 25 shifts the Caesar Cipher tries
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    /// The decoded text
+    pub text: String,
+    /// The key which gave this text, like `shift=13`
+    pub key: Option<String>,
+    /// How much the text looks like English, the average log probability of its quadgrams.
+    /// The closer to 0 the better, None if it has fewer than 4 letters
+    pub score: Option<f64>,
+}
+
+impl Candidate {
+    /// Scores the text and returns it as a candidate
+    pub fn new(text: String, key: Option<String>) -> Self {
+        let score = ENGLISH_QUADGRAMS.fitness(&text);
+        Candidate { text, key, score }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CrackResult;
+    use crate::decoders::caesar_decoder::CaesarDecoder;
+    use crate::decoders::interface::{Crack, Decoder};
+
+    #[test]
+    fn best_candidates_look_most_like_english() {
+        let mut result = CrackResult::new(&Decoder::<CaesarDecoder>::new(), "ifmmp".to_string());
+        result.set_candidates(vec![
+            ("jgnnq yqtnf".to_string(), Some("shift=2".to_string())),
+            ("hello world".to_string(), Some("shift=25".to_string())),
+            ("ok".to_string(), Some("shift=3".to_string())),
+        ]);
+        let best = result.into_best_candidates(Some(2));
+        assert_eq!(best.len(), 2);
+        assert_eq!(
+            best[0].unencrypted_text,
+            Some(vec!["hello world".to_string()])
+        );
+        assert_eq!(best[0].key.as_deref(), Some("shift=25"));
+        assert_eq!(best[1].key.as_deref(), Some("shift=2"));
+    }
+
+    #[test]
+    fn every_candidate_is_kept_without_a_limit() {
+        let mut result = CrackResult::new(&Decoder::<CaesarDecoder>::new(), "ifmmp".to_string());
+        result.set_candidates(vec![
+            ("jgnnq yqtnf".to_string(), Some("shift=2".to_string())),
+            ("hello world".to_string(), Some("shift=25".to_string())),
+            ("ok".to_string(), Some("shift=3".to_string())),
+        ]);
+        let all = result.into_best_candidates(None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].key.as_deref(), Some("shift=25"));
+    }
+
+    #[test]
+    fn decoder_with_key_shows_the_key() {
+        let mut result = CrackResult::new(&Decoder::<CaesarDecoder>::new(), "uryyb".to_string());
+        assert_eq!(result.decoder_with_key(), "Caesar Cipher");
+        result.key = Some("shift=13".to_string());
+        assert_eq!(result.decoder_with_key(), "Caesar Cipher(shift=13)");
+    }
+
+    #[test]
+    fn texts_without_candidates_are_split_too() {
+        let mut result = CrackResult::new(&Decoder::<CaesarDecoder>::new(), "ifmmp".to_string());
+        result.unencrypted_text = Some(vec!["one".to_string(), "two".to_string()]);
+        let split = result.into_best_candidates(None);
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[1].unencrypted_text, Some(vec!["two".to_string()]));
+        assert!(split.iter().all(|result| result.candidates.is_empty()));
+    }
+}
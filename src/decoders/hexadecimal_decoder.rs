@@ -4,6 +4,8 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::Preconditions;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{debug, info, trace};
 
@@ -27,6 +29,7 @@ impl Crack for Decoder<HexadecimalDecoder> {
             link: "https://en.wikipedia.org/wiki/Hexadecimal#Base16_(transfer_encoding)",
             tags: vec!["hexadecimal", "hex", "base", "decoder"],
             popularity: 1.0,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -71,29 +74,73 @@ impl Crack for Decoder<HexadecimalDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Encoding,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Hex,
+            runtime: RuntimeClass::Fast,
+        }
+    }
+    /// Gets the text the current decoder can decode
+    fn get_preconditions(&self) -> Preconditions {
+        Preconditions {
+            min_length: 2,
+            ..Default::default()
+        }
+    }
 }
 
 /// Decodes hexadecimal to string
+/// This is a single pass over the text, so very large inputs aren't copied before decoding
 fn hexadecimal_to_string(hex: &str) -> Result<String, Error> {
-    // Remove "0x" delimiters
-    let hex = hex.replace("0x", "");
-    // Remove all non-hexadecimal characters from the string
-    let hex = hex.replace(|c: char| !c.is_ascii_hexdigit(), "");
+    // Hexadecimal without delimiters is the most common, so we decode it with SIMD
+    if let Ok(decoded) = hex_simd::decode_to_vec(hex) {
+        return Ok(decoded.into_iter().map(char::from).collect());
+    }
 
-    // Convert the hexadecimal string to a vector of bytes
     let bytes = hex.as_bytes();
-
-    // Ensure that the vector of bytes has an even length, so it can be processed in pairs
-    if bytes.len() % 2 == 1 {
-        return Err(Error::InvalidLength);
+    let mut result = String::with_capacity(bytes.len() / 2);
+    // The first digit of the byte being decoded
+    let mut high = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        // Skip "0x" delimiters
+        if bytes[i] == b'0' && bytes.get(i + 1) == Some(&b'x') {
+            i += 2;
+            continue;
+        }
+        // Skip all non-hexadecimal characters
+        if let Some(digit) = (bytes[i] as char).to_digit(16) {
+            // Pair up the digits and push the ASCII character they make to the result string
+            match high.take() {
+                Some(high) => result.push((high * 16 + digit) as u8 as char),
+                None => high = Some(digit),
+            }
+        }
+        i += 1;
     }
 
-    // Iterate over the vector of bytes in pairs
-    let mut result = String::new();
-    for pair in bytes.chunks(2) {
-        // Parse the pair of bytes as a hexadecimal number and push the corresponding
-        // ASCII character to the result string
-        result.push(u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap() as char);
+    // The digits must come in pairs
+    if high.is_some() {
+        return Err(Error::InvalidLength);
     }
 
     String::from_utf8(result.into()).map_err(|_| Error::InvalidUtf8)
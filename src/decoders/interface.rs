@@ -1,4 +1,6 @@
 use crate::checkers::CheckerTypes;
+use regex::Regex;
+use serde::Serialize;
 
 use super::crack_results::CrackResult;
 
@@ -20,6 +22,10 @@ pub struct Decoder<Type> {
     pub tags: Vec<&'static str>,
     /// We get popularity by eye-balling it or using the API's data
     pub popularity: f32,
+    /// The expected runtime of the decoder
+    /// We get this by bench marking the code
+    /// Cheap decoders (base64, hex) run before expensive ones (caesar)
+    pub expected_runtime: f32,
     /// we don't use the Type, so we use PhantomData to mark it!
     pub phantom: std::marker::PhantomData<Type>,
 }
@@ -34,6 +40,7 @@ impl Default for Decoder<DefaultDecoder> {
             link: "N/A",
             tags: vec!["N/A"],
             popularity: 0.0,
+            expected_runtime: 0.0,
             phantom: std::marker::PhantomData,
         }
     }
@@ -54,6 +61,195 @@ pub trait Crack {
     fn get_tags(&self) -> &Vec<&str>;
     /// Get the nam of the current decoder
     fn get_name(&self) -> &str;
+    /// Get the popularity of the current decoder
+    fn get_popularity(&self) -> f32;
+    /// Get the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32;
+    /// Get the description of the current decoder
+    fn get_description(&self) -> &str;
+    /// Get the link to more info about the current decoder
+    fn get_link(&self) -> &str;
+    /// What the text must look like for the current decoder to decode it.
+    /// Decoders which can try any text keep the default, which has no preconditions.
+    fn get_preconditions(&self) -> Preconditions {
+        Preconditions::default()
+    }
+    /// What kind of decoder the current decoder is, see [`DecoderMetadata`].
+    /// Decoders which don't say, like most custom decoders, get the default.
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata::default()
+    }
+}
+
+/// What kind of decoder a decoder is, so decoders can be filtered by more than their tags.
+/// `--only` and `--exclude` match these as well as names and tags, like `--exclude slow`
+/// or `--only cipher`, see [`DecoderMetadata::labels`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DecoderMetadata {
+    /// What the decoder undoes
+    pub category: DecoderCategory,
+    /// How the decoding relates to the text, see [`Reversibility`]
+    pub reversibility: Reversibility,
+    /// The characters the decoder expects the text to be made of
+    pub charset: Charset,
+    /// Roughly how long the decoder takes, from its `expected_runtime`
+    pub runtime: RuntimeClass,
+}
+
+impl DecoderMetadata {
+    /// The names of the category, reversibility, charset and runtime, like "cipher" or "slow"
+    pub fn labels(&self) -> [&'static str; 4] {
+        [
+            self.category.label(),
+            self.reversibility.label(),
+            self.charset.label(),
+            self.runtime.label(),
+        ]
+    }
+}
+
+/// What a decoder undoes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecoderCategory {
+    /// An encoding with no key, like base64 or hexadecimal
+    Encoding,
+    /// A cipher, like Caesar or the rail fence cipher
+    Cipher,
+    /// Moves the characters around without changing them, like reversing the text
+    Transform,
+    /// Finds text hidden in images or audio, like LSB steganography or Morse code in a WAV file
+    Media,
+    /// Anything else, like custom decoders and plugins
+    #[default]
+    Other,
+}
+
+impl DecoderCategory {
+    /// The name used by `--only`, `--exclude` and `ares list-decoders`
+    pub fn label(self) -> &'static str {
+        match self {
+            DecoderCategory::Encoding => "encoding",
+            DecoderCategory::Cipher => "cipher",
+            DecoderCategory::Transform => "transform",
+            DecoderCategory::Media => "media",
+            DecoderCategory::Other => "other",
+        }
+    }
+}
+
+/// How the decoding relates to the text
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reversibility {
+    /// There is one decoding, which exactly undoes the encoding
+    #[default]
+    Exact,
+    /// Decoding twice gives back the text, like reversing it or Atbash.
+    /// The filtration system never runs these right after themselves, as that only undoes them.
+    SelfInverse,
+    /// Tries every key and gives a decoding for each one, like the Caesar cipher
+    KeySearch,
+}
+
+impl Reversibility {
+    /// The name used by `--only`, `--exclude` and `ares list-decoders`
+    pub fn label(self) -> &'static str {
+        match self {
+            Reversibility::Exact => "exact",
+            Reversibility::SelfInverse => "self_inverse",
+            Reversibility::KeySearch => "key_search",
+        }
+    }
+}
+
+/// The characters a decoder expects the text to be made of.
+/// [`Preconditions`] has the exact rules, this is for people and filtering.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Charset {
+    /// Any text
+    #[default]
+    Any,
+    /// Letters, like the Caesar cipher
+    Letters,
+    /// Digits, like binary or A1Z26
+    Digits,
+    /// Hexadecimal digits
+    Hex,
+    /// Letters and digits, maybe with a little punctuation like base64's `+`, `/` and `=`
+    Alphanumeric,
+    /// Printable ASCII, like base91 or URL encoding
+    Printable,
+    /// Punctuation, like Morse code's dots and dashes
+    Symbols,
+    /// Any Unicode characters, like base65536
+    Unicode,
+}
+
+impl Charset {
+    /// The name used by `--only`, `--exclude` and `ares list-decoders`
+    pub fn label(self) -> &'static str {
+        match self {
+            Charset::Any => "any",
+            Charset::Letters => "letters",
+            Charset::Digits => "digits",
+            Charset::Hex => "hex",
+            Charset::Alphanumeric => "alphanumeric",
+            Charset::Printable => "printable",
+            Charset::Symbols => "symbols",
+            Charset::Unicode => "unicode",
+        }
+    }
+}
+
+/// Roughly how long a decoder takes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeClass {
+    /// An `expected_runtime` of 0.02 or less, like base64
+    #[default]
+    Fast,
+    /// An `expected_runtime` of 0.2 or less, like the Caesar cipher
+    Medium,
+    /// Anything slower, like decoding audio
+    Slow,
+}
+
+impl RuntimeClass {
+    /// The name used by `--only`, `--exclude` and `ares list-decoders`
+    pub fn label(self) -> &'static str {
+        match self {
+            RuntimeClass::Fast => "fast",
+            RuntimeClass::Medium => "medium",
+            RuntimeClass::Slow => "slow",
+        }
+    }
+}
+
+/// Cheap checks the filtration system runs on the text before calling `.crack()`,
+/// so a decoder never runs on text it can't decode.
+/// For example base32 is never run on text containing lowercase letters.
+#[derive(Clone, Copy, Default)]
+pub struct Preconditions {
+    /// The shortest text, in bytes, the decoder can decode
+    pub min_length: usize,
+    /// The whole text must match this regex, like `^[A-Z2-7=]+$` for base32
+    pub charset: Option<&'static Regex>,
+    /// The length of the text in bytes must be a multiple of this, unless it is 0
+    pub length_multiple: usize,
+}
+
+impl Preconditions {
+    /// Returns true if the text meets every precondition
+    pub fn are_met_by(&self, text: &str) -> bool {
+        text.len() >= self.min_length
+            && (self.length_multiple == 0 || text.len().is_multiple_of(self.length_multiple))
+            && match self.charset {
+                Some(charset) => charset.is_match(text),
+                None => true,
+            }
+    }
 }
 
 /// Returns a boolean of True if the string is successfully changed
@@ -0,0 +1,309 @@
+//! Extract least significant bit steganography from PNG data URIs
+//! Performs error handling and returns a string
+//! Call lsb_stego_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! This decoder is only compiled with the `stego` feature.
+
+use crate::checkers::CheckerTypes;
+use base64::{engine::general_purpose, Engine as _};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
+
+use log::{debug, trace};
+
+/// The prefix a PNG data URI must start with for us to try and extract from it.
+const PNG_DATA_URI_PREFIX: &str = "data:image/png;base64,";
+
+/// Extracted strings shorter than this are almost certainly noise.
+const MIN_PAYLOAD_LENGTH: usize = 4;
+
+/// The bit planes we look at. Bit 0 is the least significant bit.
+/// Most tools hide in plane 0, but some CTFs use the next couple of planes too.
+const BIT_PLANES: [u8; 3] = [0, 1, 2];
+
+/// The channel orders we extract from, as indices into each RGB pixel.
+/// `[0, 1, 2]` interleaves red, green and blue which is what most tools do.
+const CHANNEL_ORDERS: [&[usize]; 4] = [&[0, 1, 2], &[0], &[1], &[2]];
+
+/// The LSB steganography decoder, call:
+/// `let lsb_stego_decoder = Decoder::<LsbStegoDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = lsb_stego_decoder.crack(input)` to extract text hidden in a PNG data URI
+/// The struct generated by new() comes from interface.rs
+pub struct LsbStegoDecoder;
+
+impl Crack for Decoder<LsbStegoDecoder> {
+    fn new() -> Decoder<LsbStegoDecoder> {
+        Decoder {
+            name: "LSB Steganography",
+            description: "Least significant bit steganography hides a message in the lowest bits of each colour channel of an image. We read PNG images given as data URIs and extract any text hidden in their bit planes.",
+            link: "https://en.wikipedia.org/wiki/Bit_plane",
+            tags: vec!["stego", "image", "lsb", "decoder"],
+            popularity: 0.3,
+            expected_runtime: 0.5,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying LSB steganography with text {:?}", text);
+        let mut results = CrackResult::new(self, text.to_string());
+
+        let candidates = match extract_lsb_strings(text) {
+            Some(candidates) if !candidates.is_empty() => candidates,
+            _ => {
+                debug!("Failed to extract anything from the image with LSB steganography");
+                return results;
+            }
+        };
+
+        for (candidate, key) in &candidates {
+            let checker_result = checker.check(candidate);
+            // If checkers return true, exit early with the correct result
+            if checker_result.is_identified {
+                trace!("Found a match in the bit planes of the image");
+                results.unencrypted_text = Some(vec![candidate.to_string()]);
+                results.key = key.clone();
+                results.update_checker(&checker_result);
+                return results;
+            }
+        }
+
+        // Nothing was plaintext, so we give every extracted string back to the search
+        results.set_candidates(candidates);
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.name
+    }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Media,
+            reversibility: Reversibility::Exact,
+            charset: Charset::Printable,
+            runtime: RuntimeClass::Slow,
+        }
+    }
+}
+
+/// Decodes the PNG inside of a data URI and returns every printable string
+/// we could pull out of its bit planes, with the bit plane and channels it came from.
+/// Returns None if the text is not a PNG data URI or the PNG is broken.
+fn extract_lsb_strings(text: &str) -> Option<Vec<(String, Option<String>)>> {
+    let encoded_image = text.trim().strip_prefix(PNG_DATA_URI_PREFIX)?;
+    let image_bytes = general_purpose::STANDARD
+        .decode(encoded_image.trim().as_bytes())
+        .ok()?;
+    let pixels = decode_png_to_rgb(&image_bytes)?;
+
+    let mut candidates: Vec<(String, Option<String>)> = Vec::new();
+    for plane in BIT_PLANES {
+        for channels in CHANNEL_ORDERS {
+            let bytes = extract_bit_plane(&pixels, plane, channels);
+            if let Some(payload) = leading_printable_string(&bytes) {
+                if !candidates.iter().any(|(text, _)| *text == payload) {
+                    let key = format!("plane={plane}, channels={}", channel_names(channels));
+                    candidates.push((payload, Some(key)));
+                }
+            }
+        }
+    }
+    Some(candidates)
+}
+
+/// Names the channels like `rgb` or `g`, for the key of each string we extract
+fn channel_names(channels: &[usize]) -> String {
+    channels
+        .iter()
+        .map(|&channel| ['r', 'g', 'b'][channel])
+        .collect()
+}
+
+/// Decodes a PNG and returns its pixels as 8-bit RGB triplets.
+/// Greyscale images are expanded so each pixel has 3 identical channels,
+/// and alpha channels are dropped as they are rarely used to hide data.
+fn decode_png_to_rgb(image_bytes: &[u8]) -> Option<Vec<[u8; 3]>> {
+    let mut decoder = png::Decoder::new(image_bytes);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    let buffer = &buffer[..info.buffer_size()];
+
+    let samples = info.color_type.samples();
+    let pixels = buffer
+        .chunks_exact(samples)
+        .map(|pixel| match info.color_type {
+            png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => {
+                [pixel[0], pixel[0], pixel[0]]
+            }
+            _ => [pixel[0], pixel[1], pixel[2]],
+        })
+        .collect();
+    Some(pixels)
+}
+
+/// Reads the given bit of each of the given channels of every pixel
+/// and packs them into bytes, most significant bit first.
+fn extract_bit_plane(pixels: &[[u8; 3]], plane: u8, channels: &[usize]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(pixels.len() * channels.len() / 8);
+    let mut current_byte: u8 = 0;
+    let mut bits_in_byte = 0;
+
+    for pixel in pixels {
+        for &channel in channels {
+            current_byte = (current_byte << 1) | ((pixel[channel] >> plane) & 1);
+            bits_in_byte += 1;
+            if bits_in_byte == 8 {
+                bytes.push(current_byte);
+                current_byte = 0;
+                bits_in_byte = 0;
+            }
+        }
+    }
+    bytes
+}
+
+/// Hidden messages are followed by whatever bits the image had before,
+/// so we only keep the printable text at the start of the extracted bytes.
+fn leading_printable_string(bytes: &[u8]) -> Option<String> {
+    let payload: String = bytes
+        .iter()
+        .take_while(|&&byte| byte.is_ascii_graphic() || byte == b' ' || byte == b'\n')
+        .map(|&byte| byte as char)
+        .collect();
+    if payload.trim().len() < MIN_PAYLOAD_LENGTH {
+        return None;
+    }
+    Some(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    // helper which hides the message in the least significant bits of a grey RGB image
+    // and returns it as a PNG data URI
+    fn hide_in_png(message: &str) -> String {
+        let mut bits: Vec<u8> = Vec::new();
+        for byte in message.bytes().chain(std::iter::once(0)) {
+            for i in (0..8).rev() {
+                bits.push((byte >> i) & 1);
+            }
+        }
+        let width = 16;
+        let height = (bits.len() as u32 / 3) / width + 1;
+        let mut image_data = vec![0x80; (width * height * 3) as usize];
+        for (i, bit) in bits.iter().enumerate() {
+            image_data[i] = (image_data[i] & 0xFE) | bit;
+        }
+
+        let mut png_bytes: Vec<u8> = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut png_bytes, width, height);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&image_data).unwrap();
+            writer.finish().unwrap();
+        }
+        format!(
+            "{}{}",
+            PNG_DATA_URI_PREFIX,
+            general_purpose::STANDARD.encode(png_bytes)
+        )
+    }
+
+    #[test]
+    fn successful_decoding() {
+        let lsb_stego_decoder = Decoder::<LsbStegoDecoder>::new();
+        let result = lsb_stego_decoder.crack(
+            &hide_in_png("hello this is a secret message"),
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello this is a secret message"
+        );
+    }
+
+    #[test]
+    fn lsb_stego_ignores_non_data_uri() {
+        let lsb_stego_decoder = Decoder::<LsbStegoDecoder>::new();
+        let result = lsb_stego_decoder
+            .crack("aGVsbG8gd29ybGQ=", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn lsb_stego_handles_broken_png() {
+        let lsb_stego_decoder = Decoder::<LsbStegoDecoder>::new();
+        let result = lsb_stego_decoder
+            .crack(
+                "data:image/png;base64,aGVsbG8gd29ybGQ=",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn lsb_stego_decode_empty_string() {
+        let lsb_stego_decoder = Decoder::<LsbStegoDecoder>::new();
+        let result = lsb_stego_decoder
+            .crack("", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn bit_plane_extraction_packs_msb_first() {
+        // The red and green channels of 4 pixels are 8 channels,
+        // whose lowest bits spell out 0b01000001, which is 'A'
+        let pixels = [[0, 1, 7], [2, 0, 7], [4, 6, 7], [8, 3, 7]];
+        assert_eq!(extract_bit_plane(&pixels, 0, &[0, 1]), vec![b'A']);
+    }
+}
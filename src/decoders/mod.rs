@@ -1,14 +1,26 @@
 //! This module contains all the code for decoders
 //! Think of a decoder as a decryption method that doesn't require a key
 //! The `interface.rs` defines what each decoder looks like.
-//! Once you have made a decoder you need to add it to the filtration system's
-//! mod.rs file
+//! Once you have made a decoder you need to add it to `DECODER_MAP` below,
+//! which the filtration system builds its list of decoders from.
 //! you will also need to make it a public module in this file.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+
+use self::interface::{Crack, Decoder};
+use self::registry::SharedDecoder;
+
 /// The a1z26_decoder module decodes A1Z26
 pub mod a1z26_decoder;
 /// The atbash_decoder module decodes atbash
 pub mod atbash_decoder;
+/// The audio_decoder module listens for Morse code and DTMF tones in WAV files
+/// It is only compiled with the `audio` feature.
+#[cfg(feature = "audio")]
+pub mod audio_decoder;
 /// The base32_decoder module decodes base32
 pub mod base32_decoder;
 /// The base58_bitcoin_decoder module decodes base58 bitcoin
@@ -43,6 +55,19 @@ pub mod crack_results;
 /// The url_decoder module decodes url
 pub mod url_decoder;
 
+/// The lsb_stego_decoder module extracts text hidden in the bit planes of PNG data URIs
+/// It is only compiled with the `stego` feature.
+#[cfg(feature = "stego")]
+pub mod lsb_stego_decoder;
+
+/// The registry module lets library users register their own decoders
+pub mod registry;
+
+/// The wasm_plugin module loads decoders compiled to WebAssembly at runtime
+/// It is only compiled with the `wasm-plugins` feature.
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm_plugin;
+
 /// The interface module defines the interface for decoders
 /// Each and every decoder has the same struct & traits
 pub mod interface;
@@ -61,3 +86,89 @@ pub mod caesar_decoder;
 
 /// For the railfence cipher decoder
 pub mod railfence_decoder;
+
+/// Every built in decoder, keyed by its name.
+/// Decoders don't hold any state so we create each one once and share it.
+/// Decoders registered by library users live in `Config.decoder_registry`,
+/// use `get_decoder_by_name` to look in both.
+pub static DECODER_MAP: Lazy<HashMap<&'static str, SharedDecoder>> = Lazy::new(|| {
+    let decoders: Vec<(&'static str, SharedDecoder)> = vec![
+        shared(Decoder::<reverse_decoder::ReverseDecoder>::new()),
+        shared(Decoder::<base64_decoder::Base64Decoder>::new()),
+        shared(Decoder::<base58_bitcoin_decoder::Base58BitcoinDecoder>::new()),
+        shared(Decoder::<base58_monero_decoder::Base58MoneroDecoder>::new()),
+        shared(Decoder::<base58_ripple_decoder::Base58RippleDecoder>::new()),
+        shared(Decoder::<base58_flickr_decoder::Base58FlickrDecoder>::new()),
+        shared(Decoder::<base91_decoder::Base91Decoder>::new()),
+        shared(Decoder::<base65536_decoder::Base65536Decoder>::new()),
+        shared(Decoder::<binary_decoder::BinaryDecoder>::new()),
+        shared(Decoder::<hexadecimal_decoder::HexadecimalDecoder>::new()),
+        shared(Decoder::<base32_decoder::Base32Decoder>::new()),
+        shared(Decoder::<morse_code::MorseCodeDecoder>::new()),
+        shared(Decoder::<atbash_decoder::AtbashDecoder>::new()),
+        shared(Decoder::<caesar_decoder::CaesarDecoder>::new()),
+        shared(Decoder::<railfence_decoder::RailfenceDecoder>::new()),
+        shared(Decoder::<citrix_ctx1_decoder::CitrixCTX1Decoder>::new()),
+        shared(Decoder::<url_decoder::URLDecoder>::new()),
+        shared(Decoder::<base64_url_decoder::Base64URLDecoder>::new()),
+        shared(Decoder::<a1z26_decoder::A1Z26Decoder>::new()),
+        // Feature-gated decoders are only added when they are compiled in
+        #[cfg(feature = "stego")]
+        shared(Decoder::<lsb_stego_decoder::LsbStegoDecoder>::new()),
+        #[cfg(feature = "audio")]
+        shared(Decoder::<audio_decoder::AudioDecoder>::new()),
+    ];
+
+    decoders.into_iter().collect()
+});
+
+/// Wraps a built in decoder so it can be put in `DECODER_MAP`
+fn shared<T: 'static>(decoder: Decoder<T>) -> (&'static str, SharedDecoder)
+where
+    Decoder<T>: Crack + Sync + Send,
+{
+    (decoder.name, Arc::new(decoder))
+}
+
+/// Finds a decoder by its name, case insensitively.
+/// Decoders in the registry take priority over built in ones.
+pub fn get_decoder_by_name(
+    name: &str,
+    registry: &registry::DecoderRegistry,
+) -> Option<SharedDecoder> {
+    registry.get(name).or_else(|| {
+        DECODER_MAP
+            .iter()
+            .find(|(decoder_name, _)| decoder_name.eq_ignore_ascii_case(name))
+            .map(|(_, decoder)| decoder.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_decoder_by_name, registry::DecoderRegistry, DECODER_MAP};
+
+    #[test]
+    fn decoder_map_is_keyed_by_decoder_name() {
+        for (name, decoder) in DECODER_MAP.iter() {
+            assert_eq!(*name, decoder.get_name());
+        }
+    }
+
+    #[test]
+    fn every_decoder_has_a_description_and_link() {
+        // `ares list-decoders` prints these, so they should never be empty
+        for decoder in DECODER_MAP.values() {
+            assert!(!decoder.get_description().is_empty());
+            assert!(!decoder.get_link().is_empty());
+        }
+    }
+
+    #[test]
+    fn decoders_can_be_found_by_name_case_insensitively() {
+        let registry = DecoderRegistry::new();
+        let decoder = get_decoder_by_name("base64", &registry).expect("Base64 should exist");
+        assert_eq!(decoder.get_name(), "Base64");
+        assert!(get_decoder_by_name("not a real decoder", &registry).is_none());
+    }
+}
@@ -4,6 +4,7 @@ use crate::decoders::interface::check_string_success;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::{info, trace};
 
@@ -17,7 +18,8 @@ impl Crack for Decoder<RailfenceDecoder> {
             description: "The rail fence cipher (also called a zigzag cipher) is a classical type of transposition cipher. It derives its name from the manner in which encryption is performed, in analogy to a fence built with horizontal rails.",
             link: "https://en.wikipedia.org/wiki/Rail_fence_cipher",
             tags: vec!["railfence", "cipher", "classic", "transposition"],
-            popularity: 5.0,
+            popularity: 0.5,
+            expected_runtime: 0.2,
             phantom: std::marker::PhantomData,
         }
     }
@@ -34,8 +36,11 @@ impl Crack for Decoder<RailfenceDecoder> {
             // Should be less than (rail * 2 - 3). This is the max offset
             for offset in 0..=(rails * 2 - 3) {
                 let decoded_text = railfence_decoder(text, rails, offset);
-                decoded_strings.push(decoded_text);
-                let borrowed_decoded_text = &decoded_strings[decoded_strings.len() - 1];
+                decoded_strings.push((
+                    decoded_text,
+                    Some(format!("rails={rails}, offset={offset}")),
+                ));
+                let (borrowed_decoded_text, key) = &decoded_strings[decoded_strings.len() - 1];
                 if !check_string_success(borrowed_decoded_text, text) {
                     info!(
                     "Failed to decode railfence because check_string_success returned false on string {}. This means the string is 'funny' as it wasn't modified.",
@@ -51,12 +56,13 @@ impl Crack for Decoder<RailfenceDecoder> {
                         offset
                     );
                     results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
+                    results.key = key.clone();
                     results.update_checker(&checker_result);
                     return results;
                 }
             }
         }
-        results.unencrypted_text = Some(decoded_strings);
+        results.set_candidates(decoded_strings);
         results
     }
     /// Gets all tags for this decoder
@@ -67,10 +73,35 @@ impl Crack for Decoder<RailfenceDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Cipher,
+            reversibility: Reversibility::KeySearch,
+            charset: Charset::Any,
+            runtime: RuntimeClass::Medium,
+        }
+    }
 }
 
 /// Decodes a text encoded with the Rail Fence Cipher with the specified number of rails and offset
-fn railfence_decoder(text: &str, rails: usize, offset: usize) -> String {
+pub(crate) fn railfence_decoder(text: &str, rails: usize, offset: usize) -> String {
     let mut indexes: Vec<_> = zigzag(rails, offset).zip(1..).take(text.len()).collect();
     indexes.sort();
     let mut char_with_index: Vec<_> = text
@@ -0,0 +1,82 @@
+//! The decoder registry lets library users add their own decoders
+//! without forking Ares.
+//! Register your decoder on the Config you give to `perform_cracking`:
+//! ```rust
+//! use ares::config::Config;
+//! use ares::decoders::interface::{Crack, Decoder};
+//! use ares::decoders::reverse_decoder::ReverseDecoder;
+//!
+//! let mut config = Config::default();
+//! // Any struct which implements Crack can be registered, here we re-use a built in one
+//! config
+//!     .decoder_registry
+//!     .register_decoder(Box::new(Decoder::<ReverseDecoder>::new()));
+//! assert_eq!(config.decoder_registry.decoders().len(), 1);
+//! ```
+
+use std::sync::Arc;
+
+use super::interface::Crack;
+
+/// A decoder which can be shared between the search threads.
+/// Decoders don't hold any state, so one instance of each is enough.
+pub type SharedDecoder = Arc<dyn Crack + Sync + Send>;
+
+/// Holds the custom decoders registered by library users.
+/// The filtration system runs these alongside the built in decoders.
+#[derive(Default, Clone)]
+pub struct DecoderRegistry {
+    /// The decoders registered so far, in the order they were registered
+    decoders: Vec<SharedDecoder>,
+}
+
+impl DecoderRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        DecoderRegistry::default()
+    }
+
+    /// Registers a custom decoder so it is used when cracking.
+    /// If it has the same name as a built in decoder, it replaces it.
+    pub fn register_decoder(&mut self, decoder: Box<dyn Crack + Sync + Send>) {
+        self.decoders.push(Arc::from(decoder));
+    }
+
+    /// Returns all registered decoders
+    pub fn decoders(&self) -> &[SharedDecoder] {
+        &self.decoders
+    }
+
+    /// Finds a registered decoder by its name, case insensitively
+    pub fn get(&self, name: &str) -> Option<SharedDecoder> {
+        self.decoders
+            .iter()
+            .find(|decoder| decoder.get_name().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecoderRegistry;
+    use crate::decoders::{
+        interface::{Crack, Decoder},
+        reverse_decoder::ReverseDecoder,
+    };
+
+    #[test]
+    fn registry_starts_empty() {
+        assert!(DecoderRegistry::new().decoders().is_empty());
+    }
+
+    #[test]
+    fn registered_decoders_can_be_found_by_name() {
+        let mut registry = DecoderRegistry::new();
+        registry.register_decoder(Box::new(Decoder::<ReverseDecoder>::new()));
+        let decoder = registry
+            .get("reverse")
+            .expect("Reverse should be registered");
+        assert_eq!(decoder.get_name(), "Reverse");
+        assert!(registry.get("Base64").is_none());
+    }
+}
@@ -8,6 +8,7 @@ use crate::checkers::CheckerTypes;
 use super::crack_results::CrackResult;
 use super::interface::Crack;
 use super::interface::Decoder;
+use super::interface::{Charset, DecoderCategory, DecoderMetadata, Reversibility, RuntimeClass};
 
 use log::trace;
 /// The Reverse decoder is a decoder that reverses the input string.
@@ -37,6 +38,7 @@ impl Crack for Decoder<ReverseDecoder> {
             // I have never seen a reversed string in a CTF
             // or otherwise
             popularity: 0.2,
+            expected_runtime: 0.01,
             phantom: std::marker::PhantomData,
         }
     }
@@ -65,6 +67,31 @@ impl Crack for Decoder<ReverseDecoder> {
     fn get_name(&self) -> &str {
         self.name
     }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.link
+    }
+    /// Gets what kind of decoder the current decoder is
+    fn get_metadata(&self) -> DecoderMetadata {
+        DecoderMetadata {
+            category: DecoderCategory::Transform,
+            reversibility: Reversibility::SelfInverse,
+            charset: Charset::Any,
+            runtime: RuntimeClass::Fast,
+        }
+    }
 }
 
 #[cfg(test)]
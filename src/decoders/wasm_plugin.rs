@@ -0,0 +1,230 @@
+//! Load decoders compiled to WebAssembly at runtime.
+//! This lets the community ship decoders without recompiling Ares.
+//! This module is only compiled with the `wasm-plugins` feature.
+//!
+//! # The plugin ABI
+//! A plugin is a WASM module which exports:
+//! * `memory` - its linear memory.
+//! * `alloc(len: i32) -> i32` - reserves `len` bytes and returns a pointer to them.
+//!   Ares writes the UTF-8 input text there.
+//! * `crack(ptr: i32, len: i32) -> i64` - decodes the text at `ptr`.
+//!   It returns a pointer to its output in the upper 32 bits and the length of its output
+//!   in the lower 32 bits. The output is UTF-8, with each candidate plaintext separated
+//!   by a NUL byte. Returning a length of 0 means the plugin could not decode the text.
+//!
+//! The plugin does not need to import anything. Each call to `crack` gets a fresh instance,
+//! so plugins do not need to free the memory they hand out.
+//! The name of the decoder is the file name of the plugin, so `rot47.wasm` becomes `rot47`.
+
+use std::path::Path;
+
+use crate::checkers::CheckerTypes;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::Decoder;
+
+use log::{debug, trace};
+use wasmtime::{Engine, Instance, Module, Store};
+
+/// Marks the metadata of a WASM plugin, the decoding is done by [`WasmDecoder`]
+pub struct WasmPlugin;
+
+/// A decoder which runs a WASM plugin, call:
+/// `let decoder = WasmDecoder::from_file("rot47.wasm")?` to load a plugin
+/// And then register it on the config with:
+/// `config.decoder_registry.register_decoder(Box::new(decoder))`
+pub struct WasmDecoder {
+    /// The name, tags, popularity etc. of the plugin
+    decoder: Decoder<WasmPlugin>,
+    /// The engine the plugin was compiled with
+    engine: Engine,
+    /// The compiled plugin. None if no plugin has been loaded yet.
+    module: Option<Module>,
+}
+
+impl WasmDecoder {
+    /// Loads and compiles the plugin at `path`
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid WASM module.
+    pub fn from_file(path: impl AsRef<Path>) -> wasmtime::Result<WasmDecoder> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "WASM plugin".to_string());
+        WasmDecoder::from_bytes(&name, std::fs::read(path)?)
+    }
+
+    /// Compiles a plugin from a WASM binary (or the WAT text format) with the given name
+    /// # Errors
+    /// Returns an error if the bytes are not a valid WASM module.
+    pub fn from_bytes(name: &str, bytes: impl AsRef<[u8]>) -> wasmtime::Result<WasmDecoder> {
+        let mut wasm_decoder = WasmDecoder::new();
+        wasm_decoder.module = Some(Module::new(&wasm_decoder.engine, bytes)?);
+        // Decoder names are &'static str, plugins are only loaded once at start up so leaking is fine
+        wasm_decoder.decoder.name = Box::leak(name.to_string().into_boxed_str());
+        Ok(wasm_decoder)
+    }
+
+    /// Runs the plugin's `crack` function on the text and returns every candidate it gave back
+    fn call_plugin(&self, module: &Module, text: &str) -> wasmtime::Result<Vec<String>> {
+        let mut store = Store::new(&self.engine, ());
+        let instance = Instance::new(&mut store, module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("plugin does not export its memory"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let crack = instance.get_typed_func::<(i32, i32), i64>(&mut store, "crack")?;
+
+        let input = text.as_bytes();
+        let input_length = i32::try_from(input.len())?;
+        let input_pointer = alloc.call(&mut store, input_length)?;
+        memory.write(&mut store, input_pointer as u32 as usize, input)?;
+
+        let packed_output = crack.call(&mut store, (input_pointer, input_length))?;
+        let output_pointer = (packed_output as u64 >> 32) as usize;
+        let output_length = (packed_output as u64 & 0xFFFF_FFFF) as usize;
+        let mut output = vec![0; output_length];
+        memory.read(&store, output_pointer, &mut output)?;
+
+        Ok(String::from_utf8_lossy(&output)
+            .split('\0')
+            .filter(|candidate| !candidate.is_empty() && *candidate != text)
+            .map(|candidate| candidate.to_string())
+            .collect())
+    }
+}
+
+impl Crack for WasmDecoder {
+    /// Creates a decoder with no plugin loaded, which never decodes anything.
+    /// Use [`WasmDecoder::from_file`] to load a plugin.
+    fn new() -> WasmDecoder {
+        WasmDecoder {
+            decoder: Decoder {
+                name: "WASM plugin",
+                description: "A decoder loaded from a WebAssembly plugin.",
+                link: "https://webassembly.org/",
+                tags: vec!["plugin", "wasm", "decoder"],
+                popularity: 0.5,
+                expected_runtime: 0.3,
+                phantom: std::marker::PhantomData,
+            },
+            engine: Engine::default(),
+            module: None,
+        }
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!(
+            "Trying WASM plugin {} with text {:?}",
+            self.decoder.name,
+            text
+        );
+        let mut results = CrackResult::new(&self.decoder, text.to_string());
+
+        let module = match &self.module {
+            Some(module) => module,
+            None => return results,
+        };
+        let candidates = match self.call_plugin(module, text) {
+            Ok(candidates) if !candidates.is_empty() => candidates,
+            Ok(_) => return results,
+            Err(error) => {
+                debug!("WASM plugin {} failed: {}", self.decoder.name, error);
+                return results;
+            }
+        };
+
+        for candidate in &candidates {
+            let checker_result = checker.check(candidate);
+            // If checkers return true, exit early with the correct result
+            if checker_result.is_identified {
+                results.unencrypted_text = Some(vec![candidate.to_string()]);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        }
+
+        results.unencrypted_text = Some(candidates);
+        results
+    }
+    /// Gets all tags for this decoder
+    fn get_tags(&self) -> &Vec<&str> {
+        &self.decoder.tags
+    }
+    /// Gets the name for the current decoder
+    fn get_name(&self) -> &str {
+        self.decoder.name
+    }
+    /// Gets the popularity of the current decoder
+    fn get_popularity(&self) -> f32 {
+        self.decoder.popularity
+    }
+    /// Gets the expected runtime of the current decoder
+    fn get_expected_runtime(&self) -> f32 {
+        self.decoder.expected_runtime
+    }
+    /// Gets the description of the current decoder
+    fn get_description(&self) -> &str {
+        self.decoder.description
+    }
+    /// Gets the link to more info about the current decoder
+    fn get_link(&self) -> &str {
+        self.decoder.link
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    // A plugin which ignores its input and always returns two candidates
+    const CONSTANT_PLUGIN: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "hello world\00aGVsbG8=")
+            (func (export "alloc") (param i32) (result i32) i32.const 1024)
+            (func (export "crack") (param i32 i32) (result i64) i64.const 20))
+    "#;
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn plugin_candidates_are_returned() {
+        let decoder = WasmDecoder::from_bytes("constant", CONSTANT_PLUGIN).unwrap();
+        assert_eq!(decoder.get_name(), "constant");
+        let result = decoder.crack("anything", &get_athena_checker());
+        assert_eq!(result.decoder, "constant");
+        assert!(result
+            .unencrypted_text
+            .unwrap()
+            .contains(&"hello world".to_string()));
+    }
+
+    #[test]
+    fn plugin_without_crack_export_fails_gracefully() {
+        let decoder =
+            WasmDecoder::from_bytes("broken", r#"(module (memory (export "memory") 1))"#).unwrap();
+        let result = decoder.crack("anything", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn unloaded_plugin_decodes_nothing() {
+        let result = WasmDecoder::new().crack("anything", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+}
@@ -2,32 +2,21 @@
 //! Given a filter object, return an array of decoders/crackers which have been filtered
 
 use std::sync::mpsc::channel;
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+// std can't read the clock in a browser, web-time reads it from JavaScript there
+#[cfg(feature = "wasm")]
+use web_time::Instant;
 
 use crate::checkers::CheckerTypes;
-use crate::decoders::atbash_decoder::AtbashDecoder;
-use crate::decoders::base32_decoder::Base32Decoder;
-use crate::decoders::base58_bitcoin_decoder::Base58BitcoinDecoder;
-use crate::decoders::base58_monero_decoder::Base58MoneroDecoder;
-use crate::decoders::binary_decoder::BinaryDecoder;
-use crate::decoders::hexadecimal_decoder::HexadecimalDecoder;
-use crate::DecoderResult;
-
-use crate::decoders::base58_flickr_decoder::Base58FlickrDecoder;
-use crate::decoders::base58_ripple_decoder::Base58RippleDecoder;
-
-use crate::decoders::a1z26_decoder::A1Z26Decoder;
-use crate::decoders::base64_decoder::Base64Decoder;
-use crate::decoders::base64_url_decoder::Base64URLDecoder;
-use crate::decoders::base65536_decoder::Base65536Decoder;
-use crate::decoders::base91_decoder::Base91Decoder;
-use crate::decoders::caesar_decoder::CaesarDecoder;
-use crate::decoders::citrix_ctx1_decoder::CitrixCTX1Decoder;
+use crate::config::Config;
 use crate::decoders::crack_results::CrackResult;
-use crate::decoders::interface::{Crack, Decoder};
-use crate::decoders::morse_code::MorseCodeDecoder;
-use crate::decoders::railfence_decoder::RailfenceDecoder;
-use crate::decoders::reverse_decoder::ReverseDecoder;
-use crate::decoders::url_decoder::URLDecoder;
+use crate::decoders::interface::Reversibility;
+use crate::decoders::registry::{DecoderRegistry, SharedDecoder};
+use crate::decoders::DECODER_MAP;
+use crate::searchers::context::SearchContext;
+use crate::searchers::decoding_cache::DecodingCache;
+use crate::DecoderResult;
 
 use log::trace;
 use rayon::prelude::*;
@@ -40,7 +29,7 @@ use rayon::prelude::*;
 /// Relevant docs: https://doc.rust-lang.org/book/ch17-02-trait-objects.html
 pub struct Decoders {
     /// Components is a vector of decoders.
-    pub components: Vec<Box<dyn Crack + Sync>>,
+    pub components: Vec<SharedDecoder>,
 }
 
 impl Decoders {
@@ -51,19 +40,48 @@ impl Decoders {
     /// https://doc.rust-lang.org/book/ch17-02-trait-objects.html
     /// Which allows us to have multiple different structs in the same vector
     /// But each struct shares the same `.crack()` method, so it's fine.
-    pub fn run(&self, text: &str, checker: CheckerTypes) -> MyResults {
+    /// Decoders whose preconditions the text doesn't meet are skipped, see [`Preconditions`](crate::decoders::interface::Preconditions).
+    /// Decoders which already decoded this text during the search are looked up in the cache instead.
+    /// How long each decoder took is recorded in the context of the run for `--stats`.
+    /// Outputs which are the same as the text, like the reverse of a palindrome, are dropped.
+    pub fn run(
+        &self,
+        text: &str,
+        checker: CheckerTypes,
+        cache: &DecodingCache,
+        context: &SearchContext,
+    ) -> MyResults {
         trace!("Running .crack() on all decoders");
+        // The decoders run on rayon's threads, which don't know which node is being expanded
+        let expansion = tracing::Span::current();
         let (sender, receiver) = channel();
         self.components
-            .into_par_iter()
+            .par_iter()
+            .filter(|decoder| {
+                let can_decode = decoder.get_preconditions().are_met_by(text);
+                if !can_decode {
+                    trace!(
+                        "Skipping {} as it can't decode the text",
+                        decoder.get_name()
+                    );
+                }
+                can_decode
+            })
             .try_for_each_with(sender, |s, i| {
-                let results = i.crack(text, &checker);
+                let _span =
+                    tracing::trace_span!(parent: &expansion, "decoder", name = i.get_name())
+                        .entered();
+                let start = Instant::now();
+                let own_checker = checker.after_decoder(i.get_name());
+                let mut results = cache.crack(i, text, own_checker.as_ref().unwrap_or(&checker));
+                context.decoder_runs.record(i.get_name(), start.elapsed());
                 if results.success {
                     s.send(results).expect("expected no send error!");
                     // returning None short-circuits the iterator
                     // we don't process any further as we got success
                     return None;
                 }
+                drop_unchanged_outputs(&mut results, text);
                 s.send(results).expect("expected no send error!");
                 // return Some(()) to indicate that continue processing
                 Some(())
@@ -81,6 +99,57 @@ impl Decoders {
 
         MyResults::Continue(all_results)
     }
+
+    /// Like [`Decoders::run`], but the results are in the order of the decoders rather than
+    /// the order they finish in, and if several decoders find the plaintext the first one wins.
+    /// Every decoder runs even if one finds the plaintext, so this is only used with `--deterministic`.
+    pub fn run_in_order(
+        &self,
+        text: &str,
+        checker: CheckerTypes,
+        cache: &DecodingCache,
+        context: &SearchContext,
+    ) -> MyResults {
+        trace!("Running .crack() on all decoders in order");
+        // The decoders run on rayon's threads, which don't know which node is being expanded
+        let expansion = tracing::Span::current();
+        let mut all_results: Vec<CrackResult> = self
+            .components
+            .par_iter()
+            .filter(|decoder| decoder.get_preconditions().are_met_by(text))
+            .map(|i| {
+                let _span =
+                    tracing::trace_span!(parent: &expansion, "decoder", name = i.get_name())
+                        .entered();
+                let start = Instant::now();
+                let own_checker = checker.after_decoder(i.get_name());
+                let mut results = cache.crack(i, text, own_checker.as_ref().unwrap_or(&checker));
+                context.decoder_runs.record(i.get_name(), start.elapsed());
+                if !results.success {
+                    drop_unchanged_outputs(&mut results, text);
+                }
+                results
+            })
+            .collect();
+
+        match all_results.iter().position(|result| result.success) {
+            Some(first_success) => MyResults::Break(all_results.swap_remove(first_success)),
+            None => MyResults::Continue(all_results),
+        }
+    }
+}
+
+/// Removes the outputs of a decoder which are the same as the text it decoded.
+/// They would only add a useless step to the path, and every decoder would be tried on them again.
+fn drop_unchanged_outputs(result: &mut CrackResult, text: &str) {
+    if let Some(outputs) = &mut result.unencrypted_text {
+        outputs.retain(|output| output != text);
+        result.candidates.retain(|candidate| candidate.text != text);
+        if outputs.is_empty() {
+            trace!("{} returned the text unchanged", result.decoder);
+            result.unencrypted_text = None;
+        }
+    }
 }
 
 /// [`Enum`] for our custom results.
@@ -104,51 +173,87 @@ impl MyResults {
     }
 }
 
-/// Currently takes no args as this is just a spike to get all the basic functionality working
-pub fn filter_and_get_decoders(_text_struct: &DecoderResult) -> Decoders {
+/// Returns the decoders to run on `text_struct`, the most promising first.
+/// These are the decoders the user hasn't turned off, without the self inverse decoder
+/// `text_struct` was just decoded with, as running it again would only undo it.
+pub fn filter_and_get_decoders(text_struct: &DecoderResult, config: &Config) -> Decoders {
     trace!("Filtering and getting all decoders");
-    let binary = Decoder::<BinaryDecoder>::new();
-    let hexadecimal = Decoder::<HexadecimalDecoder>::new();
-    let base58_bitcoin = Decoder::<Base58BitcoinDecoder>::new();
-    let base58_monero = Decoder::<Base58MoneroDecoder>::new();
-    let base58_ripple = Decoder::<Base58RippleDecoder>::new();
-    let base58_flickr = Decoder::<Base58FlickrDecoder>::new();
-    let base64 = Decoder::<Base64Decoder>::new();
-    let base91 = Decoder::<Base91Decoder>::new();
-    let base64_url = Decoder::<Base64URLDecoder>::new();
-    let base65536 = Decoder::<Base65536Decoder>::new();
-    let citrix_ctx1 = Decoder::<CitrixCTX1Decoder>::new();
-    let url = Decoder::<URLDecoder>::new();
-    let base32 = Decoder::<Base32Decoder>::new();
-    let reversedecoder = Decoder::<ReverseDecoder>::new();
-    let morsecodedecoder = Decoder::<MorseCodeDecoder>::new();
-    let atbashdecoder = Decoder::<AtbashDecoder>::new();
-    let caesardecoder = Decoder::<CaesarDecoder>::new();
-    let railfencedecoder = Decoder::<RailfenceDecoder>::new();
-    let a1z26decoder = Decoder::<A1Z26Decoder>::new();
-    Decoders {
-        components: vec![
-            Box::new(reversedecoder),
-            Box::new(base64),
-            Box::new(base58_bitcoin),
-            Box::new(base58_monero),
-            Box::new(base58_ripple),
-            Box::new(base58_flickr),
-            Box::new(base91),
-            Box::new(base65536),
-            Box::new(binary),
-            Box::new(hexadecimal),
-            Box::new(base32),
-            Box::new(morsecodedecoder),
-            Box::new(atbashdecoder),
-            Box::new(caesardecoder),
-            Box::new(railfencedecoder),
-            Box::new(citrix_ctx1),
-            Box::new(url),
-            Box::new(base64_url),
-            Box::new(a1z26decoder),
-        ],
+    let mut components = all_decoders(&config.decoder_registry);
+    filter_decoders_by_name_or_tag(
+        &mut components,
+        &config.enabled_decoders,
+        &config.disabled_decoders,
+    );
+    if let Some(last) = text_struct.path.last() {
+        components.retain(|decoder| {
+            decoder.get_name() != last.decoder
+                || decoder.get_metadata().reversibility != Reversibility::SelfInverse
+        });
     }
+    sort_decoders_by_priority(&mut components);
+    Decoders { components }
+}
+
+/// Returns every built in decoder plus the ones registered by library users.
+/// A registered decoder with the same name as a built in one replaces it.
+fn all_decoders(registry: &DecoderRegistry) -> Vec<SharedDecoder> {
+    let registered = registry.decoders();
+    DECODER_MAP
+        .iter()
+        .filter(|(name, _)| {
+            !registered
+                .iter()
+                .any(|decoder| decoder.get_name().eq_ignore_ascii_case(name))
+        })
+        .map(|(_, decoder)| decoder.clone())
+        .chain(registered.iter().cloned())
+        .collect()
+}
+
+/// Removes every decoder which is not in `enabled` (unless it is empty)
+/// and every decoder which is in `disabled`.
+/// Both lists can contain decoder names ("Base64"), tags ("base") or the labels of their
+/// metadata ("cipher", "slow"), and are case insensitive.
+fn filter_decoders_by_name_or_tag(
+    components: &mut Vec<SharedDecoder>,
+    enabled: &[String],
+    disabled: &[String],
+) {
+    components.retain(|decoder| {
+        (enabled.is_empty() || decoder_matches_any(decoder, enabled))
+            && !decoder_matches_any(decoder, disabled)
+    });
+    trace!("{} decoders left after filtering", components.len());
+}
+
+/// Returns true if the decoder's name, one of its tags or one of its metadata labels is in the list
+fn decoder_matches_any(decoder: &SharedDecoder, names_or_tags: &[String]) -> bool {
+    let labels = decoder.get_metadata().labels();
+    names_or_tags.iter().any(|wanted| {
+        decoder.get_name().eq_ignore_ascii_case(wanted)
+            || decoder
+                .get_tags()
+                .iter()
+                .chain(labels.iter())
+                .any(|tag| tag.eq_ignore_ascii_case(wanted))
+    })
+}
+
+/// Sorts the decoders so the most popular run first,
+/// and out of equally popular decoders the cheapest run first.
+/// This means common cheap decoders like base64 and hex are tried
+/// before rare or expensive ones like the Caesar cipher.
+/// Ties are broken by name so the order is the same every run.
+fn sort_decoders_by_priority(components: &mut [SharedDecoder]) {
+    components.sort_by(|a, b| {
+        b.get_popularity()
+            .total_cmp(&a.get_popularity())
+            .then(
+                a.get_expected_runtime()
+                    .total_cmp(&b.get_expected_runtime()),
+            )
+            .then(a.get_name().cmp(b.get_name()))
+    });
 }
 
 #[cfg(test)]
@@ -157,27 +262,197 @@ mod tests {
         checkers::{
             athena::Athena,
             checker_type::{Check, Checker},
+            default_checker::DefaultChecker,
             CheckerTypes,
         },
+        searchers::context::SearchContext,
         DecoderResult,
     };
 
     // TODO: when we add a proper filtration system
     // We need to test that.
-    use super::filter_and_get_decoders;
+    use super::{
+        all_decoders, drop_unchanged_outputs, filter_and_get_decoders,
+        filter_decoders_by_name_or_tag, MyResults,
+    };
+    use crate::config::Config;
+    use crate::decoders::{
+        base32_decoder::Base32Decoder,
+        citrix_ctx1_decoder::CitrixCTX1Decoder,
+        crack_results::CrackResult,
+        interface::{Crack, Decoder, DecoderCategory, RuntimeClass},
+        registry::DecoderRegistry,
+        reverse_decoder::ReverseDecoder,
+        DECODER_MAP,
+    };
+    use crate::searchers::decoding_cache::DecodingCache;
 
     #[test]
     fn it_works() {
-        let _decoders = filter_and_get_decoders(&DecoderResult::default());
+        let _decoders = filter_and_get_decoders(&DecoderResult::default(), &Config::default());
         assert_eq!(2 + 2, 4);
     }
 
+    #[test]
+    fn decoders_are_sorted_by_popularity_then_runtime() {
+        let decoders = filter_and_get_decoders(&DecoderResult::default(), &Config::default());
+        for pair in decoders.components.windows(2) {
+            let (first, second) = (&pair[0], &pair[1]);
+            assert!(first.get_popularity() >= second.get_popularity());
+            if first.get_popularity() == second.get_popularity() {
+                assert!(first.get_expected_runtime() <= second.get_expected_runtime());
+            }
+        }
+    }
+
+    #[test]
+    fn only_keeps_decoders_with_matching_name_or_tag() {
+        let mut components =
+            filter_and_get_decoders(&DecoderResult::default(), &Config::default()).components;
+        filter_decoders_by_name_or_tag(
+            &mut components,
+            &["base".to_string(), "reverse".to_string()],
+            &[],
+        );
+        assert!(!components.is_empty());
+        assert!(
+            components
+                .iter()
+                .all(|decoder| decoder.get_name() == "Reverse"
+                    || decoder.get_tags().contains(&"base"))
+        );
+    }
+
+    #[test]
+    fn exclude_removes_decoders_case_insensitively() {
+        let mut components =
+            filter_and_get_decoders(&DecoderResult::default(), &Config::default()).components;
+        let number_of_decoders = components.len();
+        filter_decoders_by_name_or_tag(&mut components, &[], &["CAESAR".to_string()]);
+        assert_eq!(components.len(), number_of_decoders - 1);
+        assert!(components
+            .iter()
+            .all(|decoder| decoder.get_name() != "Caesar Cipher"));
+    }
+
+    #[test]
+    fn unchanged_outputs_are_dropped() {
+        let reverse = Decoder::<ReverseDecoder>::new();
+        let mut palindrome = CrackResult::new(&reverse, "racecar".to_string());
+        palindrome.unencrypted_text = Some(vec!["racecar".to_string()]);
+        drop_unchanged_outputs(&mut palindrome, "racecar");
+        assert!(palindrome.unencrypted_text.is_none());
+
+        let mut shifted = CrackResult::new(&reverse, "a!b".to_string());
+        shifted.unencrypted_text = Some(vec!["a!b".to_string(), "b!c".to_string()]);
+        drop_unchanged_outputs(&mut shifted, "a!b");
+        assert_eq!(shifted.unencrypted_text, Some(vec!["b!c".to_string()]));
+    }
+
+    #[test]
+    fn decoders_can_be_filtered_by_metadata() {
+        let mut components =
+            filter_and_get_decoders(&DecoderResult::default(), &Config::default()).components;
+        filter_decoders_by_name_or_tag(&mut components, &["cipher".to_string()], &[]);
+        assert!(components
+            .iter()
+            .any(|decoder| decoder.get_name() == "Caesar Cipher"));
+        assert!(components
+            .iter()
+            .all(|decoder| decoder.get_metadata().category == DecoderCategory::Cipher));
+
+        let mut components =
+            filter_and_get_decoders(&DecoderResult::default(), &Config::default()).components;
+        filter_decoders_by_name_or_tag(&mut components, &[], &["slow".to_string()]);
+        assert!(components
+            .iter()
+            .all(|decoder| decoder.get_metadata().runtime != RuntimeClass::Slow));
+    }
+
+    #[test]
+    fn self_inverse_decoders_do_not_undo_themselves() {
+        let mut reversed = DecoderResult::_new("olleh");
+        reversed.path = vec![std::sync::Arc::new(CrackResult::new(
+            &Decoder::<ReverseDecoder>::new(),
+            "hello".to_string(),
+        ))];
+        let decoders = filter_and_get_decoders(&reversed, &Config::default()).components;
+        assert!(decoders
+            .iter()
+            .all(|decoder| decoder.get_name() != "Reverse"));
+        // Atbash is self inverse too, but it wasn't the last decoder so it can still run
+        assert!(decoders
+            .iter()
+            .any(|decoder| decoder.get_name() == "Atbash"));
+    }
+
+    #[test]
+    fn registered_decoders_are_included() {
+        assert_eq!(
+            all_decoders(&DecoderRegistry::new()).len(),
+            DECODER_MAP.len()
+        );
+
+        // Registering a decoder with the same name replaces the built in one
+        let mut registry = DecoderRegistry::new();
+        registry.register_decoder(Box::new(Decoder::<ReverseDecoder>::new()));
+        let decoders = all_decoders(&registry);
+        assert_eq!(decoders.len(), DECODER_MAP.len());
+        assert_eq!(
+            decoders
+                .iter()
+                .filter(|decoder| decoder.get_name() == "Reverse")
+                .count(),
+            1
+        );
+    }
+
     #[test]
     fn decoders_can_call_dot_run() {
-        let decoders = filter_and_get_decoders(&DecoderResult::default());
+        let decoders = filter_and_get_decoders(&DecoderResult::default(), &Config::default());
         let athena_checker = Checker::<Athena>::new();
         let checker = CheckerTypes::CheckAthena(athena_checker);
-        decoders.run("TXIgUm9ib3QgaXMgZ3JlYXQ=", checker);
+        decoders.run(
+            "TXIgUm9ib3QgaXMgZ3JlYXQ=",
+            checker,
+            &DecodingCache::new(0),
+            &SearchContext::default(),
+        );
         assert_eq!(true, true);
     }
+
+    #[test]
+    fn preconditions_rule_out_text_the_decoder_cannot_decode() {
+        let citrix_ctx1 = Decoder::<CitrixCTX1Decoder>::new().get_preconditions();
+        assert!(!citrix_ctx1.are_met_by("ABCDEF"));
+        assert!(citrix_ctx1.are_met_by("ABCDEFGH"));
+
+        let base32 = Decoder::<Base32Decoder>::new().get_preconditions();
+        assert!(!base32.are_met_by("nbswy3dp"));
+        assert!(base32.are_met_by("NBSWY3DP"));
+    }
+
+    #[test]
+    fn run_in_order_keeps_the_order_of_the_decoders() {
+        let decoders = filter_and_get_decoders(&DecoderResult::default(), &Config::default());
+        let checker = CheckerTypes::CheckDefault(Checker::<DefaultChecker>::new());
+        let text = "aGVsbG8gd29ybGQ=";
+        let expected: Vec<&str> = decoders
+            .components
+            .iter()
+            .filter(|decoder| decoder.get_preconditions().are_met_by(text))
+            .map(|decoder| decoder.get_name())
+            .collect();
+
+        let MyResults::Continue(results) = decoders.run_in_order(
+            text,
+            checker,
+            &DecodingCache::new(0),
+            &SearchContext::default(),
+        ) else {
+            panic!("the Default Checker never identifies the plaintext");
+        };
+        let names: Vec<&str> = results.iter().map(|result| result.decoder).collect();
+        assert_eq!(names, expected);
+    }
 }
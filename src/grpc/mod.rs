@@ -0,0 +1,254 @@
+//! `ares grpc` runs Ares as a gRPC service, so SOC pipelines can send it texts to crack.
+//! The service is defined in `proto/ares.proto`. `Crack` streams each plaintext as it is found,
+//! like top results mode does in the terminal, so a pipeline can act on the first plaintext
+//! while Ares keeps looking for others.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use log::debug;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+use crate::config::Config;
+use crate::{perform_cracking_with_cancel, DecoderResult};
+
+/// The code tonic generates from `proto/ares.proto`
+#[allow(missing_docs, clippy::missing_docs_in_private_items)]
+mod proto {
+    tonic::include_proto!("ares");
+}
+
+use proto::ares_server::{Ares, AresServer};
+use proto::{Candidate, CrackRequest, Step};
+
+/// The timeout for requests which don't ask for one, in seconds
+const DEFAULT_TIMEOUT: u32 = 5;
+
+/// How many plaintexts wait to be sent to a slow client before the search waits for it
+const STREAM_BUFFER: usize = 16;
+
+/// What the service allows, from the `ares grpc` arguments
+pub struct GrpcOptions {
+    /// The port to listen on
+    pub port: u16,
+    /// How many texts can be cracked at once, requests over this are turned away
+    pub max_concurrent: usize,
+    /// The longest timeout a request can ask for, in seconds
+    pub max_timeout: u32,
+}
+
+impl CrackRequest {
+    /// The config to crack this request with, in top results mode so every plaintext is streamed.
+    /// Nobody is there to answer the human checker, so it is off.
+    fn config(&self, max_timeout: u32) -> Config {
+        Config {
+            timeout: match self.timeout {
+                0 => DEFAULT_TIMEOUT.min(max_timeout),
+                timeout => timeout.min(max_timeout),
+            },
+            api_mode: true,
+            human_checker_on: false,
+            top_results: true,
+            top_results_limit: (self.limit > 0).then_some(self.limit as usize),
+            enabled_decoders: self.only.clone(),
+            disabled_decoders: self.exclude.clone(),
+            regex: self.regex.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&DecoderResult> for Candidate {
+    fn from(result: &DecoderResult) -> Self {
+        Candidate {
+            text: result.text[0].clone(),
+            path: result
+                .path
+                .iter()
+                .map(|step| Step {
+                    decoder: step.decoder.to_string(),
+                    key: step.key.clone().unwrap_or_default(),
+                    checker: step.checker_name.to_string(),
+                })
+                .collect(),
+            confidence: result.confidence,
+        }
+    }
+}
+
+/// Counts a crack as running until it is dropped, even if the crack panics
+struct InFlight(Arc<AtomicUsize>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The gRPC service, each request is cracked in a run of its own
+struct AresService {
+    /// What the service allows
+    options: GrpcOptions,
+    /// How many texts are being cracked right now
+    in_flight: Arc<AtomicUsize>,
+}
+
+#[tonic::async_trait]
+impl Ares for AresService {
+    type CrackStream = ReceiverStream<Result<Candidate, Status>>;
+
+    async fn crack(
+        &self,
+        request: Request<CrackRequest>,
+    ) -> Result<Response<Self::CrackStream>, Status> {
+        let request = request.into_inner();
+        // Each crack uses every core, so we only run a few at once
+        if self.in_flight.fetch_add(1, Ordering::SeqCst) >= self.options.max_concurrent {
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            return Err(Status::resource_exhausted(
+                "Ares is busy cracking other texts, try again soon",
+            ));
+        }
+        let in_flight = InFlight(self.in_flight.clone());
+
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut config = request.config(self.options.max_timeout);
+        let candidates = sender.clone();
+        let client_gone = cancel.clone();
+        config.top_result_callback = Some(Arc::new(move |result: &DecoderResult| {
+            // The search runs on its own threads, so it can wait here for the client to catch up
+            if candidates.blocking_send(Ok(result.into())).is_err() {
+                debug!("The client stopped listening, cancelling the search");
+                client_gone.store(true, Ordering::Relaxed);
+            }
+        }));
+        tokio::task::spawn_blocking(move || {
+            let _in_flight = in_flight;
+            // The plaintexts have been streamed already, so only errors are left to send
+            if let Err(error) = perform_cracking_with_cancel(&request.text, config, cancel) {
+                sender
+                    .blocking_send(Err(Status::invalid_argument(error.to_string())))
+                    .ok();
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+/// Answers requests until the process is killed.
+/// # Errors
+/// Returns an error if the service can't listen on the port
+pub fn serve(options: GrpcOptions) -> Result<(), String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|error| error.to_string())?;
+    let address = ([0, 0, 0, 0], options.port).into();
+    let service = AresService {
+        options,
+        in_flight: Arc::default(),
+    };
+    runtime
+        .block_on(
+            Server::builder()
+                .add_service(AresServer::new(service))
+                .serve(address),
+        )
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use super::*;
+
+    /// Sends the request to a service and collects everything it streams back, or the error code
+    fn crack(request: CrackRequest, max_concurrent: usize) -> Result<Vec<Candidate>, tonic::Code> {
+        let service = AresService {
+            options: GrpcOptions {
+                port: 0,
+                max_concurrent,
+                max_timeout: 30,
+            },
+            in_flight: Arc::default(),
+        };
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime
+            .block_on(async {
+                let stream = service.crack(Request::new(request)).await?.into_inner();
+                stream.collect::<Result<Vec<_>, _>>().await
+            })
+            .map_err(|error| error.code())
+    }
+
+    #[test]
+    fn crack_requests_only_need_text() {
+        let request = CrackRequest {
+            text: "aGVsbG8=".to_string(),
+            ..Default::default()
+        };
+        let config = request.config(30);
+        assert_eq!(config.timeout, DEFAULT_TIMEOUT);
+        assert!(config.top_results);
+        assert_eq!(config.top_results_limit, None);
+        assert!(!config.human_checker_on);
+    }
+
+    #[test]
+    fn crack_request_timeouts_are_capped() {
+        let request = CrackRequest {
+            text: "aGVsbG8=".to_string(),
+            timeout: 600,
+            limit: 2,
+            ..Default::default()
+        };
+        let config = request.config(30);
+        assert_eq!(config.timeout, 30);
+        assert_eq!(config.top_results_limit, Some(2));
+    }
+
+    #[test]
+    fn plaintexts_are_streamed_as_they_are_found() {
+        let candidates = crack(
+            CrackRequest {
+                text: "aGVsbG8gdGhlcmUgZ2VuZXJhbA==".to_string(),
+                limit: 1,
+                ..Default::default()
+            },
+            1,
+        )
+        .unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].text, "hello there general");
+        assert_eq!(candidates[0].path.last().unwrap().decoder, "Base64");
+    }
+
+    #[test]
+    fn invalid_requests_end_the_stream_with_an_error() {
+        let code = crack(
+            CrackRequest {
+                text: "aGVsbG8=".to_string(),
+                regex: vec!["(".to_string()],
+                ..Default::default()
+            },
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(code, tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn requests_over_the_limit_are_turned_away() {
+        let code = crack(
+            CrackRequest {
+                text: "aGVsbG8=".to_string(),
+                ..Default::default()
+            },
+            0,
+        )
+        .unwrap_err();
+        assert_eq!(code, tonic::Code::ResourceExhausted);
+    }
+}
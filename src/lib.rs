@@ -19,21 +19,32 @@ mod cli_input_parser;
 /// The CLI Pretty Printing module contains the functions that print the results
 pub mod cli_pretty_printing;
 /// The Config module enables a configuration module
-/// Like a global API to access config details
+/// Each run of Ares gets its own config
 pub mod config;
+/// Replays CyberChef recipes with our decoders instead of searching
+pub mod cyberchef;
 /// Decoders are the functions that actually perform the decodings.
 pub mod decoders;
 /// The filtration system builds what decoders to use at runtime
 /// By default it will use them all.
 mod filtration_system;
+/// Runs Ares as a gRPC service with `ares grpc`
+#[cfg(feature = "grpc")]
+pub mod grpc;
 /// The searcher is the thing which searches for the plaintext
 /// It is the core of the program.
 mod searchers;
+/// Runs Ares as a REST API with `ares serve`
+#[cfg(feature = "server")]
+pub mod server;
 /// The storage module contains all the dictionaries and provides
 /// storage of data to our decoderrs and checkers.
 mod storage;
 /// Timer for internal use
 mod timer;
+/// Runs Ares in a browser with a `crack` function for JavaScript
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 use checkers::{
     athena::Athena,
@@ -41,10 +52,19 @@ use checkers::{
     checker_type::{Check, Checker},
 };
 use log::debug;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
-use crate::{config::Config, decoders::interface::Decoder};
+use crate::storage::quadgrams::ENGLISH_QUADGRAMS;
+use crate::{config::Config, decoders::interface::Decoder, searchers::context::SearchContext};
 
 use self::decoders::crack_results::CrackResult;
+pub use self::storage::decoder_stats::DecoderStats;
+pub use self::storage::search_tree::{search_tree_as_dot, SearchTreeNode};
 /// The main function to call which performs the cracking.
 /// ```rust
 /// use ares::perform_cracking;
@@ -57,48 +77,218 @@ use self::decoders::crack_results::CrackResult;
 /// config.verbose = 0;
 /// let result = perform_cracking("VGhlIG1haW4gZnVuY3Rpb24gdG8gY2FsbCB3aGljaCBwZXJmb3JtcyB0aGUgY3JhY2tpbmcu", config);
 /// assert!(true);
-/// // The result is an Option<DecoderResult> so we need to unwrap it
+/// // The result is a Result<CrackOutcome, AresError>, the outcome says how the search ended
+/// // `into_plaintext` gives the DecoderResult if the search found the plaintext
 /// // The DecoderResult contains the text and the path
 /// // The path is a vector of CrackResults which contains the decoder used and the keys used
 /// // The text is a vector of strings because some decoders return more than 1 text (Caesar)
 /// // Becuase the program has returned True, the first result is the plaintext (and it will only have 1 result).
 /// // This is some tech debt we need to clean up https://github.com/bee-san/Ares/issues/130
-/// assert!(result.unwrap().text[0] == "The main function to call which performs the cracking.");
+/// let plaintext = result.unwrap().into_plaintext();
+/// assert!(plaintext.unwrap().text[0] == "The main function to call which performs the cracking.");
 /// ```
 /// The human checker defaults to off in the config, but it returns the first thing it finds currently.
 /// We have an issue for that here https://github.com/bee-san/Ares/issues/129
 /// ```rust
-/// use ares::perform_cracking;
+/// use ares::{perform_cracking, CrackOutcome};
 /// use ares::config::Config;
 /// let mut config = Config::default();
 /// // You can set the config to your liking using the Config struct
 /// // Just edit the data like below if you want:
+/// // A timeout of 0 means there is no timeout, so the search only stops when it finds the plaintext
+/// // or has decoded everything it can. If it runs out of things to decode it returns `Exhausted`
 /// config.timeout = 0;
 /// let result = perform_cracking("VGhlIG1haW4gZnVuY3Rpb24gdG8gY2FsbCB3aGljaCBwZXJmb3JtcyB0aGUgY3JhY2tpbmcu", config);
-/// assert!(true);
-/// // If the program times out, or it cannot decode the text it will return None.
-/// assert!(result.is_none());
+/// assert!(matches!(result, Ok(CrackOutcome::Cracked(_))));
+/// ```
+/// Each call uses the config it is given and keeps its own state while it searches,
+/// so texts can be cracked with different configs in the same program, even at the same time.
+/// # Errors
+/// Returns an error if the config is invalid, like a regex that doesn't compile,
+/// if the CyberChef recipe in the config can't be replayed,
+/// or if the checkpoint to resume from can't be read.
+pub fn perform_cracking(
+    text: &str,
+    config: impl Into<Arc<Config>>,
+) -> Result<CrackOutcome, AresError> {
+    perform_cracking_with_report(text, config).map(|report| report.outcome)
+}
+
+/// Like [`perform_cracking`], but also returns what the search found out on the way,
+/// like how deep it got and the decodings which looked most like plaintext.
+/// ```rust
+/// use ares::{perform_cracking_with_report, CrackOutcome};
+/// use ares::config::Config;
+/// let mut config = Config::default();
+/// config.max_depth = Some(1);
+/// let report = perform_cracking_with_report("qzkx vbnw plmr", config).unwrap();
+/// if matches!(report.outcome, CrackOutcome::Exhausted) {
+///     assert!(!report.closest_attempts.is_empty());
+/// }
+/// ```
+/// # Errors
+/// Returns an error in the same cases as [`perform_cracking`].
+pub fn perform_cracking_with_report(
+    text: &str,
+    config: impl Into<Arc<Config>>,
+) -> Result<CrackReport, AresError> {
+    crack_text(text, &config.into(), &AtomicBool::new(false))
+}
+
+/// Like [`perform_cracking`], but the search stops as soon as `cancel` is set to true.
+/// This lets the TUI and other embedders abort a search straight away, like when the user presses Esc.
+/// A cancelled search returns `CrackOutcome::Cancelled`.
+/// ```rust
+/// use ares::{perform_cracking_with_cancel, CrackOutcome};
+/// use ares::config::Config;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+/// let cancel = Arc::new(AtomicBool::new(false));
+/// let cancel_from_another_thread = cancel.clone();
+/// std::thread::spawn(move || cancel_from_another_thread.store(true, Ordering::Relaxed));
+/// let result = perform_cracking_with_cancel("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", Config::default(), cancel);
+/// // The search might find the plaintext before it is cancelled
+/// match result.unwrap() {
+///     CrackOutcome::Cracked(result) => assert_eq!(result.text[0], "hello there general"),
+///     outcome => assert!(matches!(outcome, CrackOutcome::Cancelled)),
+/// }
 /// ```
-pub fn perform_cracking(text: &str, config: Config) -> Option<DecoderResult> {
-    config::set_global_config(config);
+/// # Errors
+/// Returns an error in the same cases as [`perform_cracking`].
+pub fn perform_cracking_with_cancel(
+    text: &str,
+    config: impl Into<Arc<Config>>,
+    cancel: Arc<AtomicBool>,
+) -> Result<CrackOutcome, AresError> {
+    crack_text(text, &config.into(), &cancel).map(|report| report.outcome)
+}
+
+/// Finds every plaintext Ares can before the timeout, instead of stopping at the first one.
+/// This is top results mode for library users, so `Config.top_results` is turned on
+/// and the human checker is turned off.
+/// Each result has the checker which identified it and how confident we are it is the plaintext.
+/// ```rust
+/// use ares::perform_cracking_all;
+/// use ares::config::Config;
+/// let mut config = Config::default();
+/// config.timeout = 2;
+/// let results = perform_cracking_all("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config).unwrap();
+/// assert!(results.iter().any(|scored| scored.result.text[0] == "hello there general"));
+/// ```
+/// # Errors
+/// Returns an error in the same cases as [`perform_cracking`].
+pub fn perform_cracking_all(
+    text: &str,
+    mut config: Config,
+) -> Result<Vec<ScoredResult>, AresError> {
+    config.top_results = true;
+    config.human_checker_on = false;
+    let report = crack_text(text, &Arc::new(config), &AtomicBool::new(false))?;
+    Ok(report
+        .top_results
+        .into_iter()
+        .map(ScoredResult::new)
+        .collect())
+}
+
+/// Cracks many texts at once with the same config, in parallel.
+/// The results are in the same order as the texts.
+/// Each text gets a run of its own, with its own timer, cancel flag and results,
+/// so one text finishing or timing out never stops the others.
+/// ```rust
+/// use ares::perform_batch_cracking;
+/// use ares::config::Config;
+/// let texts = vec!["aGVsbG8gd29ybGQ=".to_string(), "68656c6c6f20776f726c64".to_string()];
+/// let results = perform_batch_cracking(&texts, Config::default());
+/// assert_eq!(results.len(), 2);
+/// ```
+/// # Panics
+/// Panics if the thread pool for the texts cannot be created
+pub fn perform_batch_cracking(
+    texts: &[String],
+    config: impl Into<Arc<Config>>,
+) -> Vec<Result<CrackOutcome, AresError>> {
+    let config = config.into();
+    // Unless `Config.threads` is set, each search runs its decoders on rayon's global pool and waits for them,
+    // so the texts get their own pool to make sure the decoders always have threads to run on
+    let pool = rayon::ThreadPoolBuilder::new()
+        .build()
+        .expect("Should be able to create a thread pool for batch mode");
+    pool.install(|| {
+        texts
+            .par_iter()
+            .map(|text| {
+                crack_text(text, &config, &AtomicBool::new(false)).map(|report| report.outcome)
+            })
+            .collect()
+    })
+}
+
+/// Cracks the text using the config of this run, giving up if `cancel` is set.
+/// The run gets a context of its own, which is turned into the report once the search is over.
+fn crack_text(
+    text: &str,
+    config: &Arc<Config>,
+    cancel: &AtomicBool,
+) -> Result<CrackReport, AresError> {
+    let context = Arc::new(SearchContext::default());
+    let outcome = search_text(text, config, cancel, &context)?;
+    Ok(CrackReport::new(outcome, &context))
+}
+
+/// Checks the config, then looks for the plaintext of the text, recording what it finds in `context`
+fn search_text(
+    text: &str,
+    config: &Arc<Config>,
+    cancel: &AtomicBool,
+    context: &Arc<SearchContext>,
+) -> Result<CrackOutcome, AresError> {
+    // The regex checker expects valid regexes, so we compile them for the run before we start
+    if !config.regex.is_empty() {
+        context
+            .regexes(config)
+            .map_err(|error| AresError::InvalidRegex(error.to_string()))?;
+    }
+    if let Some(condition) = &config.exit_condition {
+        if let Some(name) = condition.unknown_checker(config) {
+            return Err(AresError::InvalidExitCondition(format!(
+                "no checker is called {name}"
+            )));
+        }
+    }
+
+    let top_results = config.top_results;
     let text = text.to_string();
-    let initial_check_for_plaintext = check_if_input_text_is_plaintext(&text);
+
+    // A recipe tells us exactly which decoders to use, so there is nothing to search for
+    if let Some(recipe) = &config.recipe {
+        let result = cyberchef::replay_recipe(recipe, &text).map_err(AresError::Recipe)?;
+        return Ok(CrackOutcome::Cracked(result));
+    }
+
+    let initial_check_for_plaintext = check_if_input_text_is_plaintext(&text, config, context);
     if initial_check_for_plaintext.is_identified {
         debug!(
             "The input text provided to the program {} is the plaintext. Returning early.",
             text
         );
-        cli_pretty_printing::return_early_because_input_text_is_plaintext();
+        cli_pretty_printing::return_early_because_input_text_is_plaintext(config);
 
         let mut crack_result = CrackResult::new(&Decoder::default(), text.to_string());
         crack_result.checker_name = initial_check_for_plaintext.checker_name;
+        crack_result.confidence = initial_check_for_plaintext.confidence;
+        crack_result.lemmeknow = initial_check_for_plaintext.lemmeknow;
 
         let output = DecoderResult {
             text: vec![text],
-            path: vec![crack_result],
+            path: vec![Arc::new(crack_result)],
+            confidence: initial_check_for_plaintext.confidence,
         };
+        if top_results {
+            context.top_results.add(&output);
+        }
 
-        return Some(output);
+        return Ok(CrackOutcome::AlreadyPlaintext(output));
     }
 
     // Build a new search tree
@@ -106,18 +296,236 @@ pub fn perform_cracking(text: &str, config: Config) -> Option<DecoderResult> {
     // let search_tree = searchers::Tree::new(text.to_string());
     // Perform the search algorithm
     // It will either return a failure or success.
-    searchers::search_for_plaintext(text)
+    let resume_from = match &config.resume {
+        Some(file) => {
+            Some(searchers::checkpoint::load(file, &text).map_err(AresError::Checkpoint)?)
+        }
+        None => None,
+    };
+    let outcome =
+        searchers::search_for_plaintext(text, config.clone(), cancel, resume_from, context);
+    if top_results {
+        // The search never stops early in top results mode, so it runs until the timer
+        // or until there is nothing left to decode
+        let found = context.top_results.results();
+        return Ok(match outcome {
+            CrackOutcome::TimedOut { .. } => CrackOutcome::TimedOut {
+                partial_results: found,
+                best_guess: None,
+            },
+            outcome => found
+                .into_iter()
+                .next()
+                .map_or(outcome, CrackOutcome::Cracked),
+        });
+    }
+    if config.keep_going {
+        if let CrackOutcome::Cracked(result) = outcome {
+            return Ok(CrackOutcome::Cracked(keep_unwrapping(
+                result, config, cancel, context,
+            )));
+        }
+    }
+    Ok(outcome)
+}
+
+/// Keeps searching from the plaintext until it stops decoding into another plaintext,
+/// adding each new part of the path onto the result.
+/// We stop if a plaintext comes up twice so decoders which undo each other can't loop forever,
+/// or if it decodes into a plaintext which looks less like English, as English is what we are after.
+/// Otherwise a loose checker would let English plaintext be decoded into gibberish it also accepts.
+fn keep_unwrapping(
+    mut result: DecoderResult,
+    config: &Arc<Config>,
+    cancel: &AtomicBool,
+    context: &Arc<SearchContext>,
+) -> DecoderResult {
+    let mut seen_plaintexts = HashSet::from([result.text[0].clone()]);
+    while let CrackOutcome::Cracked(next) = searchers::search_for_plaintext(
+        result.text[0].clone(),
+        config.clone(),
+        cancel,
+        None,
+        context,
+    ) {
+        if !seen_plaintexts.insert(next.text[0].clone())
+            || ENGLISH_QUADGRAMS.english_likeness(&next.text[0])
+                < ENGLISH_QUADGRAMS.english_likeness(&result.text[0])
+        {
+            break;
+        }
+        debug!(
+            "The plaintext {} decoded into {}",
+            result.text[0], next.text[0]
+        );
+        result.path.extend(next.path);
+        result.text = next.text;
+        result.confidence = next.confidence;
+    }
+    result
 }
 
 /// Checks if the given input is plaintext or not
 /// Used at the start of the program to not waste CPU cycles
-fn check_if_input_text_is_plaintext(text: &str) -> CheckResult {
-    let athena_checker = Checker::<Athena>::new();
+fn check_if_input_text_is_plaintext(
+    text: &str,
+    config: &Arc<Config>,
+    context: &Arc<SearchContext>,
+) -> CheckResult {
+    let athena_checker = Checker::<Athena>::new()
+        .with_config(config.clone())
+        .with_context(context.clone());
     athena_checker.check(text)
 }
 
+/// How a call to [`perform_cracking`] ended
+#[derive(Debug, Clone)]
+pub enum CrackOutcome {
+    /// The input was already plaintext, so there was nothing to decode
+    AlreadyPlaintext(DecoderResult),
+    /// Ares decoded the input into plaintext
+    Cracked(DecoderResult),
+    /// The timer ran out before the search finished.
+    /// In top results mode these are the plaintexts found before then.
+    TimedOut {
+        /// The plaintexts found before the timer ran out
+        partial_results: Vec<DecoderResult>,
+        /// Outside of top results mode, the decoding with the most English quadgrams when the
+        /// timer ran out. No checker identified it, so it is only our best guess at the plaintext.
+        best_guess: Option<DecoderResult>,
+    },
+    /// Every decoding was tried and none of them were plaintext
+    Exhausted,
+    /// The search was cancelled, see [`perform_cracking_with_cancel`]
+    Cancelled,
+}
+
+impl CrackOutcome {
+    /// Returns the plaintext, if Ares found one.
+    /// The input counts as the plaintext if it already was one.
+    pub fn into_plaintext(self) -> Option<DecoderResult> {
+        match self {
+            CrackOutcome::AlreadyPlaintext(result) | CrackOutcome::Cracked(result) => Some(result),
+            CrackOutcome::TimedOut { .. } | CrackOutcome::Exhausted | CrackOutcome::Cancelled => {
+                None
+            }
+        }
+    }
+}
+
+/// How a call to [`perform_cracking_with_report`] ended, and what the search found out on the way.
+/// Everything in it is from that one run, even if other runs searched at the same time.
+/// ```rust
+/// use ares::perform_cracking_with_report;
+/// use ares::config::Config;
+/// let report = perform_cracking_with_report("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", Config::default()).unwrap();
+/// assert!(!report.decoder_stats.is_empty());
+/// assert!(report.decoder_stats.iter().all(|decoder| decoder.invocations > 0));
+/// // The search tree is only recorded when it is exported
+/// assert!(report.search_tree.is_empty());
+///
+/// let config = Config {
+///     export_search_tree: Some("tree.json".to_string()),
+///     ..Config::default()
+/// };
+/// let report = perform_cracking_with_report("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config).unwrap();
+/// assert_eq!(report.search_tree[0].parent, None);
+/// assert!(report.search_tree.iter().any(|node| node.identified));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CrackReport {
+    /// How the search ended
+    pub outcome: CrackOutcome,
+    /// How many decoders deep the search got
+    pub depth_reached: u32,
+    /// Whether the search stopped because every path reached `Config.max_depth`
+    pub depth_limit_hit: bool,
+    /// How many decodings the search forgot to stay within `Config.max_nodes` and `Config.max_memory`
+    pub decodings_pruned: usize,
+    /// The decodings which looked most like plaintext, the closest first.
+    /// When the search fails these show whether Ares was on the right track.
+    pub closest_attempts: Vec<DecoderResult>,
+    /// How many times each decoder ran and how long it took, the decoder which took the longest first
+    pub decoder_stats: Vec<DecoderStats>,
+    /// Every decoding the search explored, in the order it reached them.
+    /// This is only recorded when `Config.export_search_tree` is set.
+    pub search_tree: Vec<SearchTreeNode>,
+    /// Every plaintext found when `Config.top_results` is on, each only once.
+    /// The ones the checkers are most confident in come first, then those using the fewest decoders.
+    pub top_results: Vec<DecoderResult>,
+}
+
+impl CrackReport {
+    /// Reads what the run recorded in its context
+    fn new(outcome: CrackOutcome, context: &SearchContext) -> Self {
+        CrackReport {
+            outcome,
+            depth_reached: context.depth_reached(),
+            depth_limit_hit: context.depth_limit_was_hit(),
+            decodings_pruned: context.decodings_pruned(),
+            closest_attempts: context.closest_attempts(),
+            decoder_stats: context.decoder_runs.stats(),
+            search_tree: context.search_tree.nodes(),
+            top_results: context.top_results.results(),
+        }
+    }
+}
+
+/// A plaintext found by [`perform_cracking_all`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoredResult {
+    /// The name of the checker which identified the plaintext
+    pub checker_name: &'static str,
+    /// How confident the checker which identified the plaintext is, from 0.0 to 1.0.
+    /// This is the share of words in the dictionary for English, the rarity of
+    /// a LemmeKnow match and 1.0 for a regex match.
+    pub confidence: f32,
+    /// The plaintext and every decoder used to get to it
+    #[serde(flatten)]
+    pub result: DecoderResult,
+}
+
+impl ScoredResult {
+    /// Scores a plaintext found by the search
+    fn new(result: DecoderResult) -> Self {
+        ScoredResult {
+            checker_name: result.path.last().map_or("", |crack| crack.checker_name),
+            confidence: result.confidence,
+            result,
+        }
+    }
+}
+
+/// The ways cracking can fail before the search even starts
+#[derive(Debug, PartialEq, Eq)]
+pub enum AresError {
+    /// The regex in `Config.regex` does not compile
+    InvalidRegex(String),
+    /// `Config.exit_condition` names a checker which doesn't exist
+    InvalidExitCondition(String),
+    /// The CyberChef recipe in `Config.recipe` could not be replayed
+    Recipe(cyberchef::RecipeError),
+    /// The checkpoint in `Config.resume` could not be read, or is for another text
+    Checkpoint(String),
+}
+
+impl fmt::Display for AresError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AresError::InvalidRegex(error) => write!(f, "the regex is not valid: {error}"),
+            AresError::InvalidExitCondition(error) => {
+                write!(f, "the exit condition is not valid: {error}")
+            }
+            AresError::Recipe(error) => write!(f, "the recipe could not be replayed: {error}"),
+            AresError::Checkpoint(error) => write!(f, "the search could not be resumed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for AresError {}
+
 /// DecoderResult is the result of decoders
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DecoderResult {
     /// The text we have from the decoder, as a vector
     /// because the decoder might return more than 1 text (caesar)
@@ -125,7 +533,12 @@ pub struct DecoderResult {
     /// The list of decoders we have so far
     /// The CrackResult contains more than just each decoder, such as the keys used
     /// or the checkers used.
-    pub path: Vec<CrackResult>,
+    /// Each one is shared with the other paths through the search which went through it,
+    /// so branching from a long path doesn't copy the texts along it.
+    pub path: Vec<Arc<CrackResult>>,
+    /// How confident the checker which identified `text` as the plaintext is, from 0.0 to 1.0.
+    /// This is 0.0 for decodings no checker has identified.
+    pub confidence: f32,
 }
 
 /// Creates a default DecoderResult with Default as the text / path
@@ -133,7 +546,11 @@ impl Default for DecoderResult {
     fn default() -> Self {
         DecoderResult {
             text: vec!["Default".to_string()],
-            path: vec![CrackResult::new(&Decoder::default(), "Default".to_string())],
+            path: vec![Arc::new(CrackResult::new(
+                &Decoder::default(),
+                "Default".to_string(),
+            ))],
+            confidence: 0.0,
         }
     }
 }
@@ -144,20 +561,29 @@ impl DecoderResult {
     fn _new(text: &str) -> Self {
         DecoderResult {
             text: vec![text.to_string()],
-            path: vec![CrackResult::new(&Decoder::default(), "Default".to_string())],
+            path: vec![Arc::new(CrackResult::new(
+                &Decoder::default(),
+                "Default".to_string(),
+            ))],
+            confidence: 0.0,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::perform_cracking;
-    use crate::config::Config;
+    use super::{
+        keep_unwrapping, perform_batch_cracking, perform_cracking, perform_cracking_all,
+        perform_cracking_with_cancel, AresError, CrackOutcome, DecoderResult,
+    };
+    use crate::config::{Config, ExitCondition};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
 
     #[test]
     fn test_perform_cracking_returns() {
         let config = Config::default();
-        perform_cracking("SGVscCBJIG5lZWQgc29tZWJvZHkh", config);
+        assert!(perform_cracking("SGVscCBJIG5lZWQgc29tZWJvZHkh", config).is_ok());
     }
 
     #[test]
@@ -167,7 +593,9 @@ mod tests {
         // assert!(result.is_some());
         // assert!(result.unwrap() == "CANARY: hello")
         let config = Config::default();
-        let result = perform_cracking("b2xsZWg=", config);
+        let result = perform_cracking("b2xsZWg=", config)
+            .unwrap()
+            .into_plaintext();
         assert!(result.is_some());
         assert!(result.unwrap().text[0] == "hello");
     }
@@ -175,23 +603,281 @@ mod tests {
     fn test_perform_cracking_returns_failure() {
         let config = Config::default();
         let result = perform_cracking("", config);
-        assert!(result.is_none());
+        assert!(matches!(result, Ok(CrackOutcome::Exhausted)));
     }
 
     #[test]
     fn test_perform_cracking_returns_successful_base64_reverse() {
         let config = Config::default();
-        let result = perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config);
+        let result = perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config)
+            .unwrap()
+            .into_plaintext();
         assert!(result.is_some());
         assert!(result.unwrap().text[0] == "hello there general")
     }
 
+    #[test]
+    fn test_plaintext_has_the_confidence_of_its_checker() {
+        let config = Config::default();
+        let result = perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config)
+            .unwrap()
+            .into_plaintext()
+            .unwrap();
+        // Every word is English
+        assert_eq!(result.confidence, 1.0);
+        assert_eq!(result.path.last().unwrap().confidence, 1.0);
+    }
+
+    #[test]
+    fn test_decoder_result_serializes_to_json() {
+        let json = serde_json::to_string(&DecoderResult::_new("hello")).unwrap();
+        assert!(json.contains("\"text\":[\"hello\"]"));
+        assert!(json.contains("\"decoder\":\"Default decoder\""));
+    }
+
+    #[test]
+    fn test_keep_unwrapping_decodes_a_plaintext_which_is_still_encoded() {
+        // Pretend the search stopped at the base64, it should keep going to the English
+        let result = keep_unwrapping(
+            DecoderResult::_new("aGVsbG8gdGhlcmUgZ2VuZXJhbA=="),
+            &Arc::new(Config::default()),
+            &AtomicBool::new(false),
+            &Arc::default(),
+        );
+        assert_eq!(result.text[0], "hello there general");
+        // The default decoder the result started with, then the base64 decoder
+        assert_eq!(result.path.len(), 2);
+        assert_eq!(result.path[1].decoder, "Base64");
+    }
+
+    #[test]
+    fn test_each_run_uses_its_own_config() {
+        // Only reversing can't decode base64, and reversing twice gives back the input
+        let only_reverse = Config {
+            enabled_decoders: vec!["reverse".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", only_reverse),
+            Ok(CrackOutcome::Exhausted)
+        ));
+
+        // The next run must not see the first run's config
+        let result = perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", Config::default())
+            .unwrap()
+            .into_plaintext();
+        assert_eq!(result.unwrap().text[0], "hello there general");
+    }
+
+    #[test]
+    fn test_cancelled_search_returns_cancelled() {
+        let cancel = Arc::new(AtomicBool::new(true));
+        // This isn't plaintext, so we have to search and the search stops straight away
+        let result =
+            perform_cracking_with_cancel("vjkrerkdnxhrfjekfdjexk", Config::default(), cancel);
+        assert!(matches!(result, Ok(CrackOutcome::Cancelled)));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_an_error() {
+        let config = Config {
+            regex: vec!["(unclosed".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            perform_cracking("aGVsbG8=", config),
+            Err(AresError::InvalidRegex(_))
+        ));
+    }
+
+    #[test]
+    fn test_regex_is_the_only_exit_condition() {
+        // flag{crib_driven} as base64, then as hexadecimal
+        let config = Config {
+            regex: vec![r"flag\{".to_string()],
+            ..Default::default()
+        };
+        let result = perform_cracking("5a6d78685a33746a636d6c695832527961585a6c626e303d", config)
+            .unwrap()
+            .into_plaintext()
+            .unwrap();
+        assert_eq!(result.text[0], "flag{crib_driven}");
+        assert_eq!(result.path.len(), 2);
+        assert_eq!(result.path[1].checker_name, "Regex Checker");
+
+        // English which doesn't match the crib isn't plaintext
+        let config = Config {
+            regex: vec![r"flag\{".to_string()],
+            ..Default::default()
+        };
+        assert!(!matches!(
+            perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config),
+            Ok(CrackOutcome::AlreadyPlaintext(_) | CrackOutcome::Cracked(_))
+        ));
+    }
+
+    #[test]
+    fn test_top_results_only_collects_regex_matches() {
+        let config = Config {
+            regex: vec![r"flag\{".to_string()],
+            timeout: 2,
+            ..Default::default()
+        };
+        let results =
+            perform_cracking_all("5a6d78685a33746a636d6c695832527961585a6c626e303d", config)
+                .unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|scored| {
+            scored.checker_name == "Regex Checker" && scored.result.text[0].contains("flag{")
+        }));
+    }
+
+    #[test]
+    fn test_perform_cracking_all_only_returns_plaintexts_of_its_own_run() {
+        let crack_all = |text: &'static str| {
+            std::thread::spawn(move || {
+                let config = Config {
+                    timeout: 0,
+                    top_results_limit: Some(1),
+                    ..Default::default()
+                };
+                perform_cracking_all(text, config).unwrap()
+            })
+        };
+        // Both runs search at the same time in one process
+        let hello = crack_all("aGVsbG8gdGhlcmUgZ2VuZXJhbA==");
+        let kenobi = crack_all("Z2VuZXJhbCBrZW5vYmk=");
+        let hello = hello.join().unwrap();
+        let kenobi = kenobi.join().unwrap();
+
+        assert_eq!(hello.len(), 1);
+        assert_eq!(hello[0].result.text[0], "hello there general");
+        assert!(!hello[0].checker_name.is_empty());
+        assert_eq!(kenobi.len(), 1);
+        assert_eq!(kenobi[0].result.text[0], "general kenobi");
+    }
+
+    #[test]
+    fn test_top_result_callback_is_told_about_each_plaintext() {
+        let found = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let found_by_callback = found.clone();
+        let config = Config {
+            timeout: 0,
+            top_results_limit: Some(1),
+            top_result_callback: Some(Arc::new(move |result: &DecoderResult| {
+                found_by_callback
+                    .lock()
+                    .unwrap()
+                    .push(result.text[0].clone());
+            })),
+            ..Default::default()
+        };
+        let results = perform_cracking_all("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config).unwrap();
+        assert_eq!(results[0].result.text[0], "hello there general");
+        assert_eq!(
+            *found.lock().unwrap(),
+            vec!["hello there general".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_batch_cracking_cracks_each_text_in_its_own_run() {
+        let texts = vec![
+            "aGVsbG8gdGhlcmUgZ2VuZXJhbA==".to_string(),
+            "Z2VuZXJhbCBrZW5vYmk=".to_string(),
+        ];
+        let config = Config {
+            timeout: 2,
+            ..Default::default()
+        };
+        let plaintexts: Vec<Option<String>> = perform_batch_cracking(&texts, config)
+            .into_iter()
+            .map(|outcome| {
+                outcome
+                    .unwrap()
+                    .into_plaintext()
+                    .map(|result| result.text[0].clone())
+            })
+            .collect();
+        assert_eq!(
+            plaintexts,
+            [
+                Some("hello there general".to_string()),
+                Some("general kenobi".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_timing_out_returns_the_best_guess() {
+        // Random bytes as base64 decode into more and more gibberish, so the search never ends in time
+        let config = Config {
+            timeout: 1,
+            ..Default::default()
+        };
+        let outcome =
+            perform_cracking("2Ne7xRMYwq/PiVzTbAHfLuo1k8gDpjUKC+3s6lEvO9mn", config).unwrap();
+        match outcome {
+            CrackOutcome::TimedOut {
+                partial_results,
+                best_guess: Some(best_guess),
+            } => {
+                assert!(partial_results.is_empty());
+                assert!(!best_guess.path.is_empty());
+            }
+            outcome => panic!("expected the search to time out, got {outcome:?}"),
+        }
+    }
+
+    #[test]
+    fn test_exit_condition_must_name_checkers() {
+        let config = Config {
+            exit_condition: Some(ExitCondition::parse("regex || englsh").unwrap()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            perform_cracking("aGVsbG8=", config),
+            Err(AresError::InvalidExitCondition(_))
+        ));
+    }
+
+    #[test]
+    fn test_exit_condition_combines_checkers() {
+        // flag{crib_driven} as base64, then as hexadecimal. The flag isn't English,
+        // so only the flag checker half of the condition finds it
+        let config = Config {
+            exit_condition: Some(ExitCondition::parse("wordlist || flag").unwrap()),
+            ..Default::default()
+        };
+        let result = perform_cracking("5a6d78685a33746a636d6c695832527961585a6c626e303d", config)
+            .unwrap()
+            .into_plaintext()
+            .unwrap();
+        assert_eq!(result.text[0], "flag{crib_driven}");
+        assert_eq!(result.path[1].checker_name, "Composite Checker");
+    }
+
+    #[test]
+    fn test_top_results_stop_at_the_limit() {
+        // Without a limit this would search until every decoding had been tried
+        let config = Config {
+            timeout: 0,
+            top_results_limit: Some(1),
+            ..Default::default()
+        };
+        let results = perform_cracking_all("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config).unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
     #[test]
     fn test_early_exit_if_input_is_plaintext() {
         let config = Config::default();
         let result = perform_cracking("192.168.0.1", config);
         // Since we are exiting early the path should be of length 1, which is 1 check (the Athena check)
-        assert!(result.unwrap().path.len() == 1);
+        match result {
+            Ok(CrackOutcome::AlreadyPlaintext(result)) => assert!(result.path.len() == 1),
+            _ => panic!("expected the input to already be plaintext"),
+        }
     }
 
     #[ignore]
@@ -201,7 +887,9 @@ mod tests {
     // TODO: Skipping this test because the English checker still isn't good.
     fn test_successfully_decode_caesar() {
         let config = Config::default();
-        let result = perform_cracking("Ebgngr zr 13 cynprf!", config);
+        let result = perform_cracking("Ebgngr zr 13 cynprf!", config)
+            .unwrap()
+            .into_plaintext();
         // We return None since the input is the plaintext
         assert!(result.unwrap().text[0] == "Rotate me 13 places!");
     }
@@ -209,7 +897,9 @@ mod tests {
     #[test]
     fn test_successfully_inputted_plaintext() {
         let config = Config::default();
-        let result = perform_cracking("Hello, World!", config);
+        let result = perform_cracking("Hello, World!", config)
+            .unwrap()
+            .into_plaintext();
         // We return None since the input is the plaintext
         let res_unwrapped = result.unwrap();
         assert!(&res_unwrapped.text[0] == "Hello, World!");
@@ -1,17 +1,92 @@
-use ares::cli::parse_cli_args;
+use ares::cli::{parse_cli_args, run_batch, run_subcommand, CliAction, ExitStatus};
+use ares::cli_pretty_printing::decoder_stats;
+use ares::cli_pretty_printing::display_top_results;
 use ares::cli_pretty_printing::program_exiting_successful_decoding;
-use ares::perform_cracking;
+use ares::config::set_global_config;
+use ares::{
+    perform_cracking_with_report, search_tree_as_dot, CrackOutcome, CrackReport, SearchTreeNode,
+};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Instant;
 
-fn main() {
+fn main() -> ExitCode {
     // Turn CLI arguments into a library object
-    let (text, config) = parse_cli_args();
-    let result = perform_cracking(&text, config);
-    match result {
+    let (text, config) = match parse_cli_args() {
+        CliAction::Crack(text, config) => (text, config),
+        CliAction::Batch(inputs, config) => return run_batch(&inputs, config).into(),
+        CliAction::Subcommand(command) => return run_subcommand(command).into(),
+    };
+    let config = Arc::new(config);
+    // The printers below read the config the CLI parsed from the global config
+    set_global_config(config.clone());
+    let top_results = config.top_results;
+    let stats = config.stats;
+    let bell = config.bell;
+    #[cfg(feature = "notify")]
+    let notify = config.notify;
+    let search_tree_file = config.export_search_tree.clone();
+    let start = Instant::now();
+    let status = match perform_cracking_with_report(&text, config) {
+        Ok(report) => {
+            let status = if top_results {
+                display_top_results(&report);
+                if report.top_results.is_empty() {
+                    ExitStatus::Failed
+                } else {
+                    ExitStatus::Cracked
+                }
+            } else {
+                print_result(&report, start)
+            };
+            if stats {
+                decoder_stats(&report.decoder_stats);
+            }
+            if let Some(file) = &search_tree_file {
+                export_search_tree(file, &report.search_tree);
+            }
+            status
+        }
+        // Every error is about the config, like a regex which doesn't compile
+        Err(error) => {
+            ares::cli_pretty_printing::cracking_failed_with_error(&error);
+            ExitStatus::ConfigError
+        }
+    };
+    if bell {
+        ares::cli_pretty_printing::ring_bell();
+    }
+    #[cfg(feature = "notify")]
+    if notify {
+        ares::cli_pretty_printing::notify_finished(status);
+    }
+    status.into()
+}
+
+/// Prints the plaintext, or why we couldn't find it, and returns the status to exit with
+fn print_result(report: &CrackReport, start: Instant) -> ExitStatus {
+    match &report.outcome {
         // TODO: As result have array of CrackResult used,
         // we can print in better way with more info
-        Some(result) => {
-            program_exiting_successful_decoding(result);
+        CrackOutcome::AlreadyPlaintext(result) | CrackOutcome::Cracked(result) => {
+            program_exiting_successful_decoding(result.clone(), start.elapsed());
+            ExitStatus::Cracked
+        }
+        _ => {
+            ares::cli_pretty_printing::search_failed(report, start.elapsed());
+            ExitStatus::Failed
         }
-        None => ares::cli_pretty_printing::failed_to_decode(),
+    }
+}
+
+/// Writes every decoding the search explored to the file, as DOT if it ends in .dot and JSON otherwise
+fn export_search_tree(file: &str, tree: &[SearchTreeNode]) {
+    let contents = if file.ends_with(".dot") {
+        search_tree_as_dot(tree)
+    } else {
+        serde_json::to_string_pretty(tree).expect("the search tree is valid JSON")
+    };
+    if let Err(error) = std::fs::write(file, contents) {
+        ares::cli_pretty_printing::search_tree_could_not_be_written(file, error);
     }
 }
@@ -0,0 +1,74 @@
+//! Beam search is breadth first search where we only keep the `width` most promising
+//! decodings at each depth. https://en.wikipedia.org/wiki/Beam_search
+//! It's for fast best-effort runs on huge inputs where trying everything takes too long.
+
+use std::cmp::Reverse;
+
+use crate::storage::quadgrams::ENGLISH_QUADGRAMS;
+use crate::DecoderResult;
+
+/// How much of the 1000 points of [`plaintext_score`] go to how English the quadgrams of the text are
+const QUADGRAM_SCORE: usize = 900;
+
+/// Keeps the `width` decodings which look the most like plaintext, best first.
+pub fn keep_most_promising(mut strings: Vec<DecoderResult>, width: usize) -> Vec<DecoderResult> {
+    if strings.len() > width {
+        strings.sort_by_cached_key(|string| Reverse(plaintext_score(&string.text[0])));
+        strings.truncate(width);
+    }
+    strings
+}
+
+/// How much the text looks like plaintext, out of 1000.
+/// Most of the score is how English its quadgrams are, so English beats encodings like base64
+/// and ciphertexts like ROT13 which are all letters too.
+/// The rest is the share of letters, spaces and common punctuation, which tells apart
+/// texts with too few letters for quadgrams, like hex and binary.
+pub fn plaintext_score(text: &str) -> usize {
+    let total = text.chars().count();
+    if total == 0 {
+        return 0;
+    }
+    let plaintext_like = text
+        .chars()
+        .filter(|c| c.is_alphabetic() || matches!(c, ' ' | '.' | ',' | '\'' | '!' | '?'))
+        .count();
+    let english_likeness = ENGLISH_QUADGRAMS.english_likeness(text);
+    (english_likeness * QUADGRAM_SCORE as f64) as usize
+        + plaintext_like * (1000 - QUADGRAM_SCORE) / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_scores_higher_than_hex() {
+        assert!(plaintext_score("hello there, general") > plaintext_score("68656c6c6f"));
+    }
+
+    #[test]
+    fn english_scores_higher_than_rot13_and_base64() {
+        let english = plaintext_score("hello there general kenobi");
+        assert!(english > plaintext_score("uryyb gurer trareny xrabov"));
+        assert!(english > plaintext_score("aGVsbG8gdGhlcmUgZ2VuZXJhbCBrZW5vYmk="));
+    }
+
+    #[test]
+    fn keeps_the_most_promising_strings() {
+        let strings = vec![
+            DecoderResult::_new("01101000 01101001"),
+            DecoderResult::_new("hello there"),
+            DecoderResult::_new("68656c6c6f"),
+        ];
+        let kept = keep_most_promising(strings, 2);
+        let texts: Vec<&str> = kept.iter().map(|s| s.text[0].as_str()).collect();
+        assert_eq!(texts, vec!["hello there", "68656c6c6f"]);
+    }
+
+    #[test]
+    fn keeps_everything_when_there_is_room() {
+        let strings = vec![DecoderResult::_new("aaa"), DecoderResult::_new("bbb")];
+        assert_eq!(keep_most_promising(strings, 5).len(), 2);
+    }
+}
@@ -1,9 +1,13 @@
 use crate::cli_pretty_printing::decoded_how_many_times;
+use crate::config::{Config, SearchStrategy};
 use crate::filtration_system::MyResults;
+use crate::searchers::checkpoint::Checkpoint;
+use crate::searchers::context::SearchContext;
+use crate::searchers::decoding_cache::DecodingCache;
 use crossbeam::channel::Sender;
 
 use log::trace;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
@@ -11,26 +15,89 @@ use crate::DecoderResult;
 
 /// Breadth first search is our search algorithm
 /// https://en.wikipedia.org/wiki/Breadth-first_search
-pub fn bfs(input: String, result_sender: Sender<Option<DecoderResult>>, stop: Arc<AtomicBool>) {
+pub fn bfs(
+    input: String,
+    config: Arc<Config>,
+    result_sender: Sender<Option<DecoderResult>>,
+    stop: Arc<AtomicBool>,
+    context: Arc<SearchContext>,
+    resume_from: Option<Checkpoint>,
+) {
+    // Every text we have already expanded, by its hash so long texts don't use up memory.
+    // Different paths often give the same text (reverse twice, atbash twice) and it only needs decoding once.
+    // The input is in here too so we never decode back to it.
+    let mut seen_strings = HashSet::from([text_hash(&input)]);
     let initial = DecoderResult {
-        text: vec![input],
+        text: vec![input.clone()],
         path: vec![],
+        confidence: 0.0,
     };
-    let mut seen_strings = HashSet::new();
+    super::record_in_search_tree(&initial, &config, &context);
     // all strings to search through
     let mut current_strings = vec![initial];
 
+    // Decoders like Caesar return a text for every key. Only the most English text of each
+    // decoder is decoded at the next depth, the rest are put off here by the depth they are at
+    // until there is nothing else left to decode, so they never slow down the likely paths.
+    let mut deferred: BTreeMap<u32, Vec<DecoderResult>> = BTreeMap::new();
+
     let mut curr_depth: u32 = 1; // as we have input string, so we start from 1
+    if let Some(mut checkpoint) = resume_from {
+        trace!("Resuming the search {} decoders deep", checkpoint.depth);
+        curr_depth = checkpoint.depth;
+        seen_strings = std::mem::take(&mut checkpoint.seen);
+        current_strings = vec![];
+        for decoding in checkpoint.into_frontier() {
+            let depth = decoding_depth(&decoding);
+            if depth == curr_depth {
+                current_strings.push(decoding);
+            } else {
+                deferred.entry(depth).or_default().push(decoding);
+            }
+        }
+    }
+    let top_results = config.top_results;
+    let max_depth = config.max_depth;
+    let search_strategy = config.search_strategy;
+    context.start_search();
+    let cache = DecodingCache::new(config.decoding_cache_size);
 
     // loop through all of the strings in the vec
-    while !current_strings.is_empty() && !stop.load(std::sync::atomic::Ordering::Relaxed) {
+    loop {
+        if super::depth_limit_reached(curr_depth, max_depth) {
+            trace!(
+                "Stopping the search at the maximum depth of {:?}",
+                max_depth
+            );
+            context.hit_depth_limit();
+            current_strings.clear();
+        }
+        // Once there is nothing left at this depth, we decode the texts we put off
+        if current_strings.is_empty() {
+            match deferred.pop_first() {
+                Some((depth, decodings)) if !super::depth_limit_reached(depth, max_depth) => {
+                    trace!("Decoding the texts put off {} decoders deep", depth);
+                    curr_depth = depth;
+                    current_strings = super::prune_frontier(decodings, &config, &context);
+                }
+                Some(_) => {
+                    context.hit_depth_limit();
+                    break;
+                }
+                None => break,
+            }
+        }
+        if stop.load(std::sync::atomic::Ordering::Relaxed) {
+            break;
+        }
         trace!("Number of potential decodings: {}", current_strings.len());
         trace!("Current depth is {:?}", curr_depth);
+        context.reached_depth(curr_depth);
 
         let mut new_strings: Vec<DecoderResult> = vec![];
 
         current_strings.into_iter().try_for_each(|current_string| {
-            let res = super::perform_decoding(&current_string);
+            let res = super::perform_decoding(&current_string, &config, &cache, &context);
 
             match res {
                 // if it's Break variant, we have cracked the text successfully
@@ -38,13 +105,25 @@ pub fn bfs(input: String, result_sender: Sender<Option<DecoderResult>>, stop: Ar
                 MyResults::Break(res) => {
                     let mut decoders_used = current_string.path;
                     let text = res.unencrypted_text.clone().unwrap_or_default();
-                    decoders_used.push(res);
+                    let confidence = res.confidence;
+                    decoders_used.push(Arc::new(res));
                     let result_text = DecoderResult {
                         text,
                         path: decoders_used,
+                        confidence,
                     };
+                    super::record_in_search_tree(&result_text, &config, &context);
 
-                    decoded_how_many_times(curr_depth);
+                    // In top results mode we store the plaintext and keep searching,
+                    // unless we have found as many as the user wanted
+                    if top_results {
+                        if super::store_top_result(&result_text, &config, &context, &stop) {
+                            return None;
+                        }
+                        return Some(());
+                    }
+
+                    decoded_how_many_times(curr_depth, &config);
                     result_sender
                         .send(Some(result_text))
                         .expect("Should succesfully send the result");
@@ -54,45 +133,89 @@ pub fn bfs(input: String, result_sender: Sender<Option<DecoderResult>>, stop: Ar
                     None // short-circuits the iterator
                 }
                 MyResults::Continue(results_vec) => {
-                    new_strings.extend(results_vec.into_iter().flat_map(|mut r| {
+                    for (rank, mut r) in super::ranked_candidates(results_vec, &config) {
                         let mut decoders_used = current_string.path.clone();
                         // text is a vector of strings
                         let mut text = r.unencrypted_text.take().unwrap_or_default();
 
                         text.retain(|s| {
-                            !check_if_string_cant_be_decoded(s) && seen_strings.insert(s.clone())
+                            !check_if_string_cant_be_decoded(s)
+                                && !super::looks_like_random_binary(s, &config)
+                                && seen_strings.insert(text_hash(s))
                         });
 
                         if text.is_empty() {
-                            return None;
+                            continue;
                         }
 
-                        decoders_used.push(r);
-                        Some(DecoderResult {
+                        decoders_used.push(Arc::new(r));
+                        let decoding = DecoderResult {
                             // and this is a vector of strings
                             // TODO we should probably loop through all `text` and create Text structs for each one
                             // and append those structs
                             // I think we should keep text as a single string
                             // and just create more of them....
                             text,
-                            path: decoders_used.to_vec(),
-                        })
-                    }));
+                            path: decoders_used,
+                            confidence: 0.0,
+                        };
+                        context.best_guess.offer(&decoding);
+                        super::record_in_search_tree(&decoding, &config, &context);
+                        // Beam search picks the most promising texts out of all of them itself
+                        if rank == 0 || matches!(search_strategy, SearchStrategy::Beam { .. }) {
+                            new_strings.push(decoding);
+                        } else {
+                            deferred.entry(curr_depth + 1).or_default().push(decoding);
+                        }
+                    }
                     Some(()) // indicate we want to continue processing
                 }
             }
         });
 
+        if let SearchStrategy::Beam { width } = search_strategy {
+            new_strings = super::beam::keep_most_promising(new_strings, width);
+        }
+        new_strings = super::prune_frontier(new_strings, &config, &context);
+
         current_strings = new_strings;
         curr_depth += 1;
 
+        if let Some(file) = &config.checkpoint {
+            if !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                // The texts we put off are saved with the rest, their depth is the length of their path
+                let frontier = current_strings.iter().chain(deferred.values().flatten());
+                let checkpoint = Checkpoint::new(&input, curr_depth, &seen_strings, frontier);
+                super::checkpoint::save(file, &checkpoint);
+            }
+        }
+
         trace!("Refreshed the vector, {:?}", current_strings);
     }
     result_sender.try_send(None).ok();
 }
 
+/// How many decoders deep the decoding is, decoding it gives paths one decoder longer
+fn decoding_depth(decoding: &DecoderResult) -> u32 {
+    decoding.path.len() as u32 + 1
+}
+
+/// The FNV-1a offset basis, what the hash of an empty text is
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// The FNV-1a prime each byte is multiplied in with
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes a text for the set of texts we have already seen.
+/// The hashes are saved in checkpoints, so this is FNV-1a which hashes a text the same way
+/// in every build and on every run, unlike the randomly seeded hasher of the standard library.
+pub fn text_hash(text: &str) -> u64 {
+    text.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
 /// If this returns False it will not attempt to decode that string
-fn check_if_string_cant_be_decoded(text: &str) -> bool {
+pub fn check_if_string_cant_be_decoded(text: &str) -> bool {
     text.len() <= 2
 }
 
@@ -101,13 +224,29 @@ mod tests {
     use crossbeam::channel::bounded;
 
     use super::*;
+    use crate::config::default_config;
+
+    #[test]
+    fn text_hashes_are_the_same_on_every_run() {
+        // Checkpoints saved by one run are resumed by another, so the hashes can't be seeded
+        assert_eq!(text_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(text_hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(text_hash("foobar"), 0x8594_4171_f739_67e8);
+    }
 
     #[test]
     fn bfs_succeeds() {
         // this will work after english checker can identify "CANARY: hello"
         let (tx, rx) = bounded::<Option<DecoderResult>>(1);
         let stopper = Arc::new(AtomicBool::new(false));
-        bfs("b2xsZWg=".into(), tx, stopper);
+        bfs(
+            "b2xsZWg=".into(),
+            default_config(),
+            tx,
+            stopper,
+            Arc::default(),
+            None,
+        );
         let result = rx.recv().unwrap();
         assert!(result.is_some());
         let txt = result.unwrap().text;
@@ -128,7 +267,14 @@ mod tests {
         // Caesar Cipher (Rot13) -> Base64
         let (tx, rx) = bounded::<Option<DecoderResult>>(1);
         let stopper = Arc::new(AtomicBool::new(false));
-        bfs("MTkyLjE2OC4wLjE=".into(), tx, stopper);
+        bfs(
+            "MTkyLjE2OC4wLjE=".into(),
+            default_config(),
+            tx,
+            stopper,
+            Arc::default(),
+            None,
+        );
         let result = rx.recv().unwrap();
         assert!(result.is_some());
         assert_eq!(result.unwrap().text[0], "192.168.0.1");
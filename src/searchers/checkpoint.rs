@@ -0,0 +1,184 @@
+//! Long breadth first searches save where they are to a checkpoint file after each depth,
+//! so a search which is interrupted can carry on from there with `--resume` instead of starting again.
+//! The checkpoint is the decodings waiting to be decoded and the hashes of every text already decoded.
+
+use std::collections::HashSet;
+use std::fs;
+use std::sync::Arc;
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::DECODER_MAP;
+use crate::DecoderResult;
+
+/// Where a breadth first search had got to
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The text being cracked, so we don't resume the search of another text
+    input: String,
+    /// How many decoders deep the search was.
+    /// Decodings which are deeper were put off until there is nothing else to decode.
+    pub depth: u32,
+    /// The hashes of every text the search has already reached
+    pub seen: HashSet<u64>,
+    /// The decodings waiting to be decoded
+    frontier: Vec<CheckpointDecoding>,
+}
+
+/// A decoding waiting to be decoded
+#[derive(Serialize, Deserialize)]
+struct CheckpointDecoding {
+    /// The texts the last decoder gave
+    text: Vec<String>,
+    /// The decoders used to get here
+    path: Vec<CheckpointStep>,
+}
+
+/// One decoder used to get to a decoding.
+/// The rest of the [`CrackResult`] is the decoder's metadata, which we look up when resuming.
+#[derive(Serialize, Deserialize)]
+struct CheckpointStep {
+    /// The name of the decoder
+    decoder: String,
+    /// The text the decoder decoded
+    encrypted_text: String,
+    /// The key the decoder used, checkpoints saved before keys were reported don't have one
+    #[serde(default)]
+    key: Option<String>,
+}
+
+impl Checkpoint {
+    /// Saves the decodings which are waiting to be decoded, the search was at `depth`
+    pub fn new<'a>(
+        input: &str,
+        depth: u32,
+        seen: &HashSet<u64>,
+        frontier: impl IntoIterator<Item = &'a DecoderResult>,
+    ) -> Self {
+        Checkpoint {
+            input: input.to_string(),
+            depth,
+            seen: seen.clone(),
+            frontier: frontier
+                .into_iter()
+                .map(|decoding| CheckpointDecoding {
+                    text: decoding.text.clone(),
+                    path: decoding
+                        .path
+                        .iter()
+                        .map(|step| CheckpointStep {
+                            decoder: step.decoder.to_string(),
+                            encrypted_text: step.encrypted_text.clone(),
+                            key: step.key.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Turns the saved decodings back into the frontier of the search.
+    /// Decodings which went through a decoder we don't have, like a plugin which isn't loaded, are dropped.
+    pub fn into_frontier(self) -> Vec<DecoderResult> {
+        self.frontier
+            .into_iter()
+            .filter_map(|decoding| {
+                let path = decoding
+                    .path
+                    .into_iter()
+                    .map(|step| {
+                        let decoder = DECODER_MAP.get(step.decoder.as_str())?;
+                        Some(Arc::new(CrackResult {
+                            success: false,
+                            encrypted_text: step.encrypted_text,
+                            unencrypted_text: None,
+                            candidates: Vec::new(),
+                            decoder: decoder.get_name(),
+                            checker_name: "",
+                            checker_description: "",
+                            lemmeknow: None,
+                            key: step.key,
+                            description: decoder.get_description(),
+                            link: decoder.get_link(),
+                            confidence: 0.0,
+                        }))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(DecoderResult {
+                    text: decoding.text,
+                    path,
+                    // The frontier is only ever decodings no checker has identified
+                    confidence: 0.0,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Writes the checkpoint to the file. If it can't be written the search carries on without it.
+/// The checkpoint is written next to the file first, so an interruption never leaves half a checkpoint.
+pub fn save(file: &str, checkpoint: &Checkpoint) {
+    let partial_file = format!("{file}.partial");
+    let saved = serde_json::to_vec(checkpoint)
+        .map_err(|error| error.to_string())
+        .and_then(|json| fs::write(&partial_file, json).map_err(|error| error.to_string()))
+        .and_then(|()| fs::rename(&partial_file, file).map_err(|error| error.to_string()));
+    match saved {
+        Ok(()) => debug!(
+            "Saved {} decodings {} decoders deep to {}",
+            checkpoint.frontier.len(),
+            checkpoint.depth,
+            file
+        ),
+        Err(error) => warn!("Could not save the checkpoint to {}: {}", file, error),
+    }
+}
+
+/// Reads the checkpoint of a search of `input`
+///
+/// # Errors
+///
+/// Returns why the checkpoint can't be used if the file can't be read,
+/// isn't a checkpoint, or is the checkpoint of another text.
+pub fn load(file: &str, input: &str) -> Result<Checkpoint, String> {
+    let json = fs::read(file).map_err(|error| format!("could not read {file}: {error}"))?;
+    let checkpoint: Checkpoint = serde_json::from_slice(&json)
+        .map_err(|error| format!("{file} is not a checkpoint: {error}"))?;
+    if checkpoint.input != input {
+        return Err(format!("{file} is the checkpoint of another text"));
+    }
+    Ok(checkpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::interface::{Crack, Decoder};
+    use crate::decoders::reverse_decoder::ReverseDecoder;
+
+    #[test]
+    fn the_frontier_is_the_same_after_resuming() {
+        let mut step = CrackResult::new(&Decoder::<ReverseDecoder>::new(), "olleh".to_string());
+        step.checker_name = "English Checker";
+        step.key = Some("shift=13".to_string());
+        let frontier = vec![DecoderResult {
+            text: vec!["hello".to_string()],
+            path: vec![Arc::new(step)],
+            confidence: 0.0,
+        }];
+        let seen = HashSet::from([1, 2, 3]);
+
+        let checkpoint = Checkpoint::new("olleh", 2, &seen, &frontier);
+        assert_eq!(checkpoint.depth, 2);
+        assert_eq!(checkpoint.seen, seen);
+        let resumed = checkpoint.into_frontier();
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].text, frontier[0].text);
+        assert_eq!(resumed[0].path[0].decoder, "Reverse");
+        assert_eq!(resumed[0].path[0].encrypted_text, "olleh");
+        assert_eq!(resumed[0].path[0].key, frontier[0].path[0].key);
+        assert_eq!(resumed[0].path[0].link, frontier[0].path[0].link);
+    }
+}
@@ -0,0 +1,150 @@
+//! Everything one run of Ares records while it searches.
+//! Each call to `perform_cracking` gets its own context, so cracks running at the same time
+//! in one process, like in batch mode or `ares serve`, never see each other's state.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+use regex::Regex;
+
+use super::BestGuess;
+use crate::checkers::flag_checker::flag_regex;
+use crate::checkers::regex_checker::compile_patterns;
+use crate::config::Config;
+use crate::storage::decoder_stats::DecoderRuns;
+use crate::storage::human_rejections::HumanRejections;
+use crate::storage::search_tree::SearchTree;
+use crate::storage::top_results::TopResults;
+use crate::DecoderResult;
+
+/// The state of one run, shared by its searcher, decoders and checkers
+#[derive(Default)]
+pub struct SearchContext {
+    /// How many questions the human checker of this run is waiting on an answer to.
+    /// The timer of the run is paused while this isn't 0.
+    paused: Arc<AtomicUsize>,
+    /// Set when the search stopped because every path reached `Config.max_depth`
+    depth_limit_hit: AtomicBool,
+    /// How many decoders deep the search got
+    depth_reached: AtomicU32,
+    /// How many decodings the search forgot because of `Config.max_nodes` or `Config.max_memory`
+    decodings_pruned: AtomicUsize,
+    /// The decodings which look most like plaintext out of every decoding the search made
+    pub(crate) best_guess: BestGuess,
+    /// How many times each decoder ran and how long it took, for `--stats`
+    pub(crate) decoder_runs: DecoderRuns,
+    /// Every decoding the search explored, recorded when `Config.export_search_tree` is set
+    pub(crate) search_tree: SearchTree,
+    /// The texts the user said were not plaintext, so the human checker doesn't ask about them again
+    pub(crate) human_rejections: HumanRejections,
+    /// Every plaintext the search found in top results mode
+    pub(crate) top_results: TopResults,
+    /// The regexes in `Config.regex`, compiled the first time the Regex Checker needs them
+    regexes: OnceCell<Vec<Regex>>,
+    /// The regex for the flag formats, compiled the first time the Flag Checker needs it
+    flags: OnceCell<Regex>,
+}
+
+impl SearchContext {
+    /// What the timer of this run checks before counting each second, it is paused while this isn't 0
+    pub fn paused(&self) -> Arc<AtomicUsize> {
+        self.paused.clone()
+    }
+
+    /// Stops the timer of this run counting, like while the human checker waits for an answer.
+    /// The timer counts again once the returned pause is dropped, however the caller returns.
+    pub fn pause_timer(&self) -> TimerPause {
+        self.paused.fetch_add(1, Ordering::Relaxed);
+        TimerPause {
+            paused: self.paused.clone(),
+        }
+    }
+
+    /// Whether the timer of this run is paused
+    pub fn timer_is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed) > 0
+    }
+
+    /// Forgets how far the last search got, called when a searcher starts
+    pub fn start_search(&self) {
+        self.depth_limit_hit.store(false, Ordering::Relaxed);
+        self.depth_reached.store(0, Ordering::Relaxed);
+        self.decodings_pruned.store(0, Ordering::Relaxed);
+    }
+
+    /// Records that the search stopped because it hit the maximum depth
+    pub fn hit_depth_limit(&self) {
+        self.depth_limit_hit.store(true, Ordering::Relaxed);
+    }
+
+    /// Did the search stop because it hit the maximum depth?
+    /// Used to explain why we failed to decode the text.
+    pub fn depth_limit_was_hit(&self) -> bool {
+        self.depth_limit_hit.load(Ordering::Relaxed)
+    }
+
+    /// Records how many decoders deep the search has got.
+    /// Breadth first search goes back up to decode the texts it put off, which doesn't count.
+    pub fn reached_depth(&self, depth: u32) {
+        self.depth_reached.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// How many decoders deep the search got, so failures can say how far it went
+    pub fn depth_reached(&self) -> u32 {
+        self.depth_reached.load(Ordering::Relaxed)
+    }
+
+    /// Records that the search forgot some decodings to stay within its limits
+    pub fn pruned(&self, decodings: usize) {
+        self.decodings_pruned
+            .fetch_add(decodings, Ordering::Relaxed);
+    }
+
+    /// How many decodings the search forgot to stay within `Config.max_nodes` and `Config.max_memory`.
+    /// Used to explain why we failed to decode the text.
+    pub fn decodings_pruned(&self) -> usize {
+        self.decodings_pruned.load(Ordering::Relaxed)
+    }
+
+    /// The regexes in `Config.regex` of this run.
+    /// Every candidate text is checked against them, so they are only compiled once a run.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first pattern which isn't a valid regex
+    pub fn regexes(&self, config: &Config) -> Result<&[Regex], regex::Error> {
+        self.regexes
+            .get_or_try_init(|| compile_patterns(&config.regex))
+            .map(Vec::as_slice)
+    }
+
+    /// The regex matching the known flag formats and `Config.flag_formats` of this run,
+    /// compiled once a run like [`regexes`](Self::regexes)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the regex is too big to compile
+    pub fn flag_regex(&self, config: &Config) -> Result<&Regex, regex::Error> {
+        self.flags
+            .get_or_try_init(|| flag_regex(&config.flag_formats))
+    }
+
+    /// The decodings which looked most like plaintext, best first.
+    /// Used to show how close we got, so the user can see if Ares was on the right track.
+    pub fn closest_attempts(&self) -> Vec<DecoderResult> {
+        self.best_guess.closest()
+    }
+}
+
+/// Keeps the timer of a run paused until it is dropped, see [`SearchContext::pause_timer`]
+pub struct TimerPause {
+    /// The pause count of the run
+    paused: Arc<AtomicUsize>,
+}
+
+impl Drop for TimerPause {
+    fn drop(&mut self) {
+        self.paused.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,157 @@
+//! Different paths through the search often reach the same text, like base64 decoding
+//! to text which is also reached by reversing twice. The decoding cache remembers what
+//! each decoder gave for each text, so decoding the same text again is a lookup.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use log::trace;
+use lru::LruCache;
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::registry::SharedDecoder;
+
+/// The results of running decoders on texts during one search, keyed by (decoder, text).
+/// Once it is full the least recently used result is forgotten.
+pub struct DecodingCache {
+    /// The cached results by the hash of the decoder name and text, None if the cache is off
+    results: Option<Mutex<LruCache<u64, CrackResult>>>,
+}
+
+impl DecodingCache {
+    /// Creates a cache which remembers up to `capacity` results, a capacity of 0 turns it off
+    pub fn new(capacity: usize) -> Self {
+        DecodingCache {
+            results: NonZeroUsize::new(capacity)
+                .map(|capacity| Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Returns what the decoder gave for this text last time,
+    /// or runs the decoder and remembers what it gives.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another thread panicked while holding the cache's lock.
+    pub fn crack(
+        &self,
+        decoder: &SharedDecoder,
+        text: &str,
+        checker: &CheckerTypes,
+    ) -> CrackResult {
+        let Some(results) = &self.results else {
+            return decoder.crack(text, checker);
+        };
+
+        let key = cache_key(decoder.get_name(), text);
+        if let Some(result) = results.lock().unwrap().get(&key) {
+            trace!(
+                "Reusing the result of {} from the cache",
+                decoder.get_name()
+            );
+            return result.clone();
+        }
+
+        // The lock isn't held while decoding, so other decoders can use the cache in the meantime
+        let result = decoder.crack(text, checker);
+        results.lock().unwrap().put(key, result.clone());
+        result
+    }
+}
+
+/// Hashes the decoder name and text together, so long texts don't use up memory in the cache
+fn cache_key(decoder_name: &str, text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    decoder_name.hash(&mut hasher);
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::DecodingCache;
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        default_checker::DefaultChecker,
+        CheckerTypes,
+    };
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::{Crack, Decoder, DefaultDecoder};
+    use crate::decoders::registry::SharedDecoder;
+
+    /// A decoder which counts how many times it has been run
+    struct CountingDecoder {
+        /// The decoder the results say they are from
+        decoder: Decoder<DefaultDecoder>,
+        /// How many times `crack` has been called
+        runs: AtomicUsize,
+    }
+
+    impl Crack for CountingDecoder {
+        fn new() -> Self {
+            CountingDecoder {
+                decoder: Decoder::default(),
+                runs: AtomicUsize::new(0),
+            }
+        }
+        fn crack(&self, text: &str, _checker: &CheckerTypes) -> CrackResult {
+            self.runs.fetch_add(1, Ordering::Relaxed);
+            CrackResult::new(&self.decoder, text.to_string())
+        }
+        fn get_tags(&self) -> &Vec<&str> {
+            &self.decoder.tags
+        }
+        fn get_name(&self) -> &str {
+            self.decoder.name
+        }
+        fn get_popularity(&self) -> f32 {
+            self.decoder.popularity
+        }
+        fn get_expected_runtime(&self) -> f32 {
+            self.decoder.expected_runtime
+        }
+        fn get_description(&self) -> &str {
+            self.decoder.description
+        }
+        fn get_link(&self) -> &str {
+            self.decoder.link
+        }
+    }
+
+    fn runs_after_cracking(cache: &DecodingCache, texts: &[&str]) -> usize {
+        let counting = Arc::new(CountingDecoder::new());
+        let decoder: SharedDecoder = counting.clone();
+        let checker = CheckerTypes::CheckDefault(Checker::<DefaultChecker>::new());
+        for text in texts {
+            cache.crack(&decoder, text, &checker);
+        }
+        counting.runs.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn decoding_the_same_text_again_uses_the_cache() {
+        let cache = DecodingCache::new(10);
+        assert_eq!(runs_after_cracking(&cache, &["aGVsbG8=", "aGVsbG8="]), 1);
+    }
+
+    #[test]
+    fn least_recently_used_results_are_forgotten() {
+        let cache = DecodingCache::new(1);
+        assert_eq!(
+            runs_after_cracking(&cache, &["first", "second", "first"]),
+            3
+        );
+    }
+
+    #[test]
+    fn a_capacity_of_zero_turns_the_cache_off() {
+        let cache = DecodingCache::new(0);
+        assert_eq!(runs_after_cracking(&cache, &["same", "same"]), 2);
+    }
+}
@@ -0,0 +1,215 @@
+//! Iterative deepening does a complete depth first sweep of every decoding 1 decoder deep,
+//! then 2, then 3 and so on. https://en.wikipedia.org/wiki/Iterative_deepening_depth-first_search
+//! After each sweep we report the decoding that looks most like plaintext,
+//! so even short timeouts give the most likely shallow decodings.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crossbeam::channel::Sender;
+use log::trace;
+
+use super::beam::plaintext_score;
+use super::bfs::{check_if_string_cant_be_decoded, text_hash};
+use super::context::SearchContext;
+use super::decoding_cache::DecodingCache;
+use crate::cli_pretty_printing::best_candidate_so_far;
+use crate::config::Config;
+use crate::filtration_system::MyResults;
+use crate::DecoderResult;
+
+/// What we found during one sweep
+struct Sweep {
+    /// How many decoders deep this sweep goes
+    depth_limit: u32,
+    /// The shallowest depth we have seen each text at, by its hash.
+    /// We only decode a text again if we reach it by a shorter path.
+    seen: HashMap<u64, usize>,
+    /// The decoding at the depth limit which looks most like plaintext, with its score
+    best: Option<(usize, DecoderResult)>,
+    /// Whether any decoding reached the depth limit. If none did, going deeper finds nothing new.
+    reached_limit: bool,
+    /// The state of this run, which keeps the decodings which look most like plaintext at any depth
+    context: Arc<SearchContext>,
+}
+
+/// Searches deeper and deeper until we find the plaintext, run out of decodings or are stopped
+pub fn iterative_deepening(
+    input: String,
+    config: Arc<Config>,
+    result_sender: Sender<Option<DecoderResult>>,
+    stop: Arc<AtomicBool>,
+    context: Arc<SearchContext>,
+) {
+    let max_depth = config.max_depth;
+    // Depth first search only keeps the current path, so nothing is ever pruned
+    context.start_search();
+    // Every sweep decodes the texts of the sweep before it again, so they share one cache
+    let cache = DecodingCache::new(config.decoding_cache_size);
+
+    let mut depth_limit: u32 = 1;
+    while !stop.load(Ordering::Relaxed) {
+        if super::depth_limit_reached(depth_limit, max_depth) {
+            context.hit_depth_limit();
+            break;
+        }
+        trace!("Sweeping every decoding {} decoders deep", depth_limit);
+        context.reached_depth(depth_limit);
+
+        let mut sweep = Sweep {
+            depth_limit,
+            seen: HashMap::from([(text_hash(&input), 0)]),
+            best: None,
+            reached_limit: false,
+            context: context.clone(),
+        };
+        let initial = DecoderResult {
+            text: vec![input.clone()],
+            path: vec![],
+            confidence: 0.0,
+        };
+        super::record_in_search_tree(&initial, &config, &context);
+        if let Some(result) = depth_limited_search(initial, &mut sweep, &config, &cache, &stop) {
+            result_sender
+                .send(Some(result))
+                .expect("Should succesfully send the result");
+            stop.store(true, Ordering::Relaxed);
+            return;
+        }
+
+        if let Some((_, best)) = &sweep.best {
+            best_candidate_so_far(depth_limit, best, &config);
+        }
+        if !sweep.reached_limit {
+            break;
+        }
+        depth_limit += 1;
+    }
+    result_sender.try_send(None).ok();
+}
+
+/// Depth first search from `current` down to the depth limit of the sweep.
+/// Returns the plaintext if we find it, in top results mode we store it and keep going instead.
+fn depth_limited_search(
+    current: DecoderResult,
+    sweep: &mut Sweep,
+    config: &Arc<Config>,
+    cache: &DecodingCache,
+    stop: &AtomicBool,
+) -> Option<DecoderResult> {
+    if stop.load(Ordering::Relaxed) {
+        return None;
+    }
+    let child_depth = current.path.len() + 1;
+
+    match super::perform_decoding(&current, config, cache, &sweep.context) {
+        MyResults::Break(res) => {
+            let mut path = current.path;
+            let text = res.unencrypted_text.clone().unwrap_or_default();
+            let confidence = res.confidence;
+            path.push(Arc::new(res));
+            let result = DecoderResult {
+                text,
+                path,
+                confidence,
+            };
+            super::record_in_search_tree(&result, config, &sweep.context);
+            if !config.top_results {
+                return Some(result);
+            }
+            // Earlier sweeps already found the plaintexts above the depth limit
+            if child_depth == sweep.depth_limit as usize {
+                super::store_top_result(&result, config, &sweep.context, stop);
+            }
+            None
+        }
+        MyResults::Continue(results) => {
+            // Decoders like Caesar return a text for every key, we go down the most English ones first
+            for (_, mut res) in super::ranked_candidates(results, config) {
+                let mut text = res.unencrypted_text.take().unwrap_or_default();
+                text.retain(|s| {
+                    if check_if_string_cant_be_decoded(s)
+                        || super::looks_like_random_binary(s, config)
+                    {
+                        return false;
+                    }
+                    let shallowest = sweep.seen.entry(text_hash(s)).or_insert(child_depth);
+                    if *shallowest < child_depth {
+                        return false;
+                    }
+                    *shallowest = child_depth;
+                    true
+                });
+                if text.is_empty() {
+                    continue;
+                }
+
+                let mut path = current.path.clone();
+                path.push(Arc::new(res));
+                let child = DecoderResult {
+                    text,
+                    path,
+                    confidence: 0.0,
+                };
+                sweep.context.best_guess.offer(&child);
+                super::record_in_search_tree(&child, config, &sweep.context);
+
+                if child_depth < sweep.depth_limit as usize {
+                    if let Some(result) = depth_limited_search(child, sweep, config, cache, stop) {
+                        return Some(result);
+                    }
+                    continue;
+                }
+
+                sweep.reached_limit = true;
+                let score = plaintext_score(&child.text[0]);
+                if !matches!(&sweep.best, Some((best, _)) if *best >= score) {
+                    sweep.best = Some((score, child));
+                }
+            }
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossbeam::channel::bounded;
+
+    use super::*;
+    use crate::config::default_config;
+
+    #[test]
+    fn iterative_deepening_succeeds() {
+        let (tx, rx) = bounded::<Option<DecoderResult>>(1);
+        let stopper = Arc::new(AtomicBool::new(false));
+        iterative_deepening(
+            "b2xsZWg=".into(),
+            default_config(),
+            tx,
+            stopper,
+            Arc::default(),
+        );
+        let result = rx.recv().unwrap();
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().text[0], "hello");
+    }
+
+    #[test]
+    fn iterative_deepening_finds_chains_of_decoders() {
+        // Caesar Cipher (Rot13) -> Base64, like the breadth first search regression test
+        let (tx, rx) = bounded::<Option<DecoderResult>>(1);
+        let stopper = Arc::new(AtomicBool::new(false));
+        iterative_deepening(
+            "MTkyLjE2OC4wLjE=".into(),
+            default_config(),
+            tx,
+            stopper,
+            Arc::default(),
+        );
+        let result = rx.recv().unwrap();
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().text[0], "192.168.0.1");
+    }
+}
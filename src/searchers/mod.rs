@@ -3,22 +3,96 @@
 //! Click here to find out more:
 //! https://broadleaf-angora-7db.notion.site/Search-Nodes-Edges-What-should-they-look-like-b74c43ca7ac341a1a5cfdbeb84a7eef0
 
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use crossbeam::channel::bounded;
-use log::debug;
+use log::{debug, info, warn};
 
 use crate::checkers::athena::Athena;
 use crate::checkers::checker_type::{Check, Checker};
+use crate::checkers::wait_athena::WaitAthena;
 use crate::checkers::CheckerTypes;
-use crate::config::get_config;
+use crate::cli_pretty_printing::top_result_found;
+use crate::config::{Config, SearchStrategy};
+use crate::decoders::crack_results::CrackResult;
 use crate::filtration_system::{filter_and_get_decoders, MyResults};
-use crate::{timer, DecoderResult};
+use crate::searchers::checkpoint::Checkpoint;
+use crate::searchers::context::SearchContext;
+use crate::searchers::decoding_cache::DecodingCache;
+use crate::storage::quadgrams::ENGLISH_QUADGRAMS;
+use crate::{timer, CrackOutcome, DecoderResult};
+/// Beam search keeps only the most promising decodings at each depth of the breadth first search
+mod beam;
 /// This module provides access to the breadth first search
 /// which searches for the plaintext.
 mod bfs;
+/// Saves where breadth first search is after each depth, so it can be resumed
+pub(crate) mod checkpoint;
+/// The state of one run, so runs in the same process don't share it
+pub(crate) mod context;
+/// Remembers what each decoder gave for each text, so paths which reach the same text don't decode it again
+pub(crate) mod decoding_cache;
+/// Iterative deepening searches depth 1, then 2, then 3... reporting the best decoding after each
+mod iterative_deepening;
+
+/// How many of the decodings which look most like plaintext we keep to show when the search fails
+const CLOSEST_ATTEMPTS_KEPT: usize = 5;
+
+/// The decodings which look most like plaintext out of every decoding a search has made,
+/// ranked by their English quadgram fitness so English beats ROT13 and base64 which are mostly letters too.
+/// If the timer runs out before a checker identifies the plaintext, the best of them is our best guess.
+#[derive(Default)]
+pub struct BestGuess {
+    /// The closest decodings so far with their quadgram fitness, best first
+    closest: Mutex<Vec<(f64, DecoderResult)>>,
+}
+
+impl BestGuess {
+    /// Keeps the decoding if it looks more like plaintext than the ones kept so far
+    ///
+    /// # Panics
+    ///
+    /// Panics if another thread panicked while holding the lock.
+    pub fn offer(&self, decoding: &DecoderResult) {
+        // Texts with too few letters to score, like hex, are the least like plaintext
+        let score = ENGLISH_QUADGRAMS
+            .fitness(&decoding.text[0])
+            .unwrap_or(f64::NEG_INFINITY);
+        let mut closest = self.closest.lock().unwrap();
+        if closest.len() == CLOSEST_ATTEMPTS_KEPT
+            && closest.last().is_some_and(|(worst, _)| *worst >= score)
+        {
+            return;
+        }
+        // Iterative deepening reaches the same decodings again in each sweep
+        if closest
+            .iter()
+            .any(|(_, kept)| kept.text[0] == decoding.text[0])
+        {
+            return;
+        }
+        let position = closest.partition_point(|(kept, _)| *kept >= score);
+        closest.insert(position, (score, decoding.clone()));
+        closest.truncate(CLOSEST_ATTEMPTS_KEPT);
+    }
+
+    /// The decodings kept so far, best first
+    ///
+    /// # Panics
+    ///
+    /// Panics if another thread panicked while holding the lock.
+    pub fn closest(&self) -> Vec<DecoderResult> {
+        self.closest
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, decoding)| decoding.clone())
+            .collect()
+    }
+}
 
 /*pub struct Tree <'a> {
     // Wrap in a box because
@@ -36,47 +110,320 @@ mod bfs;
 /// We can return an Option? An Enum? And then match on that
 /// So if we return CrackSuccess we return
 /// Else if we return an array, we add it to the children and go again.
-pub fn search_for_plaintext(input: String) -> Option<DecoderResult> {
-    let timeout = get_config().timeout;
-    let timer = timer::start(timeout);
+///
+/// The search also stops as soon as `cancel` is set.
+/// Breadth first and beam search carry on from `resume_from` if it is given, iterative deepening starts again.
+/// How far the search got is recorded in `context`, which belongs to this run.
+pub fn search_for_plaintext(
+    input: String,
+    config: Arc<Config>,
+    cancel: &AtomicBool,
+    resume_from: Option<Checkpoint>,
+    context: &Arc<SearchContext>,
+) -> CrackOutcome {
+    let timeout = if config.auto_timeout {
+        let decoders = filter_and_get_decoders(&DecoderResult::default(), &config);
+        let timeout = timer::auto_timeout(input.len(), decoders.components.len());
+        info!(
+            "Picked a timeout of {} seconds for {} bytes and {} decoders",
+            timeout,
+            input.len(),
+            decoders.components.len()
+        );
+        timeout
+    } else {
+        config.timeout
+    };
+    // For stopping the thread, and the timer once the search is over
+    let stop = Arc::new(AtomicBool::new(false));
+    let timer = timer::start(timeout, config.clone(), context.paused(), stop.clone());
 
     let (result_sender, result_recv) = bounded::<Option<DecoderResult>>(1);
-    // For stopping the thread
-    let stop = Arc::new(AtomicBool::new(false));
     let s = stop.clone();
-    // Change this to select which search algorithm we want to use.
-    let handle = thread::spawn(move || bfs::bfs(input, result_sender, s));
+    let strategy = config.search_strategy;
+    let top_results = config.top_results;
+    let search_context = context.clone();
+    let pool = config.threads.and_then(build_thread_pool);
+    let search_thread = move || {
+        let search = move || match strategy {
+            SearchStrategy::IterativeDeepening => iterative_deepening::iterative_deepening(
+                input,
+                config,
+                result_sender,
+                s,
+                search_context,
+            ),
+            SearchStrategy::Bfs | SearchStrategy::Beam { .. } => {
+                bfs::bfs(input, config, result_sender, s, search_context, resume_from)
+            }
+        };
+        // The decoders run with rayon, so inside `install` they only use the threads of this pool
+        match pool {
+            Some(pool) => pool.install(search),
+            None => search(),
+        }
+    };
+    // Browsers can't start threads, so on wasm32 the search runs until it finishes before we look at it
+    let handle = if cfg!(target_arch = "wasm32") {
+        search_thread();
+        None
+    } else {
+        Some(thread::spawn(search_thread))
+    };
 
     loop {
         if let Ok(res) = result_recv.try_recv() {
             debug!("Found exit result: {:?}", res);
-            handle.join().unwrap();
-            return res;
+            if let Some(handle) = handle {
+                handle.join().unwrap();
+            }
+            stop.store(true, Ordering::Relaxed);
+            // The searchers send None when they run out of things to decode
+            let Some(res) = res else {
+                return CrackOutcome::Exhausted;
+            };
+            return CrackOutcome::Cracked(res);
+        }
+
+        if cancel.load(Ordering::Relaxed) {
+            stop.store(true, Ordering::Relaxed);
+            debug!("The search was cancelled");
+            return CrackOutcome::Cancelled;
         }
 
         if timer.try_recv().is_ok() {
-            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+            stop.store(true, Ordering::Relaxed);
             debug!("Ares has failed to decode");
             // this would wait for whole iteration to finish!
             // handle.join().unwrap();
-            return None;
+            return CrackOutcome::TimedOut {
+                partial_results: vec![],
+                // In top results mode the plaintexts found are the answer, not a guess
+                best_guess: if top_results {
+                    None
+                } else {
+                    context.closest_attempts().into_iter().next()
+                },
+            };
         }
     }
 }
 
+/// Stores a plaintext found in top results mode and shows it to the user if it is new.
+/// Returns true, after stopping the search, once `Config.top_results_limit` plaintexts have been found.
+fn store_top_result(
+    result: &DecoderResult,
+    config: &Config,
+    context: &SearchContext,
+    stop: &AtomicBool,
+) -> bool {
+    if !context.top_results.add(result) {
+        return false;
+    }
+    top_result_found(result, config);
+    if let Some(callback) = &config.top_result_callback {
+        callback(result);
+    }
+    let limit_reached = config
+        .top_results_limit
+        .is_some_and(|limit| context.top_results.count() >= limit);
+    if limit_reached {
+        debug!(
+            "Found {} plaintexts, stopping the search",
+            context.top_results.count()
+        );
+        stop.store(true, Ordering::Relaxed);
+    }
+    limit_reached
+}
+
+/// Adds the decoding to the search tree of the run when `Config.export_search_tree` is set
+fn record_in_search_tree(decoding: &DecoderResult, config: &Config, context: &SearchContext) {
+    if config.export_search_tree.is_some() {
+        context.search_tree.record(decoding);
+    }
+}
+
+/// How much the text looks like plaintext, from 0.0 to 1.0.
+/// Unlike the confidence of a checker this is known for every decoding, not just plaintext.
+pub fn plaintext_confidence(text: &str) -> f32 {
+    beam::plaintext_score(text) as f32 / 1000.0
+}
+
+/// Builds the pool the decoders of a search run on when `Config.threads` is set.
+/// If the pool can't be built we fall back to rayon's global pool.
+fn build_thread_pool(threads: usize) -> Option<rayon::ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|error| warn!("Could not create a pool of {} threads: {}", threads, error))
+        .ok()
+}
+
+/// Decoding the strings at `curr_depth` gives paths of `curr_depth` decoders,
+/// so we stop once that would be more than the maximum depth
+fn depth_limit_reached(curr_depth: u32, max_depth: Option<u32>) -> bool {
+    max_depth.is_some_and(|max_depth| curr_depth > max_depth)
+}
+
+/// Splits the results of the decoders into one result for each text they returned,
+/// with how each text ranks among the texts of its decoder, 0 being the one most like English.
+/// Every decoder's best text comes first, then every decoder's second best and so on,
+/// so the searchers decode the texts most likely to be right before the rest.
+/// Only the best `Config.candidates_per_decoder` texts of each decoder are kept, if it is set.
+fn ranked_candidates(results: Vec<CrackResult>, config: &Config) -> Vec<(usize, CrackResult)> {
+    let mut candidates: Vec<(usize, CrackResult)> = results
+        .into_iter()
+        .flat_map(|result| {
+            result
+                .into_best_candidates(config.candidates_per_decoder)
+                .into_iter()
+                .enumerate()
+        })
+        .collect();
+    // The sort is stable, so texts of the same rank stay in the order of their decoders
+    candidates.sort_by_key(|(rank, _)| *rank);
+    candidates
+}
+
+/// Keeps the decodings waiting to be decoded within `Config.max_nodes` and `Config.max_memory`,
+/// forgetting the ones which look least like plaintext first.
+/// The most promising decoding is always kept, so the search can carry on.
+fn prune_frontier(
+    mut frontier: Vec<DecoderResult>,
+    config: &Config,
+    context: &SearchContext,
+) -> Vec<DecoderResult> {
+    let max_nodes = config.max_nodes.unwrap_or(usize::MAX);
+    let max_memory = config.max_memory.unwrap_or(usize::MAX);
+    if frontier.len() <= max_nodes
+        && frontier.iter().map(approximate_size).sum::<usize>() <= max_memory
+    {
+        return frontier;
+    }
+
+    frontier.sort_by_cached_key(|decoding| Reverse(beam::plaintext_score(&decoding.text[0])));
+    let mut memory_used = 0;
+    let kept = frontier
+        .iter()
+        .take_while(|decoding| {
+            memory_used += approximate_size(decoding);
+            memory_used <= max_memory
+        })
+        .count()
+        .min(max_nodes)
+        .max(1);
+    debug!(
+        "Forgetting {} decodings to stay within the search limits",
+        frontier.len() - kept
+    );
+    context.pruned(frontier.len() - kept);
+    frontier.truncate(kept);
+    frontier
+}
+
+/// Roughly how many bytes a decoding uses, counting its text and the texts along its path
+fn approximate_size(decoding: &DecoderResult) -> usize {
+    let text: usize = decoding.text.iter().map(String::len).sum();
+    let path: usize = decoding
+        .path
+        .iter()
+        .map(|step| {
+            step.encrypted_text.len()
+                + step
+                    .unencrypted_text
+                    .iter()
+                    .flatten()
+                    .map(String::len)
+                    .sum::<usize>()
+        })
+        .sum();
+    std::mem::size_of::<DecoderResult>() + text + path
+}
+
+/// A cheap check for decodings which are clearly random binary, like base64 decoding
+/// text which was never base64. Nothing decodes random binary into plaintext, so the
+/// searchers drop these instead of running every decoder on them.
+fn looks_like_random_binary(text: &str, config: &Config) -> bool {
+    printable_ratio(text) < config.min_printable_ratio || shannon_entropy(text) > config.max_entropy
+}
+
+/// The fraction of characters which are printable, decoders turn invalid UTF-8 into
+/// the replacement character so it counts as unprintable.
+fn printable_ratio(text: &str) -> f32 {
+    let (mut chars, mut printable) = (0, 0);
+    for c in text.chars() {
+        chars += 1;
+        if c == char::REPLACEMENT_CHARACTER || (c.is_control() && !c.is_whitespace()) {
+            continue;
+        }
+        printable += 1;
+    }
+    if chars == 0 {
+        return 1.0;
+    }
+    printable as f32 / chars as f32
+}
+
+/// The Shannon entropy of the bytes of the text, in bits per byte from 0.0 to 8.0
+fn shannon_entropy(text: &str) -> f32 {
+    let mut counts = [0usize; 256];
+    for byte in text.bytes() {
+        counts[byte as usize] += 1;
+    }
+    let len = text.len() as f32;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f32 / len;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
 /// Performs the decodings by getting all of the decoders
 /// and calling `.run` which in turn loops through them and calls
-/// `.crack()`.
-fn perform_decoding(text: &DecoderResult) -> MyResults {
-    let decoders = filter_and_get_decoders(text);
-    let athena_checker = Checker::<Athena>::new();
-    let checker = CheckerTypes::CheckAthena(athena_checker);
-    decoders.run(&text.text[0], checker)
+/// `.crack()`. Decoders which already decoded this text in this search are looked up in the cache instead.
+fn perform_decoding(
+    text: &DecoderResult,
+    config: &Arc<Config>,
+    cache: &DecodingCache,
+    context: &Arc<SearchContext>,
+) -> MyResults {
+    let _span = tracing::debug_span!(
+        "expand",
+        depth = text.path.len(),
+        length = text.text[0].len()
+    )
+    .entered();
+    let decoders = filter_and_get_decoders(text, config);
+    // In top results mode we collect plaintexts instead of asking the human checker
+    let checker = if config.top_results {
+        CheckerTypes::CheckWaitAthena(Checker::<WaitAthena>::new().with_config(config.clone()))
+    } else {
+        let decoding_path: Vec<String> = text
+            .path
+            .iter()
+            .map(|step| step.decoder_with_key())
+            .collect();
+        CheckerTypes::CheckAthena(
+            Checker::<Athena>::new()
+                .with_config(config.clone())
+                .with_context(context.clone())
+                .with_decoding_path(decoding_path.join(" → ")),
+        )
+    };
+    if config.deterministic {
+        return decoders.run_in_order(&text.text[0], checker, cache, context);
+    }
+    decoders.run(&text.text[0], checker, cache, context)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::default_config;
 
     // https://github.com/bee-san/Ares/pull/14/files#diff-b8829c7e292562666c7fa5934de7b478c4a5de46d92e42c46215ac4d9ff89db2R37
     // Only used for tests!
@@ -99,10 +446,87 @@ mod tests {
         assert!(!result);
     }
 
+    #[test]
+    fn prune_frontier_keeps_the_most_promising_decodings() {
+        let config = Config {
+            max_nodes: Some(1),
+            ..Default::default()
+        };
+        let frontier = vec![
+            DecoderResult::_new("68656c6c6f"),
+            DecoderResult::_new("hello there"),
+        ];
+        let kept = prune_frontier(frontier, &config, &SearchContext::default());
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].text[0], "hello there");
+    }
+
+    #[test]
+    fn prune_frontier_stays_within_the_memory_budget() {
+        let frontier = vec![
+            DecoderResult::_new("hello there"),
+            DecoderResult::_new("general kenobi"),
+        ];
+        let config = Config {
+            max_memory: Some(approximate_size(&frontier[1]) + 1),
+            ..Default::default()
+        };
+        assert_eq!(
+            prune_frontier(frontier, &config, &SearchContext::default()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn prune_frontier_keeps_everything_without_limits() {
+        let frontier = vec![DecoderResult::_new("aaa"), DecoderResult::_new("bbb")];
+        assert_eq!(
+            prune_frontier(frontier, &Config::default(), &SearchContext::default()).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn depth_limit_allows_paths_up_to_max_depth() {
+        assert!(!depth_limit_reached(1, Some(1)));
+        assert!(depth_limit_reached(2, Some(1)));
+        assert!(!depth_limit_reached(1000, None));
+    }
+
+    #[test]
+    fn random_binary_is_pruned() {
+        let config = Config::default();
+        let bytes: Vec<u8> = (0..512u32).map(|i| ((i * 167 + 13) % 256) as u8).collect();
+        assert!(looks_like_random_binary(
+            &String::from_utf8_lossy(&bytes),
+            &config
+        ));
+        assert!(looks_like_random_binary(
+            "\u{FFFD}\u{FFFD}\u{FFFD}a",
+            &config
+        ));
+        assert!(!looks_like_random_binary("hello world", &config));
+        assert!(!looks_like_random_binary(
+            "aHR0cHM6Ly93d3cuZ29vZ2xlLmNvbQ==",
+            &config
+        ));
+    }
+
+    #[test]
+    fn shannon_entropy_of_repeated_text_is_zero() {
+        assert_eq!(shannon_entropy("aaaa"), 0.0);
+        assert_eq!(shannon_entropy("abab"), 1.0);
+    }
+
     #[test]
     fn perform_decoding_succeeds() {
         let dc = DecoderResult::_new("aHR0cHM6Ly93d3cuZ29vZ2xlLmNvbQ==");
-        let result = perform_decoding(&dc);
+        let result = perform_decoding(
+            &dc,
+            &default_config(),
+            &DecodingCache::new(10),
+            &Arc::default(),
+        );
         assert!(
             result
                 ._break_value()
@@ -115,7 +539,105 @@ mod tests {
     fn perform_decoding_succeeds_empty_string() {
         // Some decoders like base64 return even when the string is empty.
         let dc = DecoderResult::_new("");
-        let result = perform_decoding(&dc);
+        let result = perform_decoding(
+            &dc,
+            &default_config(),
+            &DecodingCache::new(10),
+            &Arc::default(),
+        );
         assert!(result._break_value().is_none());
     }
+
+    #[test]
+    fn ranked_candidates_put_the_best_text_of_each_decoder_first() {
+        use crate::decoders::caesar_decoder::CaesarDecoder;
+        use crate::decoders::interface::{Crack, Decoder};
+
+        let mut caesar = CrackResult::new(&Decoder::<CaesarDecoder>::new(), "ifmmp".to_string());
+        caesar.set_candidates(vec![
+            ("jgnnq yqtnf".to_string(), Some("shift=2".to_string())),
+            ("hello world".to_string(), Some("shift=25".to_string())),
+        ]);
+        let mut other = caesar.clone();
+        other.set_candidates(vec![
+            ("ok".to_string(), Some("shift=3".to_string())),
+            ("hello there".to_string(), Some("shift=4".to_string())),
+        ]);
+
+        let keys = |config: &Config| -> Vec<(usize, String)> {
+            ranked_candidates(vec![caesar.clone(), other.clone()], config)
+                .into_iter()
+                .map(|(rank, result)| (rank, result.key.unwrap()))
+                .collect()
+        };
+        // Every text is kept by default, the best of each decoder first
+        assert_eq!(
+            keys(&Config::default()),
+            [
+                (0, "shift=25".to_string()),
+                (0, "shift=4".to_string()),
+                (1, "shift=2".to_string()),
+                (1, "shift=3".to_string())
+            ]
+        );
+        let config = Config {
+            candidates_per_decoder: Some(1),
+            ..Config::default()
+        };
+        assert_eq!(
+            keys(&config),
+            [(0, "shift=25".to_string()), (0, "shift=4".to_string())]
+        );
+    }
+
+    #[test]
+    fn best_guess_keeps_the_decoding_most_like_plaintext() {
+        let best_guess = BestGuess::default();
+        best_guess.offer(&DecoderResult::_new("68656c6c6f"));
+        best_guess.offer(&DecoderResult::_new("hello there"));
+        best_guess.offer(&DecoderResult::_new("01101000 01101001"));
+        assert_eq!(best_guess.closest()[0].text[0], "hello there");
+    }
+
+    #[test]
+    fn best_guess_ranks_rot13_english_above_base64() {
+        let best_guess = BestGuess::default();
+        // ROT13 and base64 are mostly letters too, but only ROT13 keeps the quadgrams of English
+        best_guess.offer(&DecoderResult::_new("aGVsbG8gdGhlcmUgZ2VuZXJhbCBrZW5vYmk="));
+        best_guess.offer(&DecoderResult::_new("uryyb gurer trareny xrabov"));
+        assert_eq!(
+            best_guess.closest()[0].text[0],
+            "uryyb gurer trareny xrabov"
+        );
+    }
+
+    #[test]
+    fn best_guess_keeps_the_closest_attempts_best_first() {
+        let best_guess = BestGuess::default();
+        for text in [
+            "68656c6c6f20746865726521",
+            "aGVsbG8gdGhlcmUgZ2VuZXJhbCBrZW5vYmk=",
+            "hello there general kenobi",
+            "01101000 01101001",
+            "uryyb gurer trareny xrabov",
+            "hello there general kenobi",
+            "0110 1000 0110 0101",
+        ] {
+            best_guess.offer(&DecoderResult::_new(text));
+        }
+        let closest: Vec<String> = best_guess
+            .closest()
+            .into_iter()
+            .map(|decoding| decoding.text[0].clone())
+            .collect();
+        assert_eq!(closest.len(), CLOSEST_ATTEMPTS_KEPT);
+        assert_eq!(
+            closest[..3],
+            [
+                "hello there general kenobi",
+                "uryyb gurer trareny xrabov",
+                "aGVsbG8gdGhlcmUgZ2VuZXJhbCBrZW5vYmk="
+            ]
+        );
+    }
 }
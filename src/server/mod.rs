@@ -0,0 +1,201 @@
+//! `ares serve` runs Ares as a REST API, so a team can share one instance.
+//! `POST /crack` takes JSON like `{"text": "aGVsbG8=", "timeout": 5}` and returns the `DecoderResult` as JSON.
+//! It also takes `only`, `exclude` and `regex`, which work like the CLI arguments.
+//! `GET /decoders` returns every decoder, like `ares list-decoders --json`.
+
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::cli_pretty_printing::decoder_listings;
+use crate::config::Config;
+use crate::perform_cracking;
+
+/// The biggest request body we read, ciphertexts are never this big
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// The timeout for requests which don't ask for one, in seconds
+const DEFAULT_TIMEOUT: u32 = 5;
+
+/// What the server allows, from the `ares serve` arguments
+pub struct ServerOptions {
+    /// The port to listen on
+    pub port: u16,
+    /// How many texts can be cracked at once, requests over this are turned away
+    pub max_concurrent: usize,
+    /// The longest timeout a request can ask for, in seconds
+    pub max_timeout: u32,
+}
+
+/// The body of `POST /crack`
+#[derive(Deserialize)]
+struct CrackRequest {
+    /// The text to crack
+    text: String,
+    /// How long to search for, in seconds. This is capped at the server's maximum timeout,
+    /// so a request can't ask for no timeout with 0
+    #[serde(default)]
+    timeout: Option<u32>,
+    /// Only use these decoders, like `--only`
+    #[serde(default)]
+    only: Vec<String>,
+    /// Never use these decoders, like `--exclude`
+    #[serde(default)]
+    exclude: Vec<String>,
+    /// Look for these regexes or cribs, like `--regex`
+    #[serde(default)]
+    regex: Vec<String>,
+}
+
+impl CrackRequest {
+    /// The config to crack this request with.
+    /// Nobody is there to answer the human checker, so it is off.
+    fn config(&self, max_timeout: u32) -> Config {
+        Config {
+            timeout: match self.timeout.unwrap_or(DEFAULT_TIMEOUT) {
+                0 => max_timeout,
+                timeout => timeout.min(max_timeout),
+            },
+            api_mode: true,
+            human_checker_on: false,
+            enabled_decoders: self.only.clone(),
+            disabled_decoders: self.exclude.clone(),
+            regex: self.regex.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// What we send back when we don't have a `DecoderResult`
+#[derive(Serialize)]
+struct ErrorResponse<'a> {
+    /// What went wrong
+    error: &'a str,
+}
+
+/// Counts a crack as running until it is dropped, even if the crack panics
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Answers requests until the process is killed.
+/// Every request is handled on its own thread.
+/// # Errors
+/// Returns an error if the server can't listen on the port
+pub fn serve(options: ServerOptions) -> Result<(), String> {
+    let server = Server::http(("0.0.0.0", options.port)).map_err(|error| error.to_string())?;
+    let options = Arc::new(options);
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    for request in server.incoming_requests() {
+        let options = options.clone();
+        let in_flight = in_flight.clone();
+        thread::spawn(move || handle_request(request, &options, &in_flight));
+    }
+    Ok(())
+}
+
+/// Routes the request to its endpoint and sends back the JSON it returns
+fn handle_request(mut request: Request, options: &ServerOptions, in_flight: &AtomicUsize) {
+    trace!("Got a {:?} request for {}", request.method(), request.url());
+    let (status, body) = match (request.method(), request.url()) {
+        (Method::Get, "/decoders") => (200, to_json(&decoder_listings())),
+        (Method::Post, "/crack") => crack(&mut request, options, in_flight),
+        _ => error(
+            404,
+            "There is no such endpoint, use POST /crack or GET /decoders",
+        ),
+    };
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("the content type header is valid");
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(error) = request.respond(response) {
+        debug!("Could not send the response: {}", error);
+    }
+}
+
+/// Cracks the text in the request, returning the status code and JSON body to send back
+fn crack(request: &mut Request, options: &ServerOptions, in_flight: &AtomicUsize) -> (u16, String) {
+    let mut body = String::new();
+    if request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .is_err()
+    {
+        return error(400, "The body must be UTF-8 text");
+    }
+    let crack_request: CrackRequest = match serde_json::from_str(&body) {
+        Ok(crack_request) => crack_request,
+        Err(parse_error) => {
+            return error(
+                400,
+                &format!("The body is not a valid crack request: {parse_error}"),
+            )
+        }
+    };
+
+    // Each crack uses every core, so we only run a few at once
+    if in_flight.fetch_add(1, Ordering::SeqCst) >= options.max_concurrent {
+        in_flight.fetch_sub(1, Ordering::SeqCst);
+        return error(503, "Ares is busy cracking other texts, try again soon");
+    }
+    let _in_flight = InFlight(in_flight);
+
+    let config = crack_request.config(options.max_timeout);
+    match perform_cracking(&crack_request.text, config) {
+        Ok(outcome) => match outcome.into_plaintext() {
+            Some(result) => (200, to_json(&result)),
+            None => error(422, "Ares could not decode the text"),
+        },
+        Err(crack_error) => error(400, &crack_error.to_string()),
+    }
+}
+
+/// An error response with this status code
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, to_json(&ErrorResponse { error: message }))
+}
+
+/// Turns a response into JSON
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("responses are valid JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crack_requests_only_need_text() {
+        let crack_request: CrackRequest = serde_json::from_str(r#"{"text": "aGVsbG8="}"#).unwrap();
+        let config = crack_request.config(30);
+        assert_eq!(crack_request.text, "aGVsbG8=");
+        assert_eq!(config.timeout, DEFAULT_TIMEOUT);
+        assert!(config.enabled_decoders.is_empty());
+        assert!(!config.human_checker_on);
+    }
+
+    #[test]
+    fn crack_request_timeouts_are_capped() {
+        let crack_request: CrackRequest =
+            serde_json::from_str(r#"{"text": "aGVsbG8=", "timeout": 600, "only": ["base64"]}"#)
+                .unwrap();
+        let config = crack_request.config(30);
+        assert_eq!(config.timeout, 30);
+        let no_timeout: CrackRequest =
+            serde_json::from_str(r#"{"text": "aGVsbG8=", "timeout": 0}"#).unwrap();
+        assert_eq!(no_timeout.config(30).timeout, 30);
+        assert_eq!(config.enabled_decoders, vec!["base64".to_string()]);
+    }
+}
@@ -0,0 +1,82 @@
+//! How many times each decoder ran and how long it took, so `--stats` can show where the time went.
+//! Each run has its own, and every search in the run adds to them.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// How much one decoder was used during the run
+#[derive(Clone, Debug, Serialize)]
+pub struct DecoderStats {
+    /// The name of the decoder
+    pub name: String,
+    /// How many times the decoder was run
+    pub invocations: u64,
+    /// The wall time spent in the decoder, including the checkers it ran on what it decoded
+    pub time_taken: Duration,
+}
+
+/// The stats of every decoder which has run during one run, by decoder name
+#[derive(Default)]
+pub struct DecoderRuns {
+    /// The stats of each decoder, by its name
+    stats: Mutex<HashMap<String, DecoderStats>>,
+}
+
+impl DecoderRuns {
+    /// Records that the decoder ran once and how long it took
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn record(&self, name: &str, time_taken: Duration) {
+        let mut stats = self
+            .stats
+            .lock()
+            .expect("decoder stats lock should not be poisoned");
+        let decoder = stats
+            .entry(name.to_string())
+            .or_insert_with(|| DecoderStats {
+                name: name.to_string(),
+                invocations: 0,
+                time_taken: Duration::ZERO,
+            });
+        decoder.invocations += 1;
+        decoder.time_taken += time_taken;
+    }
+
+    /// Returns the stats of every decoder which has run, the one which took the longest first
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn stats(&self) -> Vec<DecoderStats> {
+        let mut stats: Vec<DecoderStats> = self
+            .stats
+            .lock()
+            .expect("decoder stats lock should not be poisoned")
+            .values()
+            .cloned()
+            .collect();
+        stats.sort_by(|a, b| b.time_taken.cmp(&a.time_taken).then(a.name.cmp(&b.name)));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoder_runs_are_added_up() {
+        let runs = DecoderRuns::default();
+        runs.record("Slow", Duration::from_secs(5));
+        runs.record("Slow", Duration::from_secs(5));
+        runs.record("Fast", Duration::from_secs(1));
+
+        let stats = runs.stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].name, "Slow");
+        assert_eq!(stats[0].invocations, 2);
+        assert_eq!(stats[0].time_taken, Duration::from_secs(10));
+        assert_eq!(stats[1].name, "Fast");
+    }
+}
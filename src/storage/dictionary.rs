@@ -0,0 +1,171 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// A set of words, used by the English Checker's dictionaries and the user's wordlists.
+///
+/// Dictionaries hold every word unless they would use more memory than their limit,
+/// then they become a Bloom filter which uses a fixed amount of memory. Bloom filters
+/// never miss a word, but sometimes say a word is in the dictionary when it isn't,
+/// see [`Dictionary::false_positive_rate`].
+pub struct Dictionary {
+    /// How the words are stored
+    storage: Storage,
+    /// How many words were added
+    len: usize,
+}
+
+/// The two ways a dictionary can store its words
+enum Storage {
+    /// Every word, so lookups are exact and the words can be listed
+    Exact(HashSet<Box<str>>),
+    /// Only a Bloom filter of the words
+    Bloom(BloomFilter),
+}
+
+/// Roughly how many bytes a hash set uses for each word on top of the word itself
+const BYTES_PER_WORD: usize = 32;
+
+impl Dictionary {
+    /// Creates a dictionary of the words. If storing every word would take more than
+    /// `memory_limit` bytes, a Bloom filter of that size is used instead.
+    /// The words are iterated twice, once to count them and once to store them.
+    pub fn new<'a>(
+        words: impl Iterator<Item = &'a str> + Clone,
+        memory_limit: Option<usize>,
+    ) -> Self {
+        let (len, bytes_needed) = words.clone().fold((0, 0), |(len, bytes), word| {
+            (len + 1, bytes + word.len() + BYTES_PER_WORD)
+        });
+
+        let storage = match memory_limit {
+            Some(limit) if bytes_needed > limit => {
+                Storage::Bloom(BloomFilter::new(words, len, limit))
+            }
+            _ => Storage::Exact(words.map(Box::from).collect()),
+        };
+        Dictionary { storage, len }
+    }
+
+    /// Is the word in the dictionary?
+    pub fn contains(&self, word: &str) -> bool {
+        match &self.storage {
+            Storage::Exact(words) => words.contains(word),
+            Storage::Bloom(bloom_filter) => bloom_filter.contains(word),
+        }
+    }
+
+    /// Every word in the dictionary, or None if it is a Bloom filter which can't list its words
+    pub fn words(&self) -> Option<impl Iterator<Item = &str>> {
+        match &self.storage {
+            Storage::Exact(words) => Some(words.iter().map(AsRef::as_ref)),
+            Storage::Bloom(_) => None,
+        }
+    }
+
+    /// The chance that a word which isn't in the dictionary is said to be in it.
+    /// This is 0 unless the dictionary went over its memory limit.
+    pub fn false_positive_rate(&self) -> f64 {
+        match &self.storage {
+            Storage::Exact(_) => 0.0,
+            Storage::Bloom(bloom_filter) => bloom_filter.false_positive_rate(self.len),
+        }
+    }
+
+    /// How many words were added to the dictionary
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the dictionary empty?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// A Bloom filter, each word sets `hashes` bits and a word is only in the
+/// filter if all of its bits are set.
+struct BloomFilter {
+    /// The bits, 64 at a time
+    bits: Vec<u64>,
+    /// How many bits each word sets
+    hashes: u64,
+}
+
+impl BloomFilter {
+    /// Creates a filter of at most `memory_limit` bytes holding the words,
+    /// using the number of hashes which gives the fewest false positives
+    fn new<'a>(words: impl Iterator<Item = &'a str>, len: usize, memory_limit: usize) -> Self {
+        let blocks = (memory_limit / 8).max(1);
+        let bits_per_word = (blocks * 64) as f64 / len.max(1) as f64;
+        let mut bloom_filter = BloomFilter {
+            bits: vec![0; blocks],
+            hashes: (bits_per_word * std::f64::consts::LN_2)
+                .round()
+                .clamp(1.0, 16.0) as u64,
+        };
+        for word in words {
+            for bit in bloom_filter.bit_indexes(word) {
+                bloom_filter.bits[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        bloom_filter
+    }
+
+    /// Are all of the word's bits set?
+    fn contains(&self, word: &str) -> bool {
+        self.bit_indexes(word)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// The bits the word sets, using double hashing to get every index from 2 hashes
+    fn bit_indexes(&self, word: &str) -> impl Iterator<Item = usize> {
+        let (first, second) = (seeded_hash(word, 0), seeded_hash(word, 1));
+        let len = (self.bits.len() * 64) as u64;
+        (0..self.hashes).map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % len) as usize)
+    }
+
+    /// The expected false positive rate holding `len` words
+    fn false_positive_rate(&self, len: usize) -> f64 {
+        let bits = (self.bits.len() * 64) as f64;
+        let hashes = self.hashes as f64;
+        (1.0 - (-hashes * len as f64 / bits).exp()).powf(hashes)
+    }
+}
+
+/// Hashes the word with a seed, so each seed gives an independent hash
+fn seeded_hash(word: &str, seed: u8) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    word.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dictionary;
+
+    #[test]
+    fn dictionary_without_a_limit_is_exact() {
+        let dictionary = Dictionary::new(["hello", "world"].into_iter(), None);
+        assert!(dictionary.contains("hello"));
+        assert!(!dictionary.contains("goodbye"));
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.false_positive_rate(), 0.0);
+        assert_eq!(dictionary.words().map(Iterator::count), Some(2));
+    }
+
+    #[test]
+    fn dictionary_over_its_limit_is_a_bloom_filter() {
+        let words: Vec<String> = (0..10_000).map(|i| format!("word{i}")).collect();
+        let dictionary = Dictionary::new(words.iter().map(String::as_str), Some(16 * 1024));
+
+        assert!(dictionary.words().is_none());
+        assert!(words.iter().all(|word| dictionary.contains(word)));
+        let false_positives = (0..10_000)
+            .filter(|i| dictionary.contains(&format!("other{i}")))
+            .count();
+        assert!(false_positives < 500, "{false_positives} false positives");
+        assert!(dictionary.false_positive_rate() < 0.05);
+    }
+}
@@ -0,0 +1,65 @@
+//! Texts the user said were not plaintext in the human checker.
+//! Symmetric decoders like Reverse and ROT13 reach the same text down many branches,
+//! so we remember the hash of each rejected text and don't ask about it again.
+//! Each run remembers its own rejections.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// The hash we remember the text by, so we don't keep every rejected text around
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The texts the user rejected in one run
+#[derive(Default)]
+pub struct HumanRejections {
+    /// The hashes of the rejected texts
+    hashes: Mutex<HashSet<u64>>,
+}
+
+impl HumanRejections {
+    /// Remembers that the user said the text is not plaintext
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn record(&self, text: &str) {
+        self.hashes
+            .lock()
+            .expect("human rejections lock should not be poisoned")
+            .insert(text_hash(text));
+    }
+
+    /// Whether the user already said the text is not plaintext this run
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn is_rejected(&self, text: &str) -> bool {
+        self.hashes
+            .lock()
+            .expect("human rejections lock should not be poisoned")
+            .contains(&text_hash(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejected_texts_are_remembered() {
+        let rejections = HumanRejections::default();
+        rejections.record("not the plaintext you are looking for");
+        assert!(rejections.is_rejected("not the plaintext you are looking for"));
+        assert!(!rejections.is_rejected("the plaintext you are looking for"));
+    }
+
+    #[test]
+    fn each_run_has_its_own_rejections() {
+        let first_run = HumanRejections::default();
+        first_run.record("not the plaintext you are looking for");
+        assert!(!HumanRejections::default().is_rejected("not the plaintext you are looking for"));
+    }
+}
@@ -2,25 +2,43 @@ use include_dir::include_dir;
 use include_dir::Dir;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::collections::HashSet;
+
+use self::dictionary::Dictionary;
+
+/// How many times each decoder ran and how long it took, for `--stats`
+pub mod decoder_stats;
+/// Sets of words, stored exactly or as Bloom filters to bound their memory
+pub mod dictionary;
+
+/// Texts the user rejected in the human checker, so they are only asked about once
+pub mod human_rejections;
+
+/// English quadgram statistics, used to score how much text looks like English
+pub mod quadgrams;
+/// Every decoding the search explored, for `--export-tree`
+pub mod search_tree;
+/// Stores every plaintext found in top results mode
+pub mod top_results;
+/// Wordlists the user gives us, used by the Wordlist Checker
+pub mod wordlists;
 
 /// Tells Rust to load the dictionaries into the binary
 /// at compile time. Which means that we do not waste
 /// time loading them at runtime.
-pub static DICTIONARIES: Lazy<HashMap<&str, HashSet<&str>>> = Lazy::new(|| {
+pub static DICTIONARIES: Lazy<HashMap<&str, Dictionary>> = Lazy::new(|| {
     /// The directory where our dictionaries are stored.
     static DICTIONARIES_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/storage/dictionaries");
     let mut entries = HashMap::new();
 
     for entry in DICTIONARIES_DIR.files() {
         let content = entry.contents_utf8().expect("The file you moved into the dictionaries folder is not UTF-8. The storage module only works on UTF-8 files.");
-        let hash_set: HashSet<&str> = content.split_ascii_whitespace().collect();
+        let dictionary = Dictionary::new(content.split_ascii_whitespace(), None);
 
         let filename = entry.path().to_str().expect(
             "Cannot turn filename of the file you moved into the Dictionaries folder into a string",
         );
 
-        entries.insert(filename, hash_set);
+        entries.insert(filename, dictionary);
     }
     entries
 });
@@ -0,0 +1,114 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// Log10 probabilities of English quadgrams, embedded into the binary at compile time.
+/// Each line of the table is a quadgram and how often it appeared, like `TION 12773`.
+pub static ENGLISH_QUADGRAMS: Lazy<QuadgramTable> =
+    Lazy::new(|| QuadgramTable::parse(include_str!("english_quadgrams.txt")));
+
+/// The fitness of typical English text, anything this fit or fitter looks just like English
+const ENGLISH_FITNESS: f64 = -4.0;
+
+/// A table of quadgram log probabilities used to score how much text looks like English.
+/// It is cheap enough to score every candidate, so the checkers and any cipher
+/// which needs a fitness function to climb can share it.
+pub struct QuadgramTable {
+    /// The log10 probability of every quadgram in the table
+    log_probabilities: HashMap<[u8; 4], f64>,
+    /// The log10 probability given to quadgrams not in the table
+    floor: f64,
+}
+
+impl QuadgramTable {
+    /// Builds the table from lines of `QUADGRAM count`
+    fn parse(table: &str) -> Self {
+        let counts: Vec<([u8; 4], f64)> = table
+            .lines()
+            .filter_map(|line| {
+                let (quadgram, count) = line.split_once(' ')?;
+                Some((quadgram.as_bytes().try_into().ok()?, count.parse().ok()?))
+            })
+            .collect();
+        let total: f64 = counts.iter().map(|(_, count)| count).sum();
+
+        QuadgramTable {
+            log_probabilities: counts
+                .into_iter()
+                .map(|(quadgram, count)| (quadgram, (count / total).log10()))
+                .collect(),
+            // Unseen quadgrams are rare but not impossible, so they get a small probability
+            floor: (0.01 / total).log10(),
+        }
+    }
+
+    /// Returns the average log10 probability of the quadgrams in the text.
+    /// Only letters are scored and case is ignored, so `"Hello, World"` scores `HELLOWORLD`.
+    /// The closer to 0 the more the text looks like English, random letters score around the floor.
+    /// Returns None if the text has fewer than 4 letters.
+    pub fn fitness(&self, text: &str) -> Option<f64> {
+        let letters: Vec<u8> = text
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|letter| letter.to_ascii_uppercase())
+            .collect();
+        if letters.len() < 4 {
+            return None;
+        }
+
+        let total: f64 = letters
+            .windows(4)
+            .map(|quadgram| {
+                self.log_probabilities
+                    .get(quadgram)
+                    .copied()
+                    .unwrap_or(self.floor)
+            })
+            .sum();
+        Some(total / (letters.len() - 3) as f64)
+    }
+
+    /// How much the text looks like English going by its [`fitness`](Self::fitness), from 0.0 to 1.0.
+    /// Random letters score 0.0 and typical English 1.0.
+    /// Texts with fewer than 4 letters, like hex or binary, score 0.0.
+    pub fn english_likeness(&self, text: &str) -> f64 {
+        self.fitness(text).map_or(0.0, |fitness| {
+            ((fitness - self.floor) / (ENGLISH_FITNESS - self.floor)).clamp(0.0, 1.0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ENGLISH_QUADGRAMS;
+
+    #[test]
+    fn english_is_more_english_like_than_rot13() {
+        let english = ENGLISH_QUADGRAMS.english_likeness("hello there general kenobi");
+        let rot13 = ENGLISH_QUADGRAMS.english_likeness("uryyb gurer trareny xrabov");
+        assert!(english > 0.8);
+        assert!(english > rot13);
+        assert_eq!(ENGLISH_QUADGRAMS.english_likeness("0110100001101001"), 0.0);
+    }
+
+    #[test]
+    fn english_is_fitter_than_gibberish() {
+        let english = ENGLISH_QUADGRAMS
+            .fitness("It was the best of times, it was the worst of times")
+            .unwrap();
+        let gibberish = ENGLISH_QUADGRAMS.fitness("xqzvkwpjhf lmnbvcxz").unwrap();
+        assert!(english > gibberish);
+    }
+
+    #[test]
+    fn fitness_ignores_case_and_punctuation() {
+        assert_eq!(
+            ENGLISH_QUADGRAMS.fitness("Hello, World!"),
+            ENGLISH_QUADGRAMS.fitness("helloworld")
+        );
+    }
+
+    #[test]
+    fn fitness_needs_four_letters() {
+        assert_eq!(ENGLISH_QUADGRAMS.fitness("abc 123"), None);
+    }
+}
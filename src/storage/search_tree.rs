@@ -0,0 +1,193 @@
+//! Every decoding the search explored, so `--export-tree` can show how it got to the plaintext.
+//! Each node is a text and each edge is the decoder which turned its parent into it.
+//! Nodes are only recorded when `Config.export_search_tree` is set, and each run records its own tree.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::DecoderResult;
+
+/// How many characters of each text the DOT labels show
+const DOT_LABEL_LENGTH: usize = 40;
+
+/// One text the search reached
+#[derive(Clone, Debug, Serialize)]
+pub struct SearchTreeNode {
+    /// Where this node is in the tree, the input is 0
+    pub id: usize,
+    /// The node this was decoded from, None for the input
+    pub parent: Option<usize>,
+    /// The decoder which turned the parent into this text, empty for the input
+    pub decoder: &'static str,
+    /// The texts the decoder gave
+    pub text: Vec<String>,
+    /// The checker which looked at the text last
+    pub checker_name: &'static str,
+    /// Whether the checker identified the text as plaintext
+    pub identified: bool,
+    /// How much the text looks like plaintext, from 0.0 to 1.0
+    pub plaintext_confidence: f32,
+}
+
+/// The nodes recorded so far
+#[derive(Default)]
+struct Nodes {
+    /// Every node, in the order they were reached
+    nodes: Vec<SearchTreeNode>,
+    /// The id of each node by [`node_key`], so we can find parents and
+    /// sweeps of iterative deepening which reach the same node again don't add it twice
+    ids: HashMap<u64, usize>,
+}
+
+/// The search tree of one run
+#[derive(Default)]
+pub struct SearchTree {
+    /// The nodes recorded so far, the searchers record them from many threads
+    nodes: Mutex<Nodes>,
+}
+
+/// A node is the decoders used to reach it and the text they gave.
+/// The text a step decoded is its parent's text, so we can work out the key of the parent too.
+fn node_key<'a>(decoders: impl Iterator<Item = &'a str>, text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for decoder in decoders {
+        decoder.hash(&mut hasher);
+    }
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl SearchTree {
+    /// Adds the decoding to the search tree, under the decoding it came from
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn record(&self, decoding: &DecoderResult) {
+        let decoders = || decoding.path.iter().map(|step| step.decoder);
+        let key = node_key(decoders(), &decoding.text[0]);
+        let mut tree = self
+            .nodes
+            .lock()
+            .expect("search tree lock should not be poisoned");
+        if tree.ids.contains_key(&key) {
+            return;
+        }
+
+        let last_step = decoding.path.last();
+        let parent = last_step.and_then(|step| {
+            let parent_key = node_key(
+                decoders().take(decoding.path.len() - 1),
+                &step.encrypted_text,
+            );
+            tree.ids.get(&parent_key).copied()
+        });
+        let id = tree.nodes.len();
+        tree.ids.insert(key, id);
+        tree.nodes.push(SearchTreeNode {
+            id,
+            parent,
+            decoder: last_step.map_or("", |step| step.decoder),
+            text: decoding.text.clone(),
+            checker_name: last_step.map_or("", |step| step.checker_name),
+            identified: last_step.is_some_and(|step| step.success),
+            plaintext_confidence: crate::searchers::plaintext_confidence(&decoding.text[0]),
+        });
+    }
+
+    /// Returns every node of the search tree, in the order the search reached them
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn nodes(&self) -> Vec<SearchTreeNode> {
+        self.nodes
+            .lock()
+            .expect("search tree lock should not be poisoned")
+            .nodes
+            .clone()
+    }
+}
+
+/// Draws the search tree as a Graphviz DOT graph, with the plaintext in green.
+/// Render it with `dot -Tsvg tree.dot -o tree.svg`
+pub fn search_tree_as_dot(nodes: &[SearchTreeNode]) -> String {
+    let mut dot = String::from("digraph search_tree {\n    node [shape=box];\n");
+    for node in nodes {
+        let mut text: String = node.text[0].chars().take(DOT_LABEL_LENGTH).collect();
+        if node.text[0].chars().count() > DOT_LABEL_LENGTH {
+            text.push('…');
+        }
+        // Debug formatting escapes quotes and newlines the same way DOT does
+        let label = format!("{text}\n{:.2}", node.plaintext_confidence);
+        let colour = if node.identified { ", color=green" } else { "" };
+        writeln!(dot, "    {} [label={label:?}{colour}];", node.id).unwrap();
+        if let Some(parent) = node.parent {
+            writeln!(
+                dot,
+                "    {parent} -> {} [label={:?}];",
+                node.id, node.decoder
+            )
+            .unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: usize, parent: Option<usize>, decoder: &'static str, text: &str) -> SearchTreeNode {
+        SearchTreeNode {
+            id,
+            parent,
+            decoder,
+            text: vec![text.to_string()],
+            checker_name: "",
+            identified: false,
+            plaintext_confidence: 0.5,
+        }
+    }
+
+    #[test]
+    fn decodings_are_recorded_under_the_decoding_they_came_from() {
+        let tree = SearchTree::default();
+        let input = DecoderResult {
+            text: vec!["aGVsbG8=".to_string()],
+            path: vec![],
+            confidence: 0.0,
+        };
+        let mut decoded = DecoderResult::_new("hello");
+        decoded.path = vec![std::sync::Arc::new(
+            crate::decoders::crack_results::CrackResult::new(
+                &crate::decoders::interface::Decoder::default(),
+                "aGVsbG8=".to_string(),
+            ),
+        )];
+        tree.record(&input);
+        tree.record(&decoded);
+        // Iterative deepening reaches the same decoding again in every sweep
+        tree.record(&decoded);
+
+        let nodes = tree.nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].parent, None);
+        assert_eq!(nodes[1].parent, Some(0));
+    }
+
+    #[test]
+    fn dot_has_a_node_for_each_text_and_an_edge_for_each_decoder() {
+        let nodes = [
+            node(0, None, "", "aGVsbG8="),
+            node(1, Some(0), "Base64", "say \"hello\""),
+        ];
+        let dot = search_tree_as_dot(&nodes);
+        assert!(dot.starts_with("digraph search_tree {"));
+        assert!(dot.contains(r#"0 [label="aGVsbG8=\n0.50"];"#));
+        assert!(dot.contains(r#"1 [label="say \"hello\"\n0.50"];"#));
+        assert!(dot.contains(r#"0 -> 1 [label="Base64"];"#));
+    }
+}
@@ -0,0 +1,136 @@
+//! In top results mode the search does not stop at the first plaintext.
+//! Every plaintext each run finds is stored here, and shown to the user at the end.
+//! A plaintext found by more than one path is only stored once, with its shortest path,
+//! and they are ranked so the one most likely to be right comes first.
+
+use std::sync::Mutex;
+
+use crate::DecoderResult;
+
+/// The plaintexts found by one run
+#[derive(Default)]
+pub struct TopResults {
+    /// Every plaintext found so far, in the order they were found
+    found: Mutex<Vec<DecoderResult>>,
+}
+
+impl TopResults {
+    /// Stores a plaintext found by the search, returning false if it was already found by another path.
+    /// If this path is shorter it replaces the one we had.
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn add(&self, result: &DecoderResult) -> bool {
+        let mut found = self
+            .found
+            .lock()
+            .expect("top results lock should not be poisoned");
+        match found
+            .iter_mut()
+            .find(|found| found.text[0] == result.text[0])
+        {
+            Some(found) => {
+                if result.path.len() < found.path.len() {
+                    *found = result.clone();
+                }
+                false
+            }
+            None => {
+                found.push(result.clone());
+                true
+            }
+        }
+    }
+
+    /// Returns every plaintext found so far, the ones the checkers are most confident in first.
+    /// Plaintexts with the same confidence are ranked by the fewest decoders used,
+    /// then by which was found first.
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn results(&self) -> Vec<DecoderResult> {
+        let mut results = self
+            .found
+            .lock()
+            .expect("top results lock should not be poisoned")
+            .clone();
+        rank_by_confidence(&mut results);
+        results
+    }
+
+    /// Returns how many different plaintexts have been found so far
+    /// # Panics
+    /// Panics if another thread panicked while holding the lock
+    pub fn count(&self) -> usize {
+        self.found
+            .lock()
+            .expect("top results lock should not be poisoned")
+            .len()
+    }
+}
+
+/// Sorts the plaintexts so the ones the checkers are most confident in come first,
+/// and of those the ones using the fewest decoders.
+/// The sort is stable, so ties keep the order they were found in.
+fn rank_by_confidence(results: &mut [DecoderResult]) {
+    results.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(a.path.len().cmp(&b.path.len()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_results_are_ranked_by_confidence() {
+        let mut results: Vec<DecoderResult> =
+            [("unsure", 0.5), ("sure", 0.9), ("also unsure", 0.5)]
+                .into_iter()
+                .map(|(text, confidence)| {
+                    let mut result = DecoderResult::_new(text);
+                    result.confidence = confidence;
+                    result
+                })
+                .collect();
+        rank_by_confidence(&mut results);
+        let texts: Vec<&str> = results.iter().map(|r| r.text[0].as_str()).collect();
+        assert_eq!(texts, ["sure", "unsure", "also unsure"]);
+    }
+
+    #[test]
+    fn top_results_are_stored_in_order() {
+        let top_results = TopResults::default();
+        top_results.add(&DecoderResult::_new("first"));
+        top_results.add(&DecoderResult::_new("second"));
+        let texts: Vec<String> = top_results
+            .results()
+            .into_iter()
+            .map(|r| r.text[0].clone())
+            .collect();
+        assert_eq!(texts, ["first", "second"]);
+    }
+
+    #[test]
+    fn top_results_are_deduplicated() {
+        let mut long_path = DecoderResult::_new("hello there general kenobi");
+        long_path.path.push(long_path.path[0].clone());
+        let top_results = TopResults::default();
+        assert!(top_results.add(&long_path));
+        assert!(!top_results.add(&DecoderResult::_new("hello there general kenobi")));
+
+        let results = top_results.results();
+        assert_eq!(top_results.count(), 1);
+        // The shorter path replaces the longer one
+        assert_eq!(results[0].path.len(), 1);
+    }
+
+    #[test]
+    fn equally_confident_top_results_are_ranked_by_the_fewest_decoders() {
+        let mut long_path = DecoderResult::_new("long");
+        long_path.path.push(long_path.path[0].clone());
+        let mut results = vec![long_path, DecoderResult::_new("short")];
+        rank_by_confidence(&mut results);
+        assert_eq!(results[0].text[0], "short");
+    }
+}
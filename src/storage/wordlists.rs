@@ -0,0 +1,244 @@
+use aho_corasick::AhoCorasick;
+use once_cell::sync::OnceCell;
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::dictionary::Dictionary;
+use crate::config::WordlistMatching;
+
+/// A list of words the user expects the plaintext to be, like a list of passwords or usernames.
+/// Several can be loaded at once, and the Wordlist Checker reports which one matched.
+pub struct Wordlist {
+    /// What the wordlist is called, this is the file name without its extension when loaded from a file
+    pub name: String,
+    /// Disabled wordlists stay loaded but the Wordlist Checker ignores them
+    pub enabled: bool,
+    /// Every word in the list
+    words: Dictionary,
+    /// Every word lowercased without whitespace, only built if normalised matching is used
+    normalised_words: OnceCell<HashSet<String>>,
+    /// Finds any of the words inside a text, only built if substring matching is used.
+    /// The words are kept in the same order as the automaton's patterns.
+    substring_automaton: OnceCell<(AhoCorasick, Vec<String>)>,
+}
+
+impl Wordlist {
+    /// Creates an enabled wordlist from its words
+    pub fn new<'a, I>(name: impl Into<String>, words: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+        I::IntoIter: Clone,
+    {
+        Wordlist::with_dictionary(name, Dictionary::new(words.into_iter(), None))
+    }
+
+    /// Creates an enabled wordlist from a dictionary of its words
+    fn with_dictionary(name: impl Into<String>, words: Dictionary) -> Self {
+        Wordlist {
+            name: name.into(),
+            enabled: true,
+            words,
+            normalised_words: OnceCell::new(),
+            substring_automaton: OnceCell::new(),
+        }
+    }
+
+    /// Loads a wordlist with one word per line, skipping blank lines.
+    /// The wordlist is named after the file, so `rockyou.txt` is called `rockyou`.
+    /// If holding every word would take more than `memory_limit` bytes the wordlist
+    /// becomes a Bloom filter, which can only be used for exact matching.
+    /// # Errors
+    /// Returns an error if the file cannot be read
+    pub fn from_file(path: impl AsRef<Path>, memory_limit: Option<usize>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let name = path.file_stem().map_or_else(
+            || path.display().to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+        let words = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        Ok(Wordlist::with_dictionary(
+            name,
+            Dictionary::new(words, memory_limit),
+        ))
+    }
+
+    /// Is the text exactly one of the words in the list?
+    pub fn contains(&self, text: &str) -> bool {
+        self.words.contains(text)
+    }
+
+    /// Returns the word the text matches, if it matches one.
+    /// With normalised or fuzzy matching this is the normalised word.
+    pub fn find_match(&self, text: &str, matching: WordlistMatching) -> Option<String> {
+        match matching {
+            WordlistMatching::Exact => self.words.contains(text).then(|| text.to_string()),
+            WordlistMatching::Normalised => self.normalised_words()?.get(&normalise(text)).cloned(),
+            WordlistMatching::Fuzzy { max_distance } => {
+                let text = normalise(text);
+                let words = self.normalised_words()?;
+                if let Some(word) = words.get(&text) {
+                    return Some(word.clone());
+                }
+                let text: Vec<char> = text.chars().collect();
+                words
+                    .iter()
+                    .find(|word| within_distance(&text, word, max_distance))
+                    .cloned()
+            }
+            WordlistMatching::Substring => {
+                let (automaton, words) = self.substring_automaton()?;
+                let found = automaton.find(text)?;
+                Some(words[found.pattern().as_usize()].clone())
+            }
+        }
+    }
+
+    /// The Aho-Corasick automaton of the words, built the first time it is needed.
+    /// Building it takes a while for big wordlists, but then every check is linear in the text.
+    /// # Panics
+    /// Panics if the automaton cannot be built, which only happens if the wordlist is too big to fit in memory
+    /// Returns None if the wordlist is a Bloom filter, as it can't list its words.
+    fn substring_automaton(&self) -> Option<&(AhoCorasick, Vec<String>)> {
+        let words = self.words.words()?;
+        Some(self.substring_automaton.get_or_init(|| {
+            // An empty word would be in every text
+            let words: Vec<String> = words
+                .filter(|word| !word.is_empty())
+                .map(str::to_string)
+                .collect();
+            let automaton = AhoCorasick::new(&words).unwrap_or_else(|error| {
+                panic!("Failed to build the wordlist {}: {error}", self.name)
+            });
+            (automaton, words)
+        }))
+    }
+
+    /// The words lowercased without whitespace, built the first time they are needed.
+    /// Returns None if the wordlist is a Bloom filter, as it can't list its words.
+    fn normalised_words(&self) -> Option<&HashSet<String>> {
+        let words = self.words.words()?;
+        Some(
+            self.normalised_words
+                .get_or_init(|| words.map(normalise).collect()),
+        )
+    }
+
+    /// The chance of matching a word which isn't in the list.
+    /// This is 0 unless the wordlist went over its memory limit and became a Bloom filter.
+    pub fn false_positive_rate(&self) -> f64 {
+        self.words.false_positive_rate()
+    }
+
+    /// How many words are in the list
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Is the list empty?
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Lowercases the text and removes its whitespace
+fn normalise(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Is the Levenshtein distance between the text and the word at most `max_distance`?
+/// Words whose length is too different are skipped without computing the distance.
+fn within_distance(text: &[char], word: &str, max_distance: usize) -> bool {
+    let word: Vec<char> = word.chars().collect();
+    if text.len().abs_diff(word.len()) > max_distance {
+        return false;
+    }
+    // The classic dynamic programming algorithm, keeping one row at a time
+    let mut previous: Vec<usize> = (0..=word.len()).collect();
+    for (i, &text_char) in text.iter().enumerate() {
+        let mut current = vec![i + 1; word.len() + 1];
+        for (j, &word_char) in word.iter().enumerate() {
+            let substitution = previous[j] + usize::from(text_char != word_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        // Every later row is at least the smallest value in this one
+        if current.iter().min().is_some_and(|&min| min > max_distance) {
+            return false;
+        }
+        previous = current;
+    }
+    previous[word.len()] <= max_distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Wordlist;
+    use crate::config::WordlistMatching;
+
+    #[test]
+    fn from_file_names_the_wordlist_after_the_file() {
+        let path = std::env::temp_dir().join("ares_test_passwords.txt");
+        std::fs::write(&path, "hunter2\n\n  letmein  \n").unwrap();
+        let wordlist = Wordlist::from_file(&path, None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(wordlist.name, "ares_test_passwords");
+        assert_eq!(wordlist.len(), 2);
+        assert!(wordlist.contains("letmein"));
+        assert!(wordlist.enabled);
+    }
+
+    #[test]
+    fn wordlists_over_their_memory_limit_only_match_exactly() {
+        let path = std::env::temp_dir().join("ares_test_big_wordlist.txt");
+        let words: Vec<String> = (0..1000).map(|i| format!("password{i}")).collect();
+        std::fs::write(&path, words.join("\n")).unwrap();
+        let wordlist = Wordlist::from_file(&path, Some(1024)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(wordlist.contains("password999"));
+        assert!(wordlist
+            .find_match("Password999", WordlistMatching::Normalised)
+            .is_none());
+    }
+
+    #[test]
+    fn normalised_matching_ignores_case_and_whitespace() {
+        let wordlist = Wordlist::new("passwords", ["Hunter2"]);
+        assert_eq!(
+            wordlist.find_match("hunter 2", WordlistMatching::Normalised),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(
+            wordlist.find_match("hunter 2", WordlistMatching::Exact),
+            None
+        );
+    }
+
+    #[test]
+    fn substring_matching_finds_words_inside_the_text() {
+        let wordlist = Wordlist::new("passwords", ["hunter2", "letmein"]);
+        assert_eq!(
+            wordlist.find_match("the password is hunter2!", WordlistMatching::Substring),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(
+            wordlist.find_match("no password here", WordlistMatching::Substring),
+            None
+        );
+    }
+
+    #[test]
+    fn fuzzy_matching_allows_near_misses() {
+        let wordlist = Wordlist::new("phrases", ["attack at dawn"]);
+        let fuzzy = WordlistMatching::Fuzzy { max_distance: 1 };
+        assert!(wordlist.find_match("attack at dawo", fuzzy).is_some());
+        assert!(wordlist.find_match("attack at dxwo", fuzzy).is_none());
+    }
+}
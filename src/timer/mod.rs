@@ -1,42 +1,105 @@
-use crossbeam::channel::{bounded, Receiver};
+use crossbeam::channel::{bounded, never, Receiver};
 use std::sync::atomic::Ordering::Relaxed;
 use std::{
-    sync::atomic::AtomicBool,
+    sync::atomic::{AtomicBool, AtomicUsize},
     thread::{self, sleep},
     time::Duration,
 };
 
 use crate::cli_pretty_printing::countdown_until_program_ends;
+use crate::config::Config;
+use std::sync::Arc;
 
-/// Indicate whether timer is paused
-static PAUSED: AtomicBool = AtomicBool::new(false);
+/// How often a paused timer checks if it can count again
+const PAUSED_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// The shortest timeout `Config.auto_timeout` picks, in seconds
+const AUTO_TIMEOUT_MIN: u32 = 3;
+/// The longest timeout `Config.auto_timeout` picks, in seconds
+const AUTO_TIMEOUT_MAX: u32 = 300;
+/// `Config.auto_timeout` gives the search a second for every this many bytes of input
+const AUTO_TIMEOUT_BYTES_PER_SECOND: usize = 256;
+/// `Config.auto_timeout` gives the search a second for every this many decoders it can use
+const AUTO_TIMEOUT_DECODERS_PER_SECOND: usize = 10;
 
-/// Start the timer with duration in seconds
-pub fn start(duration: u32) -> Receiver<()> {
+/// Start the timer with duration in seconds.
+/// A duration of 0 never runs out, so the search carries on until it is exhausted or cancelled.
+/// The timer doesn't count while `paused` isn't 0, and gives up once `stop` is set.
+/// Both belong to the run the timer is for, so runs in the same process have their own timers.
+/// Browsers can't start the thread the timer counts on, so on wasm32 it never runs out either.
+pub fn start(
+    duration: u32,
+    config: Arc<Config>,
+    paused: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
+) -> Receiver<()> {
+    if duration == 0 || cfg!(target_arch = "wasm32") {
+        return never();
+    }
     let (sender, recv) = bounded(1);
     thread::spawn(move || {
         let mut time_spent = 0;
 
         while time_spent < duration {
-            if !PAUSED.load(Relaxed) {
-                sleep(Duration::from_secs(1));
-                time_spent += 1;
-                // Some pretty printing support
-                countdown_until_program_ends(time_spent, duration);
+            if stop.load(Relaxed) {
+                return;
             }
+            if paused.load(Relaxed) > 0 {
+                // Someone is answering the human checker, so we check back in a moment
+                sleep(PAUSED_CHECK_INTERVAL);
+                continue;
+            }
+            sleep(Duration::from_secs(1));
+            time_spent += 1;
+            // Some pretty printing support
+            countdown_until_program_ends(time_spent, duration, &config);
         }
-        sender.send(()).expect("Timer should send succesfully");
+        // Nobody is listening if the search finished in the last second
+        sender.send(()).ok();
     });
 
     recv
 }
 
-/// Pause timer
-pub fn pause() {
-    PAUSED.store(true, Relaxed);
+/// Picks a timeout in seconds for `Config.auto_timeout`.
+/// Each decoder has to decode the whole input and everything it decodes into,
+/// so the timeout grows with the length of the input and the number of decoders.
+/// Short texts fail fast and long multi layered ones get a fair chance.
+pub fn auto_timeout(input_length: usize, decoders: usize) -> u32 {
+    let seconds = AUTO_TIMEOUT_MIN as usize
+        + input_length / AUTO_TIMEOUT_BYTES_PER_SECOND
+        + decoders / AUTO_TIMEOUT_DECODERS_PER_SECOND;
+    u32::try_from(seconds)
+        .unwrap_or(AUTO_TIMEOUT_MAX)
+        .min(AUTO_TIMEOUT_MAX)
 }
 
-/// Resume timer
-pub fn resume() {
-    PAUSED.store(false, Relaxed);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paused_timers_do_not_run_out() {
+        let paused = Arc::new(AtomicUsize::new(1));
+        let stop = Arc::new(AtomicBool::new(false));
+        let timer = start(1, Arc::new(Config::default()), paused.clone(), stop);
+        assert!(timer.recv_timeout(Duration::from_millis(1500)).is_err());
+
+        paused.store(0, Relaxed);
+        assert!(timer.recv_timeout(Duration::from_secs(3)).is_ok());
+    }
+
+    #[test]
+    fn stopped_timers_never_run_out() {
+        let stop = Arc::new(AtomicBool::new(true));
+        let timer = start(1, Arc::new(Config::default()), Arc::default(), stop);
+        assert!(timer.recv_timeout(Duration::from_secs(2)).is_err());
+    }
+
+    #[test]
+    fn auto_timeout_grows_with_the_input_and_is_capped() {
+        assert_eq!(auto_timeout(10, 0), AUTO_TIMEOUT_MIN);
+        assert!(auto_timeout(10_000, 40) > auto_timeout(10, 40));
+        assert!(auto_timeout(10, 40) > auto_timeout(10, 0));
+        assert_eq!(auto_timeout(usize::MAX, 40), AUTO_TIMEOUT_MAX);
+    }
 }
@@ -0,0 +1,96 @@
+//! Runs Ares in a browser, built for `wasm32-unknown-unknown` with the `wasm` feature.
+//! JavaScript calls `crack(text, options)`, where the options are JSON like `{"only": ["base64"]}`.
+//! They take `only`, `exclude` and `regex`, which work like the CLI arguments, and `max_depth`.
+//! It returns the `DecoderResult` as JSON, like `POST /crack` in `ares serve`.
+//!
+//! Browsers can't start threads, so the search runs on the thread which called `crack`
+//! and the timer never runs out. `max_depth` stops searches for texts Ares can't decode.
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::config::Config;
+use crate::perform_cracking;
+
+/// How many decoders deep the search goes when the options don't say, as nothing times it out
+const DEFAULT_MAX_DEPTH: u32 = 5;
+
+/// The options JavaScript passes to `crack`
+#[derive(Deserialize, Default)]
+struct CrackOptions {
+    /// Only use these decoders, like `--only`
+    #[serde(default)]
+    only: Vec<String>,
+    /// Never use these decoders, like `--exclude`
+    #[serde(default)]
+    exclude: Vec<String>,
+    /// Look for these regexes or cribs, like `--regex`
+    #[serde(default)]
+    regex: Vec<String>,
+    /// The most decoders the search chains together, like `--max-depth`
+    #[serde(default)]
+    max_depth: Option<u32>,
+}
+
+impl CrackOptions {
+    /// The config to crack with.
+    /// Nobody can answer the human checker in a terminal, and nothing is printed.
+    fn config(&self) -> Config {
+        Config {
+            timeout: 0,
+            api_mode: true,
+            human_checker_on: false,
+            enabled_decoders: self.only.clone(),
+            disabled_decoders: self.exclude.clone(),
+            regex: self.regex.clone(),
+            max_depth: Some(self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Cracks the text and returns the `DecoderResult` as JSON.
+/// `options` is JSON, an empty string uses the defaults.
+/// # Errors
+/// Throws if the options are not valid JSON, the config is invalid or the text could not be decoded
+#[wasm_bindgen]
+pub fn crack(text: &str, options: &str) -> Result<String, JsError> {
+    let options: CrackOptions = if options.trim().is_empty() {
+        CrackOptions::default()
+    } else {
+        serde_json::from_str(options)
+            .map_err(|error| JsError::new(&format!("The options are not valid: {error}")))?
+    };
+    let outcome = perform_cracking(text, options.config())
+        .map_err(|error| JsError::new(&error.to_string()))?;
+    let result = outcome
+        .into_plaintext()
+        .ok_or_else(|| JsError::new("Ares could not decode the text"))?;
+    serde_json::to_string(&result).map_err(|error| JsError::new(&error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_are_all_optional() {
+        let options: CrackOptions = serde_json::from_str("{}").unwrap();
+        let config = options.config();
+        assert_eq!(config.timeout, 0);
+        assert_eq!(config.max_depth, Some(DEFAULT_MAX_DEPTH));
+        assert!(config.enabled_decoders.is_empty());
+        assert!(!config.human_checker_on);
+    }
+
+    #[test]
+    fn options_work_like_the_cli_arguments() {
+        let options: CrackOptions =
+            serde_json::from_str(r#"{"only": ["base64"], "regex": ["flag"], "max_depth": 2}"#)
+                .unwrap();
+        let config = options.config();
+        assert_eq!(config.enabled_decoders, vec!["base64".to_string()]);
+        assert_eq!(config.regex, vec!["flag".to_string()]);
+        assert_eq!(config.max_depth, Some(2));
+    }
+}
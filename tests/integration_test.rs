@@ -1,4 +1,6 @@
 use ares::cli::read_and_parse_file;
+#[cfg(feature = "barcode")]
+use ares::cli::{read_and_parse_file_with_strategy, BinaryFileStrategy};
 use ares::config::Config;
 use ares::perform_cracking;
 
@@ -9,7 +11,7 @@ fn test_it_works() {
     // It will panic if it doesn't work!
     // Plaintext is `Mutley, you snickering, floppy eared hound. When courage is needed, you’re never around. Those m...	`
     let config = Config::default();
-    perform_cracking("TXV0bGV5LCB5b3Ugc25pY2tlcmluZywgZmxvcHB5IGVhcmVkIGhvdW5kLiBXaGVuIGNvdXJhZ2UgaXMgbmVlZGVkLCB5b3XigJlyZSBuZXZlciBhcm91bmQuIFRob3NlIG1lZGFscyB5b3Ugd2VhciBvbiB5b3VyIG1vdGgtZWF0ZW4gY2hlc3Qgc2hvdWxkIGJlIHRoZXJlIGZvciBidW5nbGluZyBhdCB3aGljaCB5b3UgYXJlIGJlc3QuIFNvLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLiBIb3d3d3chIE5hYiBoaW0sIGphYiBoaW0sIHRhYiBoaW0sIGdyYWIgaGltLCBzdG9wIHRoYXQgcGlnZW9uIG5vdy4g", config);
+    assert!(perform_cracking("TXV0bGV5LCB5b3Ugc25pY2tlcmluZywgZmxvcHB5IGVhcmVkIGhvdW5kLiBXaGVuIGNvdXJhZ2UgaXMgbmVlZGVkLCB5b3XigJlyZSBuZXZlciBhcm91bmQuIFRob3NlIG1lZGFscyB5b3Ugd2VhciBvbiB5b3VyIG1vdGgtZWF0ZW4gY2hlc3Qgc2hvdWxkIGJlIHRoZXJlIGZvciBidW5nbGluZyBhdCB3aGljaCB5b3UgYXJlIGJlc3QuIFNvLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLCBzdG9wIHRoYXQgcGlnZW9uLiBIb3d3d3chIE5hYiBoaW0sIGphYiBoaW0sIHRhYiBoaW0sIGdyYWIgaGltLCBzdG9wIHRoYXQgcGlnZW9uIG5vdy4g", config).is_ok());
     assert_eq!(true, true);
 }
 
@@ -17,7 +19,7 @@ fn test_it_works() {
 fn test_no_panic_if_empty_string() {
     // It will panic if it doesn't work!
     let config = Config::default();
-    perform_cracking("", config);
+    assert!(perform_cracking("", config).is_ok());
     assert_eq!(true, true);
 }
 
@@ -26,8 +28,10 @@ fn test_program_parses_files_and_cracks() {
     // It should be able to open and crack this file
     let file_path = "tests/test_fixtures/base64_3_times_with_no_new_line";
     let config = Config::default();
-    let to_crack = read_and_parse_file(file_path.to_string());
-    let result = perform_cracking(&to_crack, config);
+    let to_crack = read_and_parse_file(file_path.to_string()).unwrap();
+    let result = perform_cracking(&to_crack, config)
+        .unwrap()
+        .into_plaintext();
     assert_eq!(true, true);
     assert!(result.unwrap().text[0] == "Multiple base64 encodings");
 }
@@ -37,8 +41,35 @@ fn test_program_parses_files_with_new_line_and_cracks() {
     // It should be able to open and crack this file
     let file_path = "tests/test_fixtures/rot13_base64_hex_with_newline";
     let config = Config::default();
-    let to_crack = read_and_parse_file(file_path.to_string());
-    let result = perform_cracking(&to_crack, config);
+    let to_crack = read_and_parse_file(file_path.to_string()).unwrap();
+    let result = perform_cracking(&to_crack, config)
+        .unwrap()
+        .into_plaintext();
     assert_eq!(true, true);
     assert!(result.unwrap().text[0] == "This is a test!");
 }
+
+#[cfg(feature = "barcode")]
+#[test]
+fn test_program_reads_barcodes_in_images_and_cracks() {
+    // The QR code in this image holds the text to crack
+    let file_path = "tests/test_fixtures/qr_code_of_base64.png";
+    let config = Config::default();
+    let to_crack = read_and_parse_file(file_path.to_string()).unwrap();
+    let result = perform_cracking(&to_crack, config)
+        .unwrap()
+        .into_plaintext();
+    assert!(result.unwrap().text[0] == "hello there general");
+}
+
+#[cfg(feature = "barcode")]
+#[test]
+fn test_program_reads_images_without_barcodes_as_binary_files() {
+    // There is no barcode in this image, so it is hex encoded like any other binary file
+    let file_path = "tests/test_fixtures/image_with_no_barcode.png";
+    let to_crack =
+        read_and_parse_file_with_strategy(file_path.to_string(), BinaryFileStrategy::Hex).unwrap();
+    let bytes = std::fs::read(file_path).unwrap();
+    assert_eq!(to_crack.len(), bytes.len() * 2);
+    assert!(to_crack.starts_with("89504e47"));
+}
@@ -0,0 +1,57 @@
+use ares::config::Config;
+use ares::perform_cracking;
+
+/// Texts encoded with several layers, like the ones people give Ares, and their plaintexts.
+/// Run these after changing the searcher, filtration system or checkers to make sure
+/// a faster Ares still cracks what it used to.
+const CORPUS: &[(&str, &str, &str)] = &[
+    (
+        "base64 twice",
+        "VFdWbGRDQnRaU0JoZENCMGFHVWdiMnhrSUc5aGF5QjBjbVZsSUdGMElHMXBaRzVwWjJoMA==",
+        "Meet me at the old oak tree at midnight",
+    ),
+    (
+        "hexadecimal of base64",
+        "5647686c4948463161574e7249474a796233647549475a76654342716457317763794276646d56794948526f5a53427359587035494752765a773d3d",
+        "The quick brown fox jumps over the lazy dog",
+    ),
+    (
+        "base32 of base64",
+        "KRWVMMS2LBEWOWRSHF2WE3KFM5NDE3BSLJJUENLCGNKWOZCYIFZUSRZVNRSG2VTZJFDWI5TCNU2WQSKHPBWGIQ2CGVRDGVLHLJDTSM3CM46T2===",
+        "Never gonna give you up, never gonna let you down",
+    ),
+    (
+        "reversed base64",
+        "==gb3FGZgQXYgMHbsF2dgUGb0NXYjBSZoRHIrNWY0RXQ",
+        "Attack the castle walls at dawn",
+    ),
+    (
+        "base64 of rot13",
+        "R3VyIGNuZmZqYmVxIHNiZSBndXIgZnJlaXJlIHZmIGpldmdncmEgYmEgZ3VyIGp1dmdyb2JuZXE=",
+        "The password for the server is written on the whiteboard",
+    ),
+    (
+        "url encoded base64",
+        "UGxlYXNlIHJlbWVtYmVyIHRvIGJyaW5nIHRoZSBkb2N1bWVudHMgdG9tb3Jyb3c%3D",
+        "Please remember to bring the documents tomorrow",
+    ),
+    (
+        "base32 of atbash",
+        "IR3CAZDSN5XSA3TWOZTSA6TUPJZG2IDSNUQGO43WEBTGQZT2N4QGW332PB3A====",
+        "We will meet again in the usual place",
+    ),
+];
+
+#[test]
+fn regression_corpus_is_cracked() {
+    for (layers, encoded, plaintext) in CORPUS {
+        let result = perform_cracking(encoded, Config::default())
+            .unwrap()
+            .into_plaintext();
+        assert_eq!(
+            result.map(|result| result.text[0].clone()).as_deref(),
+            Some(*plaintext),
+            "Ares should crack {layers}"
+        );
+    }
+}